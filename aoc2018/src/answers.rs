@@ -0,0 +1,105 @@
+//! Regression check of the answers to the checked-in puzzle inputs
+//!
+//! The expected answers live in `answers.txt` at the root of the workspace,
+//! in the same format printed by `aoc2018 run --all`, so after a change
+//! that is meant to alter an answer the file is regenerated with
+//!
+//! ```text
+//! cargo run --release -p aoc2018 -- run --all > answers.txt
+//! ```
+
+use std::collections::BTreeMap;
+
+use common::Part;
+
+const ANSWERS: &str = include_str!("../../answers.txt");
+
+// `day07 part2: 877`, a label with the answer on the same line, or just the
+// label when the answer spans the following lines
+fn parse_label(line: &str) -> Option<((usize, Part), &str)> {
+    let (label, answer) = line.split_at(line.find(':')?);
+    let mut words = label.split(' ');
+    let day = words.next()?.strip_prefix("day")?.parse().ok()?;
+    let part = match words.next()? {
+        "part1" => Part::One,
+        "part2" => Part::Two,
+        _ => return None,
+    };
+    Some(((day, part), answer[1..].trim()))
+}
+
+/// Answers of the file by day and part, multi-line answers are made of
+/// the lines up to the next label
+fn parse_answers(text: &str) -> Result<BTreeMap<(usize, Part), String>, String> {
+    let mut answers = BTreeMap::new();
+    let mut last = None;
+    for (idx, line) in text.lines().enumerate() {
+        if let Some((key, answer)) = parse_label(line) {
+            if answers.insert(key, answer.to_string()).is_some() {
+                return Err(format!("line {}: duplicated answer", idx + 1));
+            }
+            last = Some(key);
+            continue;
+        }
+
+        let answer = last
+            .and_then(|key| answers.get_mut(&key))
+            .ok_or_else(|| format!("line {}: answer without a label", idx + 1))?;
+        if !answer.is_empty() {
+            answer.push('\n');
+        }
+        answer.push_str(line);
+    }
+    Ok(answers)
+}
+
+#[cfg(test)]
+mod tests {
+    use common::Part;
+
+    use super::{parse_answers, ANSWERS};
+    use crate::days::DAYS;
+    use crate::default_input;
+
+    #[test]
+    fn test_parse_answers() {
+        let answers = parse_answers("day01 part1: 3\nday10 part1:\n#..#\n####\n").unwrap();
+        assert_eq!(answers[&(1, Part::One)], "3");
+        assert_eq!(answers[&(10, Part::One)], "#..#\n####");
+
+        assert!(parse_answers("3\nday01 part1: 3").is_err());
+        assert!(parse_answers("day01 part1: 3\nday01 part1: 3").is_err());
+    }
+
+    #[test]
+    fn test_answers() {
+        let expected = parse_answers(ANSWERS).unwrap();
+
+        let parts = [Part::One, Part::Two];
+        let mut mismatches = Vec::new();
+        for day in 1..=DAYS.len() {
+            let input = std::fs::read_to_string(default_input(day)).unwrap();
+            let answers = DAYS[day - 1].solver().solve(&input, &parts).unwrap();
+            for solved in answers {
+                let (part, answer) = (solved.part, solved.answer.trim_end());
+                match expected.get(&(day, part)) {
+                    Some(expected) if expected == answer => (),
+                    Some(expected) => mismatches.push(format!(
+                        "day{:02} part{}: expected {}, got {}",
+                        day,
+                        part.number(),
+                        expected,
+                        answer
+                    )),
+                    None => mismatches.push(format!(
+                        "day{:02} part{}: no stored answer",
+                        day,
+                        part.number()
+                    )),
+                }
+            }
+        }
+
+        assert!(mismatches.is_empty(), "\n{}", mismatches.join("\n"));
+    }
+}
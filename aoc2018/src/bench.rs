@@ -0,0 +1,338 @@
+//! Timing of the phases of every day on its checked-in puzzle input
+//!
+//! The parsing of the input and both parts are run a few times to warm up,
+//! then timed over a number of runs. The medians can be saved as a baseline
+//! and later runs compared against it, flagging the phases slower than the
+//! baseline by more than a threshold. Only timings of release builds are
+//! worth comparing:
+//!
+//! ```text
+//! cargo run --release -p aoc2018 -- bench --all --save bench.txt
+//! cargo run --release -p aoc2018 -- bench 6 --baseline bench.txt
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use common::ParseError;
+
+use crate::days::{Timings, DAYS};
+use crate::{default_input, read_input, Failure};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Options {
+    pub runs: usize,
+    pub warmup: usize,
+    /// File the medians are saved to, updating the days already there
+    pub save: Option<PathBuf>,
+    pub baseline: Option<PathBuf>,
+    /// Slowdown over the baseline flagged as a regression, in percent
+    pub threshold: f64,
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options {
+            runs: 5,
+            warmup: 1,
+            save: None,
+            baseline: None,
+            threshold: 10.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Phase {
+    Parse,
+    Part1,
+    Part2,
+}
+
+const PHASES: [Phase; 3] = [Phase::Parse, Phase::Part1, Phase::Part2];
+
+impl Phase {
+    fn of(self, timings: &Timings) -> Duration {
+        match self {
+            Phase::Parse => timings.parse,
+            Phase::Part1 => timings.part1,
+            Phase::Part2 => timings.part2,
+        }
+    }
+}
+
+impl Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(match self {
+            Phase::Parse => "parse",
+            Phase::Part1 => "part1",
+            Phase::Part2 => "part2",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    /// Statistics of a non-empty set of samples, the median of an even
+    /// number of samples is the mean of the two in the middle
+    pub fn new(mut samples: Vec<Duration>) -> Stats {
+        samples.sort_unstable();
+        let mid = samples.len() / 2;
+        let median = if samples.len().is_multiple_of(2) {
+            (samples[mid - 1] + samples[mid]) / 2
+        } else {
+            samples[mid]
+        };
+        Stats {
+            min: samples[0],
+            median,
+            max: samples[samples.len() - 1],
+        }
+    }
+}
+
+/// Run `time` `warmup` times discarding the timings, then `runs` times
+/// collecting the statistics of every phase
+pub fn measure(
+    mut time: impl FnMut() -> Result<Timings, ParseError>,
+    runs: usize,
+    warmup: usize,
+) -> Result<Vec<(Phase, Stats)>, ParseError> {
+    for _ in 0..warmup {
+        time()?;
+    }
+    let timings = (0..runs.max(1))
+        .map(|_| time())
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(PHASES
+        .iter()
+        .map(|&phase| {
+            let samples = timings.iter().map(|t| phase.of(t)).collect();
+            (phase, Stats::new(samples))
+        })
+        .collect())
+}
+
+/// Median time of every phase by day
+pub type Baseline = BTreeMap<(usize, Phase), Duration>;
+
+// `day06 part1: 12.345678`, in milliseconds
+fn parse_baseline_line(line: &str) -> Option<((usize, Phase), Duration)> {
+    let (label, ms) = line.split_at(line.find(':')?);
+    let mut words = label.split(' ');
+    let day = words.next()?.strip_prefix("day")?.parse().ok()?;
+    let phase = match words.next()? {
+        "parse" => Phase::Parse,
+        "part1" => Phase::Part1,
+        "part2" => Phase::Part2,
+        _ => return None,
+    };
+    let ms = ms[1..].trim().parse::<f64>().ok()?;
+    let median = Duration::try_from_secs_f64(ms / 1000.0).ok()?;
+    Some(((day, phase), median))
+}
+
+/// Baseline saved by a previous run, blank lines and `#` comments are
+/// skipped
+pub fn parse_baseline(text: &str) -> Result<Baseline, String> {
+    let mut baseline = Baseline::new();
+    for (idx, line) in text.lines().enumerate() {
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (key, median) = parse_baseline_line(line)
+            .ok_or_else(|| format!("line {}: expected `dayNN phase: ms`", idx + 1))?;
+        baseline.insert(key, median);
+    }
+    Ok(baseline)
+}
+
+pub fn format_baseline(baseline: &Baseline) -> String {
+    let mut text = "# median times in milliseconds, see `aoc2018 bench`\n".to_string();
+    for ((day, phase), median) in baseline {
+        text += &format!("day{:02} {}: {:.6}\n", day, phase, ms(*median));
+    }
+    text
+}
+
+/// Whether `median` is slower than `baseline` by more than `threshold`
+/// percent; never against a zero baseline, too fast to compare with
+pub fn is_regression(median: Duration, baseline: Duration, threshold: f64) -> bool {
+    baseline != Duration::ZERO
+        && median.as_secs_f64() > baseline.as_secs_f64() * (1.0 + threshold / 100.0)
+}
+
+/// Change of `median` relative to `baseline` for the `change` column,
+/// `-` when the baseline is too fast to measure
+pub fn format_change(median: Duration, baseline: Duration) -> String {
+    if baseline == Duration::ZERO {
+        return "-".to_string();
+    }
+    format!("{:+.1}%", (ms(median) / ms(baseline) - 1.0) * 100.0)
+}
+
+fn ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+fn read_baseline(path: &PathBuf) -> Result<Baseline, Failure> {
+    parse_baseline(&read_input(path)?)
+        .map_err(|err| Failure::Input(format!("Invalid baseline {}, {}", path.display(), err)))
+}
+
+/// Time the given days and print a table of the statistics in
+/// milliseconds, compared with the baseline if any
+pub fn run(days: &[usize], options: &Options) -> Result<(), Failure> {
+    let baseline = match &options.baseline {
+        Some(path) => Some(read_baseline(path)?),
+        None => None,
+    };
+
+    print!(
+        "{:<5} {:<5} {:>10} {:>10} {:>10}",
+        "day", "phase", "min", "median", "max"
+    );
+    if baseline.is_some() {
+        print!(" {:>10} {:>8}", "baseline", "change");
+    }
+    println!();
+
+    let mut medians = Baseline::new();
+    let mut regressions = 0;
+    for &day in days {
+        let input = read_input(&default_input(day))?;
+        let solver = DAYS[day - 1].solver();
+        let stats = measure(|| solver.time(&input), options.runs, options.warmup)
+            .map_err(Failure::Parse)?;
+
+        for (phase, stats) in stats {
+            medians.insert((day, phase), stats.median);
+            print!(
+                "day{:02} {:<5} {:>10.3} {:>10.3} {:>10.3}",
+                day,
+                phase,
+                ms(stats.min),
+                ms(stats.median),
+                ms(stats.max)
+            );
+            if let Some(&before) = baseline.as_ref().and_then(|b| b.get(&(day, phase))) {
+                let change = format_change(stats.median, before);
+                print!(" {:>10.3} {:>8}", ms(before), change);
+                if is_regression(stats.median, before, options.threshold) {
+                    print!("  regression");
+                    regressions += 1;
+                }
+            }
+            println!();
+        }
+    }
+
+    if let Some(path) = &options.save {
+        let mut saved = if path.exists() {
+            read_baseline(path)?
+        } else {
+            Baseline::new()
+        };
+        saved.extend(medians);
+        std::fs::write(path, format_baseline(&saved))
+            .map_err(|e| Failure::Input(format!("Cannot write {}: {}", path.display(), e)))?;
+    }
+
+    match regressions {
+        0 => Ok(()),
+        n => Err(Failure::Input(format!(
+            "{} phase(s) slower than the baseline by more than {}%",
+            n, options.threshold
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{
+        format_baseline, format_change, is_regression, measure, parse_baseline, Phase, Stats,
+    };
+    use crate::days::Timings;
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn test_stats() {
+        let stats = Stats::new(vec![ms(5), ms(1), ms(3)]);
+        assert_eq!((stats.min, stats.median, stats.max), (ms(1), ms(3), ms(5)));
+
+        let stats = Stats::new(vec![ms(4), ms(1), ms(2), ms(8)]);
+        assert_eq!(stats.median, ms(3));
+    }
+
+    #[test]
+    fn test_measure() {
+        let mut calls = 0;
+        let stats = measure(
+            || {
+                calls += 1;
+                Ok(Timings {
+                    parse: ms(calls),
+                    part1: ms(10 * calls),
+                    part2: ms(1),
+                })
+            },
+            3,
+            2,
+        )
+        .unwrap();
+
+        assert_eq!(calls, 5);
+        assert_eq!(
+            stats[0],
+            (Phase::Parse, Stats::new(vec![ms(3), ms(4), ms(5)]))
+        );
+        assert_eq!(stats[1].1.max, ms(50));
+    }
+
+    #[test]
+    fn test_baseline() {
+        let text = "# comment\n\nday06 part1: 12.5\nday11 parse: 0.001\n";
+        let baseline = parse_baseline(text).unwrap();
+        assert_eq!(baseline[&(6, Phase::Part1)], Duration::from_micros(12500));
+        assert_eq!(parse_baseline(&format_baseline(&baseline)), Ok(baseline));
+
+        assert!(parse_baseline("day06 part3: 1").is_err());
+        assert!(parse_baseline("day06 part1: -1").is_err());
+        assert!(parse_baseline("day06: 1").is_err());
+        assert!(parse_baseline("day06 part1: inf").is_err());
+        assert!(parse_baseline("day06 part1: NaN").is_err());
+        assert!(parse_baseline("day06 part1: 1e300").is_err());
+
+        let baseline = parse_baseline("day01 parse: 0.000000").unwrap();
+        assert_eq!(baseline[&(1, Phase::Parse)], Duration::ZERO);
+    }
+
+    #[test]
+    fn test_format_change() {
+        assert_eq!(format_change(ms(110), ms(100)), "+10.0%");
+        assert_eq!(format_change(ms(50), ms(100)), "-50.0%");
+        assert_eq!(format_change(ms(1), Duration::ZERO), "-");
+        assert_eq!(format_change(Duration::ZERO, Duration::ZERO), "-");
+    }
+
+    #[test]
+    fn test_is_regression() {
+        assert!(!is_regression(ms(105), ms(100), 10.0));
+        assert!(is_regression(ms(111), ms(100), 10.0));
+        assert!(!is_regression(ms(50), ms(100), 0.0));
+        assert!(!is_regression(ms(1), Duration::ZERO, 10.0));
+    }
+}
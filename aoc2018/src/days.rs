@@ -0,0 +1,123 @@
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use common::{Flag, ParseError, Part, Rng, Solution, Solved};
+
+/// A day of the calendar with its puzzle constants set
+pub trait Solver {
+    /// Parse the input and solve the requested parts, in order
+    fn solve(&self, input: &str, parts: &[Part]) -> Result<Vec<Solved>, ParseError>;
+
+    /// Time the parsing of the input and both parts
+    fn time(&self, input: &str) -> Result<Timings, ParseError>;
+
+    /// Parse the input without solving it
+    fn check(&self, input: &str) -> Result<(), ParseError>;
+
+    /// Random valid input, see [`Solution::generate`]
+    fn generate(&self, rng: &mut Rng, size: usize) -> String;
+}
+
+impl<S: Solution> Solver for S {
+    fn solve(&self, input: &str, parts: &[Part]) -> Result<Vec<Solved>, ParseError> {
+        let input = common::parse(self, input)?;
+        Ok(parts
+            .iter()
+            .map(|&part| common::solve_part(self, &input, part))
+            .collect())
+    }
+
+    // the answers are only computed, not rendered, and kept opaque to the
+    // optimizer so that the work is not skipped
+    fn time(&self, input: &str) -> Result<Timings, ParseError> {
+        let start = Instant::now();
+        let input = common::parse(self, black_box(input))?;
+        let parse = start.elapsed();
+
+        let start = Instant::now();
+        black_box(self.part1(black_box(&input)));
+        let part1 = start.elapsed();
+
+        let start = Instant::now();
+        black_box(self.part2(black_box(&input)));
+        let part2 = start.elapsed();
+
+        Ok(Timings {
+            parse,
+            part1,
+            part2,
+        })
+    }
+
+    fn check(&self, input: &str) -> Result<(), ParseError> {
+        common::parse(self, input).map(|_| ())
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        Solution::generate(self, rng, size)
+    }
+}
+
+// solver of a day with the puzzle constants set by `(flag, value)` pairs
+type Configure = fn(&[(String, String)]) -> Result<Box<dyn Solver>, String>;
+
+/// Entry point of a day of the calendar
+#[derive(Clone, Copy)]
+pub struct Day {
+    /// Flags setting the puzzle constants
+    pub flags: &'static [Flag],
+    new: Configure,
+}
+
+impl Day {
+    const fn of<S: Solution + Default + 'static>() -> Day {
+        Day {
+            flags: S::FLAGS,
+            new: configure::<S>,
+        }
+    }
+
+    /// Solver with the constants set by `(flag, value)` pairs, the others
+    /// keep the values of the puzzle
+    pub fn configure(&self, flags: &[(String, String)]) -> Result<Box<dyn Solver>, String> {
+        (self.new)(flags)
+    }
+
+    /// Solver of the puzzle as stated
+    pub fn solver(&self) -> Box<dyn Solver> {
+        self.configure(&[]).expect("No flags to set")
+    }
+}
+
+fn configure<S: Solution + Default + 'static>(
+    flags: &[(String, String)],
+) -> Result<Box<dyn Solver>, String> {
+    let mut solution = S::default();
+    common::set_flags(&mut solution, flags)?;
+    Ok(Box::new(solution))
+}
+
+pub const DAYS: [Day; 14] = [
+    Day::of::<day01::Day01>(),
+    Day::of::<day02::Day02>(),
+    Day::of::<day03::Day03>(),
+    Day::of::<day04::Day04>(),
+    Day::of::<day05::Day05>(),
+    Day::of::<day06::Day06>(),
+    Day::of::<day07::Day07>(),
+    Day::of::<day08::Day08>(),
+    Day::of::<day09::Day09>(),
+    Day::of::<day10::Day10>(),
+    Day::of::<day11::Day11>(),
+    Day::of::<day12::Day12>(),
+    Day::of::<day13::Day13>(),
+    Day::of::<day14::Day14>(),
+];
+
+/// Time taken by every phase of a day in a single run
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Timings {
+    pub parse: Duration,
+    pub part1: Duration,
+    pub part2: Duration,
+}
@@ -0,0 +1,98 @@
+//! Worked examples of the puzzles, extracted from the README of every day
+//! and run through the solvers, see [`common::extract_examples`] for how
+//! they are marked
+
+use std::path::PathBuf;
+
+use common::{extract_examples, ParseError, Part};
+
+use crate::days::Solver;
+
+pub fn readme(day: usize) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join(format!("day{:02}", day))
+        .join("README.md")
+}
+
+/// Answers of the examples checked against the README
+pub struct Checked {
+    /// number of answers compared
+    pub answers: usize,
+    /// `README.md:LINE partN: expected …, got …`, or why an example could
+    /// not be solved
+    pub failures: Vec<String>,
+}
+
+/// Solve the examples of the README `text` for the given parts, the
+/// failures located in the file `name`
+pub fn check_examples(
+    solver: &dyn Solver,
+    text: &str,
+    name: &str,
+    parts: &[Part],
+) -> Result<Checked, ParseError> {
+    let mut checked = Checked {
+        answers: 0,
+        failures: Vec::new(),
+    };
+    for example in extract_examples(text)? {
+        let expected: Vec<(Part, String)> =
+            vec![(Part::One, example.part1), (Part::Two, example.part2)]
+                .into_iter()
+                .filter(|(part, _)| parts.contains(part))
+                .filter_map(|(part, answer)| answer.map(|answer| (part, answer)))
+                .collect();
+        let parts: Vec<Part> = expected.iter().map(|&(part, _)| part).collect();
+        let location = format!("{}:{}", name, example.line);
+
+        let answers = match solver.solve(&example.input, &parts) {
+            Ok(answers) => answers,
+            Err(err) => {
+                checked.failures.push(format!("{}: {}", location, err));
+                continue;
+            }
+        };
+        for ((part, expected), solved) in expected.into_iter().zip(answers) {
+            checked.answers += 1;
+            let answer = solved.answer.trim_end();
+            if answer != expected {
+                checked.failures.push(format!(
+                    "{} part{}: expected {}, got {}",
+                    location,
+                    part.number(),
+                    expected,
+                    answer
+                ));
+            }
+        }
+    }
+    Ok(checked)
+}
+
+#[cfg(test)]
+mod tests {
+    use common::Part;
+
+    use super::{check_examples, readme};
+    use crate::days::DAYS;
+
+    #[test]
+    fn test_readme_examples() {
+        let mut answers = 0;
+        let mut failures = Vec::new();
+        for day in 1..=DAYS.len() {
+            let name = format!("day{:02}/README.md", day);
+            let text = std::fs::read_to_string(readme(day)).unwrap();
+            let solver = DAYS[day - 1].solver();
+            let checked = check_examples(&*solver, &text, &name, &[Part::One, Part::Two])
+                .unwrap_or_else(|err| panic!("{}\n{}", name, err.diagnostic()));
+            assert!(checked.answers > 0, "day{:02}: no examples", day);
+            answers += checked.answers;
+            failures.extend(checked.failures);
+        }
+
+        assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+        assert!(answers > 0);
+    }
+}
@@ -0,0 +1,254 @@
+//! Smoke fuzzing of the parsers of every day, with nothing to install
+//!
+//! Every case is a random input, either random characters or an input made
+//! up by the generator of the day with a few random edits, parsed in a
+//! worker thread: the parser must return an answer or an error, without
+//! panicking and within the timeout. The coverage guided targets of
+//! `fuzz/` go deeper but need cargo-fuzz and a nightly compiler:
+//!
+//! ```text
+//! cargo run --release -p aoc2018 -- fuzz --all --cases 100000
+//! cd fuzz && cargo +nightly fuzz run day04
+//! ```
+
+use std::fmt::{self, Display};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use common::Rng;
+
+use crate::days::DAYS;
+use crate::{panic_message, quiet_panics, Failure};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Options {
+    pub cases: usize,
+    pub seed: u64,
+    /// Time a parser is given on a case before it is deemed hanging
+    pub timeout: Duration,
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options {
+            cases: 1_000,
+            seed: 0,
+            timeout: Duration::from_secs(1),
+        }
+    }
+}
+
+/// An input on which a parser panicked or hanged
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    /// Number of the case, from 0
+    pub case: usize,
+    pub input: String,
+    /// Message of the panic, none for a hang
+    pub panic: Option<String>,
+}
+
+impl Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.panic {
+            Some(message) => write!(f, "case {} panicked: {}", self.case, message)?,
+            None => write!(f, "case {} did not return", self.case)?,
+        }
+        write!(f, "\ninput: {:?}", self.input)
+    }
+}
+
+// pieces of the puzzle inputs most likely to reach the corners of the
+// parsers: separators, signs, numbers overflowing the integer types
+const TOKENS: [&str; 24] = [
+    "\n",
+    " ",
+    "  ",
+    ",",
+    ":",
+    "#",
+    "@",
+    "x",
+    "-",
+    "+",
+    "<",
+    ">",
+    "=",
+    "[",
+    "]",
+    ".",
+    "0",
+    "-1",
+    "255",
+    "65536",
+    "4294967296",
+    "99999999999999999999",
+    "é",
+    "\u{0}",
+];
+
+fn random_char(rng: &mut Rng) -> char {
+    match rng.below(4) {
+        // printable ASCII most of the time
+        0..=2 => (b' ' + rng.below(95) as u8) as char,
+        _ => std::char::from_u32(rng.below(0x11_0000) as u32).unwrap_or('\u{fffd}'),
+    }
+}
+
+/// Random characters and tokens, or one of the `bases` with a few random
+/// edits
+pub fn make_case(rng: &mut Rng, bases: &[String]) -> String {
+    if bases.is_empty() || rng.chance(0.3) {
+        let mut input = String::new();
+        for _ in 0..rng.range(0..=60) {
+            match rng.chance(0.5) {
+                true => input += *rng.pick(&TOKENS),
+                false => input.push(random_char(rng)),
+            }
+        }
+        return input;
+    }
+
+    let mut chars: Vec<char> = rng.pick(bases).chars().collect();
+    for _ in 0..rng.range(1..=4) {
+        let at = rng.index(chars.len() + 1);
+        let len = rng.range(1..=8) as usize;
+        let end = (at + len).min(chars.len());
+        match rng.below(5) {
+            0 => {
+                chars.drain(at..end);
+            }
+            1 => {
+                let token: Vec<char> = rng.pick(&TOKENS).chars().collect();
+                chars.splice(at..at, token);
+            }
+            2 if at < chars.len() => chars[at] = random_char(rng),
+            3 => {
+                let copy = chars[at..end].to_vec();
+                chars.splice(at..at, copy);
+            }
+            _ => chars.truncate(at),
+        }
+    }
+    chars.into_iter().collect()
+}
+
+/// Parse `options.cases` random inputs with the parser of the day,
+/// returning the first one that panics or hangs. The parser runs in a
+/// worker thread left behind when it hangs
+pub fn fuzz_day(day: usize, options: &Options) -> Result<(), Finding> {
+    let (inputs, worker_inputs) = mpsc::channel::<String>();
+    let (worker_results, results) = mpsc::channel();
+    thread::spawn(move || {
+        let solver = DAYS[day - 1].solver();
+        for input in worker_inputs {
+            let result = panic::catch_unwind(AssertUnwindSafe(|| solver.check(&input)));
+            if worker_results.send(result.map(|_| ())).is_err() {
+                break;
+            }
+        }
+    });
+
+    let solver = DAYS[day - 1].solver();
+    let mut rng = Rng::new(options.seed);
+    let bases: Vec<String> = (1..=8)
+        .map(|size| solver.generate(&mut rng, size))
+        .collect();
+    for case in 0..options.cases {
+        let input = make_case(&mut rng, &bases);
+        inputs.send(input.clone()).expect("Fuzzing worker");
+        let panic = match results.recv_timeout(options.timeout) {
+            Ok(Ok(())) => continue,
+            Ok(Err(payload)) => Some(panic_message(payload)),
+            Err(_) => None,
+        };
+        return Err(Finding { case, input, panic });
+    }
+    Ok(())
+}
+
+/// Fuzz the given days printing the outcome of every day, the panics
+/// caught are not reported as they happen
+pub fn run(days: &[usize], options: &Options) -> Result<(), Failure> {
+    let mut failures = 0;
+    quiet_panics(|| {
+        for &day in days {
+            match fuzz_day(day, options) {
+                Ok(()) => println!("day{:02}: {} cases ok", day, options.cases),
+                Err(finding) => {
+                    println!("day{:02}: {}", day, finding);
+                    failures += 1;
+                }
+            }
+        }
+    });
+
+    match failures {
+        0 => Ok(()),
+        n => Err(Failure::Input(format!(
+            "{} day(s) with a parser panicking or hanging",
+            n
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use common::Rng;
+
+    use super::{fuzz_day, make_case, Finding, Options};
+    use crate::days::DAYS;
+
+    #[test]
+    fn test_make_case() {
+        let mut rng = Rng::new(0);
+        let bases = vec!["#1 @ 1,3: 4x4\n".to_string()];
+        let cases: Vec<String> = (0..100).map(|_| make_case(&mut rng, &bases)).collect();
+        assert!(cases.iter().any(|case| case.starts_with("#1 @")));
+        assert!(cases.iter().any(|case| !case.is_ascii()));
+        assert!(cases.iter().any(|case| case.is_empty()));
+        assert_eq!(
+            make_case(&mut Rng::new(3), &bases),
+            make_case(&mut Rng::new(3), &bases)
+        );
+    }
+
+    #[test]
+    fn test_fuzz_days() {
+        let options = Options {
+            cases: 300,
+            timeout: Duration::from_secs(10),
+            ..Options::default()
+        };
+        for day in 1..=DAYS.len() {
+            if let Err(finding) = fuzz_day(day, &options) {
+                panic!("day{:02}: {}", day, finding);
+            }
+        }
+    }
+
+    #[test]
+    fn test_finding() {
+        let finding = Finding {
+            case: 3,
+            input: "1\n".to_string(),
+            panic: Some("overflow".to_string()),
+        };
+        assert_eq!(
+            finding.to_string(),
+            "case 3 panicked: overflow\ninput: \"1\\n\""
+        );
+        let finding = Finding {
+            panic: None,
+            ..finding
+        };
+        assert_eq!(
+            finding.to_string(),
+            "case 3 did not return\ninput: \"1\\n\""
+        );
+    }
+}
@@ -0,0 +1,51 @@
+//! Inputs made up by the generators of every day, which must be accepted
+//! by the parsers and, when quick enough, solved
+
+use common::{Part, Rng};
+
+use crate::days::DAYS;
+
+#[test]
+fn test_generated_inputs() {
+    for (n, day) in DAYS.iter().enumerate() {
+        let solver = day.solver();
+        for seed in 0..5 {
+            let input = solver.generate(&mut Rng::new(seed), 20);
+            if let Err(err) = solver.check(&input) {
+                panic!(
+                    "day{:02} seed {}: generated input rejected\n{}",
+                    n + 1,
+                    seed,
+                    err.diagnostic()
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_generated_answers() {
+    // the parts taking seconds without optimizations are left out: part 2
+    // of day 11 and day 14 searching for random digits
+    let parts = |day: usize| match day {
+        11 | 14 => vec![Part::One],
+        _ => vec![Part::One, Part::Two],
+    };
+    for day in 1..=DAYS.len() {
+        let solver = DAYS[day - 1].solver();
+        let input = solver.generate(&mut Rng::new(day as u64), 20);
+        let answers = solver.solve(&input, &parts(day)).unwrap();
+        assert_eq!(answers.len(), parts(day).len(), "day{:02}", day);
+    }
+}
+
+#[test]
+fn test_same_seed() {
+    for day in DAYS.iter() {
+        let solver = day.solver();
+        assert_eq!(
+            solver.generate(&mut Rng::new(7), 10),
+            solver.generate(&mut Rng::new(7), 10)
+        );
+    }
+}
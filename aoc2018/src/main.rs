@@ -0,0 +1,404 @@
+use std::any::Any;
+use std::fmt::Display;
+use std::panic;
+use std::path::PathBuf;
+use std::process;
+use std::str::FromStr;
+use std::thread;
+use std::time::Duration;
+
+use common::{Format, ParseError, Part, Rng};
+
+#[cfg(test)]
+mod answers;
+mod bench;
+mod days;
+mod examples;
+mod fuzz;
+#[cfg(test)]
+mod generated;
+mod summary;
+mod watch;
+
+use days::{Solver, DAYS};
+
+const USAGE: &str = "Usage: aoc2018 run <day> [--part 1|2] [--input FILE] [--format text|json]\n                     \
+                     [--watch] [--FLAG VALUE]...\n       \
+                     aoc2018 run --all [--format text|json]\n       \
+                     aoc2018 bench <day>|--all [--runs N] [--warmup N] [--save FILE]\n                     \
+                     [--baseline FILE] [--threshold PERCENT]\n       \
+                     aoc2018 gen <day> [--seed N] [--size N] [--output FILE]\n       \
+                     aoc2018 fuzz <day>|--all [--cases N] [--seed N] [--timeout MS]\n       \
+                     aoc2018 summary [--jobs N]";
+
+enum Command {
+    Run {
+        day: usize,
+        parts: Vec<Part>,
+        input: Option<PathBuf>,
+        format: Format,
+        flags: Vec<(String, String)>,
+        watch: bool,
+    },
+    RunAll {
+        format: Format,
+    },
+    Bench {
+        days: Vec<usize>,
+        options: bench::Options,
+    },
+    Gen {
+        day: usize,
+        seed: u64,
+        size: usize,
+        output: Option<PathBuf>,
+    },
+    Fuzz {
+        days: Vec<usize>,
+        options: fuzz::Options,
+    },
+    Summary {
+        jobs: usize,
+    },
+}
+
+fn parse_day(arg: &str) -> Result<usize, String> {
+    match arg.parse::<usize>() {
+        Ok(day) if day >= 1 && day <= DAYS.len() => Ok(day),
+        _ => Err(format!("Invalid day {}, expected 1-{}", arg, DAYS.len())),
+    }
+}
+
+fn parse_part(arg: Option<String>) -> Result<Part, String> {
+    match arg.as_deref() {
+        Some("1") => Ok(Part::One),
+        Some("2") => Ok(Part::Two),
+        Some(part) => Err(format!("Invalid part {}, expected 1 or 2", part)),
+        None => Err("Missing value for --part".to_string()),
+    }
+}
+
+fn parse_value<T>(arg: &str, value: Option<String>) -> Result<T, String>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let value = value.ok_or_else(|| format!("Missing value for {}", arg))?;
+    value
+        .parse()
+        .map_err(|err| format!("Invalid value {} for {}: {}", value, arg, err))
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Command, String> {
+    match args.next().as_deref() {
+        Some("run") => parse_run(args),
+        Some("bench") => parse_bench(args),
+        Some("gen") => parse_gen(args),
+        Some("fuzz") => parse_fuzz(args),
+        Some("summary") => parse_summary(args),
+        Some(cmd) => Err(format!("Unknown command {}", cmd)),
+        None => Err("Missing command".to_string()),
+    }
+}
+
+fn parse_run(mut args: impl Iterator<Item = String>) -> Result<Command, String> {
+    let mut day = None;
+    let mut all = false;
+    let mut parts = vec![Part::One, Part::Two];
+    let mut input = None;
+    let mut format = Format::Text;
+    let mut flags = Vec::new();
+    let mut watch = false;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--all" => all = true,
+            "--watch" => watch = true,
+            "--part" => parts = vec![parse_part(args.next())?],
+            "--input" => {
+                input = Some(PathBuf::from(
+                    args.next().ok_or("Missing value for --input")?,
+                ))
+            }
+            "--format" => format = args.next().ok_or("Missing value for --format")?.parse()?,
+            _ if day.is_none() && !arg.starts_with('-') => day = Some(parse_day(&arg)?),
+            // a puzzle constant of the day, checked once the day is known
+            _ if arg.starts_with("--") => match args.next() {
+                Some(value) => flags.push((arg[2..].to_string(), value)),
+                None => return Err(format!("Unexpected argument {}", arg)),
+            },
+            _ => return Err(format!("Unexpected argument {}", arg)),
+        }
+    }
+
+    let known = day.map_or(&[][..], |day| DAYS[day - 1].flags);
+    if let Some((flag, _)) = flags
+        .iter()
+        .find(|(flag, _)| !known.iter().any(|f| f.name == flag))
+    {
+        let names: Vec<_> = known.iter().map(|f| format!("--{}", f.name)).collect();
+        return Err(match (day, names.is_empty()) {
+            (Some(day), false) => format!(
+                "Unexpected argument --{}, the flags of day {} are {}",
+                flag,
+                day,
+                names.join(", ")
+            ),
+            _ => format!("Unexpected argument --{}", flag),
+        });
+    }
+
+    if watch && format != Format::Text {
+        return Err("--watch prints the answers as text only".to_string());
+    }
+    match (day, all) {
+        (Some(day), false) => Ok(Command::Run {
+            day,
+            parts,
+            input,
+            format,
+            flags,
+            watch,
+        }),
+        (None, true) if watch => Err("--watch cannot be used with --all".to_string()),
+        (None, true) if input.is_none() => Ok(Command::RunAll { format }),
+        (None, true) => Err("--input cannot be used with --all".to_string()),
+        (Some(_), true) => Err("Cannot use a day together with --all".to_string()),
+        (None, false) => Err("Missing day".to_string()),
+    }
+}
+
+fn parse_bench(mut args: impl Iterator<Item = String>) -> Result<Command, String> {
+    let mut days = Vec::new();
+    let mut all = false;
+    let mut options = bench::Options::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--all" => all = true,
+            "--runs" => options.runs = parse_value(&arg, args.next())?,
+            "--warmup" => options.warmup = parse_value(&arg, args.next())?,
+            "--save" => options.save = Some(parse_value(&arg, args.next())?),
+            "--baseline" => options.baseline = Some(parse_value(&arg, args.next())?),
+            "--threshold" => options.threshold = parse_value(&arg, args.next())?,
+            _ if days.is_empty() && !arg.starts_with('-') => days.push(parse_day(&arg)?),
+            _ => return Err(format!("Unexpected argument {}", arg)),
+        }
+    }
+
+    if options.runs == 0 {
+        return Err("--runs must be at least 1".to_string());
+    }
+    if options.threshold.is_nan() || options.threshold < 0.0 {
+        return Err("--threshold must be a non-negative percentage".to_string());
+    }
+    match (days.is_empty(), all) {
+        (false, false) => Ok(Command::Bench { days, options }),
+        (true, true) => Ok(Command::Bench {
+            days: (1..=DAYS.len()).collect(),
+            options,
+        }),
+        (false, true) => Err("Cannot use a day together with --all".to_string()),
+        (true, false) => Err("Missing day".to_string()),
+    }
+}
+
+fn parse_gen(mut args: impl Iterator<Item = String>) -> Result<Command, String> {
+    let mut day = None;
+    let mut seed = 0;
+    let mut size = 100;
+    let mut output = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seed" => seed = parse_value(&arg, args.next())?,
+            "--size" => size = parse_value(&arg, args.next())?,
+            "--output" => output = Some(parse_value(&arg, args.next())?),
+            _ if day.is_none() && !arg.starts_with('-') => day = Some(parse_day(&arg)?),
+            _ => return Err(format!("Unexpected argument {}", arg)),
+        }
+    }
+
+    let day = day.ok_or("Missing day")?;
+    Ok(Command::Gen {
+        day,
+        seed,
+        size,
+        output,
+    })
+}
+
+fn parse_fuzz(mut args: impl Iterator<Item = String>) -> Result<Command, String> {
+    let mut days = Vec::new();
+    let mut all = false;
+    let mut options = fuzz::Options::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--all" => all = true,
+            "--cases" => options.cases = parse_value(&arg, args.next())?,
+            "--seed" => options.seed = parse_value(&arg, args.next())?,
+            "--timeout" => options.timeout = Duration::from_millis(parse_value(&arg, args.next())?),
+            _ if days.is_empty() && !arg.starts_with('-') => days.push(parse_day(&arg)?),
+            _ => return Err(format!("Unexpected argument {}", arg)),
+        }
+    }
+
+    match (days.is_empty(), all) {
+        (false, false) => Ok(Command::Fuzz { days, options }),
+        (true, true) => Ok(Command::Fuzz {
+            days: (1..=DAYS.len()).collect(),
+            options,
+        }),
+        (false, true) => Err("Cannot use a day together with --all".to_string()),
+        (true, false) => Err("Missing day".to_string()),
+    }
+}
+
+fn parse_summary(mut args: impl Iterator<Item = String>) -> Result<Command, String> {
+    // a thread per core by default
+    let mut jobs = thread::available_parallelism().map_or(4, |n| n.get());
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--jobs" => jobs = parse_value(&arg, args.next())?,
+            _ => return Err(format!("Unexpected argument {}", arg)),
+        }
+    }
+    if jobs == 0 {
+        return Err("--jobs must be at least 1".to_string());
+    }
+    Ok(Command::Summary { jobs })
+}
+
+// the checked-in puzzle input of a day
+fn default_input(day: usize) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join(format!("day{:02}", day))
+        .join("input")
+}
+
+// why a run stopped, parse errors are rendered pointing at the input
+enum Failure {
+    Input(String),
+    Parse(ParseError),
+}
+
+// message of a panic caught with `catch_unwind`
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast::<&str>() {
+            Ok(message) => message.to_string(),
+            Err(_) => "unknown panic".to_string(),
+        },
+    }
+}
+
+// run `f` without printing the panics, which are caught and reported by
+// the caller
+fn quiet_panics<T>(f: impl FnOnce() -> T) -> T {
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let result = f();
+    panic::set_hook(hook);
+    result
+}
+
+fn read_input(path: &PathBuf) -> Result<String, Failure> {
+    std::fs::read_to_string(path)
+        .map_err(|e| Failure::Input(format!("Cannot read {}: {}", path.display(), e)))
+}
+
+// print the answers alone, labelled with the day when running them all,
+// or as a JSON object per line
+fn run_day(
+    day: usize,
+    solver: &dyn Solver,
+    parts: &[Part],
+    input: &str,
+    format: Format,
+    label: bool,
+) -> Result<(), Failure> {
+    let answers = solver.solve(input, parts).map_err(Failure::Parse)?;
+    for solved in answers {
+        let answer = solved.answer.trim_end();
+        match format {
+            Format::Json => println!("{}", solved.to_json(day as u8)),
+            Format::Text if label => {
+                let sep = if answer.contains('\n') { '\n' } else { ' ' };
+                println!(
+                    "day{:02} part{}:{}{}",
+                    day,
+                    solved.part.number(),
+                    sep,
+                    answer
+                );
+            }
+            Format::Text => println!("{}", answer),
+        }
+    }
+    Ok(())
+}
+
+fn run(command: Command) -> Result<(), Failure> {
+    match command {
+        Command::Run {
+            day,
+            parts,
+            input,
+            format,
+            flags,
+            watch,
+        } => {
+            let solver = DAYS[day - 1].configure(&flags).map_err(Failure::Input)?;
+            let path = input.unwrap_or_else(|| default_input(day));
+            if watch {
+                return watch::run(path, examples::readme(day), solver, parts);
+            }
+            run_day(day, &*solver, &parts, &read_input(&path)?, format, false)
+        }
+        Command::RunAll { format } => (1..=DAYS.len()).try_for_each(|day| {
+            let input = read_input(&default_input(day))?;
+            let solver = DAYS[day - 1].solver();
+            run_day(day, &*solver, &[Part::One, Part::Two], &input, format, true)
+        }),
+        Command::Bench { days, options } => bench::run(&days, &options),
+        Command::Gen {
+            day,
+            seed,
+            size,
+            output,
+        } => {
+            let solver = DAYS[day - 1].solver();
+            let input = solver.generate(&mut Rng::new(seed), size);
+            // a generator bug rather than a bad input, but shown the same
+            solver.check(&input).map_err(Failure::Parse)?;
+            match output {
+                Some(path) => std::fs::write(&path, input)
+                    .map_err(|e| Failure::Input(format!("Cannot write {}: {}", path.display(), e))),
+                None => {
+                    print!("{}", input);
+                    Ok(())
+                }
+            }
+        }
+        Command::Fuzz { days, options } => fuzz::run(&days, &options),
+        Command::Summary { jobs } => summary::run(jobs),
+    }
+}
+
+fn main() {
+    let command = parse_args(std::env::args().skip(1)).unwrap_or_else(|err| {
+        eprintln!("Error: {}\n{}", err, USAGE);
+        process::exit(2);
+    });
+
+    match run(command) {
+        Ok(()) => (),
+        Err(Failure::Input(err)) => {
+            eprintln!("Error: {}", err);
+            process::exit(1);
+        }
+        Err(Failure::Parse(err)) => {
+            eprint!("{}", err.diagnostic());
+            process::exit(1);
+        }
+    }
+}
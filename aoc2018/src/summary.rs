@@ -0,0 +1,240 @@
+//! All the days run at once on their checked-in puzzle inputs
+//!
+//! The days are shared out to a pool of worker threads, every day parsed
+//! and solved on its own so that a day failing or panicking does not stop
+//! the others. The outcomes are printed as a table once all the days are
+//! done, and any failure makes the command fail:
+//!
+//! ```text
+//! cargo run --release -p aoc2018 -- summary --jobs 4
+//! ```
+
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use common::Part;
+
+use crate::days::DAYS;
+use crate::{default_input, panic_message, quiet_panics, read_input, Failure};
+
+/// How a day ended
+#[derive(Debug, Clone, PartialEq)]
+pub enum Status {
+    Ok,
+    /// The input could not be read or parsed
+    Failed(String),
+    Panicked(String),
+}
+
+/// Answers of a day with the time taken to read, parse and solve it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Outcome {
+    pub day: usize,
+    /// Answers of the parts solved, none unless the status is ok
+    pub answers: Vec<String>,
+    pub time: Duration,
+    pub status: Status,
+}
+
+/// Solve the days with `solve` on `jobs` threads, returning the outcomes
+/// in the order of the days
+pub fn run_days(
+    days: &[usize],
+    jobs: usize,
+    solve: impl Fn(usize) -> Result<Vec<String>, String> + Sync,
+) -> Vec<Outcome> {
+    let next = AtomicUsize::new(0);
+    let outcomes = Mutex::new(Vec::new());
+    thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, days.len().max(1)) {
+            scope.spawn(|| {
+                while let Some(&day) = days.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    let start = Instant::now();
+                    let result = panic::catch_unwind(AssertUnwindSafe(|| solve(day)));
+                    let time = start.elapsed();
+                    let (answers, status) = match result {
+                        Ok(Ok(answers)) => (answers, Status::Ok),
+                        Ok(Err(err)) => (Vec::new(), Status::Failed(err)),
+                        Err(payload) => (Vec::new(), Status::Panicked(panic_message(payload))),
+                    };
+                    let outcome = Outcome {
+                        day,
+                        answers,
+                        time,
+                        status,
+                    };
+                    outcomes.lock().unwrap().push(outcome);
+                }
+            });
+        }
+    });
+
+    let mut outcomes = outcomes.into_inner().unwrap();
+    outcomes.sort_by_key(|outcome| outcome.day);
+    outcomes
+}
+
+// an answer in a cell of the table, the messages drawn on several lines
+// only counted
+fn cell(answer: Option<&String>) -> String {
+    match answer {
+        Some(answer) if answer.trim_end().contains('\n') => {
+            format!("<{} lines>", answer.trim_end().lines().count())
+        }
+        Some(answer) => answer.trim_end().to_string(),
+        None => "-".to_string(),
+    }
+}
+
+/// Table of the outcomes with a row per day, the time in milliseconds
+pub fn format_table(outcomes: &[Outcome]) -> String {
+    let rows: Vec<[String; 5]> = outcomes
+        .iter()
+        .map(|outcome| {
+            let status = match &outcome.status {
+                Status::Ok => "ok".to_string(),
+                Status::Failed(err) => format!("failed: {}", err),
+                Status::Panicked(message) => format!("panicked: {}", message),
+            };
+            [
+                format!("day{:02}", outcome.day),
+                cell(outcome.answers.first()),
+                cell(outcome.answers.get(1)),
+                format!("{:.3}", outcome.time.as_secs_f64() * 1000.0),
+                status,
+            ]
+        })
+        .collect();
+
+    let header = ["day", "part1", "part2", "time", "status"].map(String::from);
+    let width = |column: usize| {
+        std::iter::once(&header)
+            .chain(&rows)
+            .map(|row| row[column].chars().count())
+            .max()
+            .unwrap_or(0)
+    };
+    let widths: Vec<usize> = (0..5).map(width).collect();
+
+    let mut table = String::new();
+    for row in std::iter::once(&header).chain(&rows) {
+        let line = format!(
+            "{:<w0$}  {:<w1$}  {:<w2$}  {:>w3$}  {}",
+            row[0],
+            row[1],
+            row[2],
+            row[3],
+            row[4],
+            w0 = widths[0],
+            w1 = widths[1],
+            w2 = widths[2],
+            w3 = widths[3]
+        );
+        table += line.trim_end();
+        table.push('\n');
+    }
+    table
+}
+
+fn solve_day(day: usize) -> Result<Vec<String>, String> {
+    let input = read_input(&default_input(day)).map_err(|err| match err {
+        Failure::Input(err) => err,
+        Failure::Parse(err) => err.to_string(),
+    })?;
+    let solved = DAYS[day - 1]
+        .solver()
+        .solve(&input, &[Part::One, Part::Two])
+        .map_err(|err| err.to_string())?;
+    Ok(solved.into_iter().map(|solved| solved.answer).collect())
+}
+
+/// Run all the days on `jobs` threads and print the table of the outcomes
+pub fn run(jobs: usize) -> Result<(), Failure> {
+    let days: Vec<usize> = (1..=DAYS.len()).collect();
+    let start = Instant::now();
+    let outcomes = quiet_panics(|| run_days(&days, jobs, solve_day));
+    print!("{}", format_table(&outcomes));
+
+    let failures = outcomes
+        .iter()
+        .filter(|outcome| outcome.status != Status::Ok)
+        .count();
+    println!(
+        "{} of {} days ok in {:.3} ms",
+        outcomes.len() - failures,
+        outcomes.len(),
+        start.elapsed().as_secs_f64() * 1000.0
+    );
+    match failures {
+        0 => Ok(()),
+        n => Err(Failure::Input(format!("{} day(s) failed", n))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{format_table, run_days, Outcome, Status};
+
+    #[test]
+    fn test_run_days() {
+        let solve = |day: usize| match day {
+            2 => Err("day 2: empty input".to_string()),
+            3 => panic!("no claim left intact"),
+            _ => Ok(vec![day.to_string(), (day * 10).to_string()]),
+        };
+        let outcomes = run_days(&[1, 2, 3, 4, 5, 6], 3, solve);
+
+        let days: Vec<usize> = outcomes.iter().map(|outcome| outcome.day).collect();
+        assert_eq!(days, [1, 2, 3, 4, 5, 6]);
+        assert_eq!(outcomes[0].answers, ["1", "10"]);
+        assert_eq!(outcomes[0].status, Status::Ok);
+        assert_eq!(
+            outcomes[1].status,
+            Status::Failed("day 2: empty input".to_string())
+        );
+        assert_eq!(
+            outcomes[2].status,
+            Status::Panicked("no claim left intact".to_string())
+        );
+        // the days after the panic still run
+        assert_eq!(outcomes[5].answers, ["6", "60"]);
+
+        assert!(run_days(&[], 4, solve).is_empty());
+    }
+
+    #[test]
+    fn test_format_table() {
+        let outcomes = [
+            Outcome {
+                day: 2,
+                answers: vec!["7134".to_string(), "kbqwt".to_string()],
+                time: Duration::from_micros(1_500),
+                status: Status::Ok,
+            },
+            Outcome {
+                day: 10,
+                answers: vec!["#..\n.#.\n..#\n".to_string(), "3".to_string()],
+                time: Duration::from_millis(42),
+                status: Status::Ok,
+            },
+            Outcome {
+                day: 13,
+                answers: Vec::new(),
+                time: Duration::from_millis(1),
+                status: Status::Panicked("Malformed track".to_string()),
+            },
+        ];
+        assert_eq!(
+            format_table(&outcomes),
+            "day    part1      part2    time  status\n\
+             day02  7134       kbqwt   1.500  ok\n\
+             day10  <3 lines>  3      42.000  ok\n\
+             day13  -          -       1.000  panicked: Malformed track\n"
+        );
+    }
+}
@@ -0,0 +1,269 @@
+//! Re-solving a day whenever its input or its examples change, for
+//! `run --watch`
+//!
+//! The modification time and the size of the input and of the README of
+//! the day are polled, nothing to install. After every change the input is
+//! parsed and solved again, the answers that changed since the last
+//! successful run shown as a diff, then the worked examples of the README
+//! are checked; errors are shown in their place and the watch goes on.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use common::Part;
+
+use crate::days::Solver;
+use crate::examples::check_examples;
+use crate::Failure;
+
+const INTERVAL: Duration = Duration::from_millis(500);
+
+// the lines of an answer under its label, an answer on several lines
+// starting on the line after the label
+fn labelled(part: Part, answer: &str) -> Vec<String> {
+    let answer = answer.trim_end();
+    let label = format!("part{}:", part.number());
+    if answer.contains('\n') {
+        std::iter::once(label)
+            .chain(answer.lines().map(String::from))
+            .collect()
+    } else {
+        vec![format!("{} {}", label, answer)]
+    }
+}
+
+/// The answers of the parts, the ones that differ from the `previous`
+/// answers as the removed lines `- ` and the added lines `+ `
+pub fn diff_answers(parts: &[Part], previous: Option<&[String]>, answers: &[String]) -> String {
+    let mut diff = String::new();
+    for (n, (&part, answer)) in parts.iter().zip(answers).enumerate() {
+        let before = previous.and_then(|previous| previous.get(n));
+        let mut add = |prefix: &str, answer: &str| {
+            for line in labelled(part, answer) {
+                diff += &format!("{}{}\n", prefix, line);
+            }
+        };
+        match before {
+            Some(before) if before.trim_end() != answer.trim_end() => {
+                add("- ", before);
+                add("+ ", answer);
+            }
+            _ => add("  ", answer),
+        }
+    }
+    diff
+}
+
+// what tells that the file changed, or why it cannot be read
+fn stamp(path: &Path) -> Result<(SystemTime, u64), String> {
+    fs::metadata(path)
+        .and_then(|metadata| Ok((metadata.modified()?, metadata.len())))
+        .map_err(|e| format!("Cannot read {}: {}", path.display(), e))
+}
+
+// the lines reporting the examples of the README, the failures only when
+// some answers differ
+fn report_examples(solver: &dyn Solver, readme: &Path, parts: &[Part]) -> String {
+    let text = match fs::read_to_string(readme) {
+        Ok(text) => text,
+        Err(e) => return format!("Error: Cannot read {}: {}\n", readme.display(), e),
+    };
+    match check_examples(solver, &text, "README.md", parts) {
+        Ok(checked) if checked.failures.is_empty() => {
+            format!("  examples: {} answer(s) match\n", checked.answers)
+        }
+        Ok(checked) => checked
+            .failures
+            .iter()
+            .map(|failure| format!("! {}\n", failure))
+            .collect(),
+        Err(err) => format!("Invalid example in the README, {}\n", err.diagnostic()),
+    }
+}
+
+/// A day solved again on every change of its input or of the README with
+/// its examples
+pub struct Watcher {
+    path: PathBuf,
+    readme: PathBuf,
+    solver: Box<dyn Solver>,
+    parts: Vec<Part>,
+    stamps: Option<[Result<(SystemTime, u64), String>; 2]>,
+    /// answers of the last successful run
+    answers: Option<Vec<String>>,
+}
+
+impl Watcher {
+    pub fn new(
+        path: PathBuf,
+        readme: PathBuf,
+        solver: Box<dyn Solver>,
+        parts: Vec<Part>,
+    ) -> Watcher {
+        Watcher {
+            path,
+            readme,
+            solver,
+            parts,
+            stamps: None,
+            answers: None,
+        }
+    }
+
+    /// Solve the input and check the examples if either file changed
+    /// since the last poll, returning the report of the run: the diff of
+    /// the answers or the error, then the examples
+    pub fn poll(&mut self) -> Option<String> {
+        let stamps = [stamp(&self.path), stamp(&self.readme)];
+        if self.stamps.as_ref() == Some(&stamps) {
+            return None;
+        }
+        let [input_stamp, _] = stamps.clone();
+        self.stamps = Some(stamps);
+
+        let input = input_stamp.and_then(|_| {
+            fs::read_to_string(&self.path)
+                .map_err(|e| format!("Cannot read {}: {}", self.path.display(), e))
+        });
+        let mut report = match input.map(|input| self.solver.solve(&input, &self.parts)) {
+            Ok(Ok(solved)) => {
+                let answers: Vec<String> = solved.into_iter().map(|s| s.answer).collect();
+                let diff = diff_answers(&self.parts, self.answers.as_deref(), &answers);
+                self.answers = Some(answers);
+                diff
+            }
+            Ok(Err(err)) => err.diagnostic(),
+            Err(err) => format!("Error: {}\n", err),
+        };
+        report += &report_examples(&*self.solver, &self.readme, &self.parts);
+        Some(report)
+    }
+}
+
+/// Watch the input and the README, printing a report after every change;
+/// only stops when interrupted
+pub fn run(
+    path: PathBuf,
+    readme: PathBuf,
+    solver: Box<dyn Solver>,
+    parts: Vec<Part>,
+) -> Result<(), Failure> {
+    println!(
+        "Watching {} and {}, interrupt to stop",
+        path.display(),
+        readme.display()
+    );
+    let mut watcher = Watcher::new(path, readme, solver, parts);
+    let mut runs = 0;
+    loop {
+        match watcher.poll() {
+            Some(report) => {
+                runs += 1;
+                print!("--- run {}\n{}", runs, report);
+            }
+            None => thread::sleep(INTERVAL),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{self, File};
+    use std::path::PathBuf;
+    use std::time::{Duration, SystemTime};
+
+    use common::Part;
+
+    use super::{diff_answers, Watcher};
+    use crate::days::DAYS;
+
+    #[test]
+    fn test_diff_answers() {
+        let parts = [Part::One, Part::Two];
+        let answers = ["3".to_string(), "#.\n.#\n".to_string()];
+        assert_eq!(
+            diff_answers(&parts, None, &answers),
+            "  part1: 3\n  part2:\n  #.\n  .#\n"
+        );
+
+        let previous = ["3".to_string(), "5".to_string()];
+        assert_eq!(
+            diff_answers(&parts, Some(&previous), &answers),
+            "  part1: 3\n- part2: 5\n+ part2:\n+ #.\n+ .#\n"
+        );
+        assert_eq!(
+            diff_answers(&[Part::Two], Some(&["1".to_string()]), &["2".to_string()]),
+            "- part2: 1\n+ part2: 2\n"
+        );
+    }
+
+    #[test]
+    fn test_watcher() {
+        let dir = std::env::temp_dir();
+        let path = dir.join(format!("watch-{}.txt", std::process::id()));
+        let readme = dir.join(format!("watch-{}.md", std::process::id()));
+        // the modification times are set by hand, as they may be too coarse
+        // to tell the writes apart
+        let write = |path: &PathBuf, text: &str, secs: u64| {
+            fs::write(path, text).unwrap();
+            let time = SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+            File::options()
+                .write(true)
+                .open(path)
+                .unwrap()
+                .set_modified(time)
+                .unwrap();
+        };
+        write(
+            &readme,
+            "<!-- example input=\"+1\\n-1\" part1=0 -->\n",
+            1_000,
+        );
+
+        let parts = vec![Part::One, Part::Two];
+        let mut watcher = Watcher::new(path.clone(), readme.clone(), DAYS[0].solver(), parts);
+        let report = watcher.poll().unwrap();
+        assert!(report.starts_with("Error: Cannot read"), "{}", report);
+        assert!(
+            report.ends_with("  examples: 1 answer(s) match\n"),
+            "{}",
+            report
+        );
+        assert_eq!(watcher.poll(), None);
+
+        write(&path, "+1\n-2\n+3\n+1\n", 1_000);
+        assert_eq!(
+            watcher.poll().unwrap(),
+            "  part1: 3\n  part2: 2\n  examples: 1 answer(s) match\n"
+        );
+        assert_eq!(watcher.poll(), None);
+
+        write(&path, "+1\n-2\n+x\n", 2_000);
+        let report = watcher.poll().unwrap();
+        assert!(report.contains("line 3"), "{}", report);
+
+        // compared with the last answers, before the error
+        write(&path, "+1\n-1\n+3\n", 3_000);
+        assert_eq!(
+            watcher.poll().unwrap(),
+            "  part1: 3\n- part2: 2\n+ part2: 0\n  examples: 1 answer(s) match\n"
+        );
+
+        // an edit of the examples alone runs the day again
+        write(
+            &readme,
+            "<!-- example input=\"+1\\n-1\" part1=1 -->\n",
+            2_000,
+        );
+        assert_eq!(
+            watcher.poll().unwrap(),
+            "  part1: 3\n  part2: 0\n! README.md:1 part1: expected 1, got 0\n"
+        );
+        assert_eq!(watcher.poll(), None);
+
+        fs::remove_file(&path).unwrap();
+        fs::remove_file(&readme).unwrap();
+    }
+}
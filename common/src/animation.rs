@@ -0,0 +1,302 @@
+//! Simulations drawn step by step for `--frames` and `--step`
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::{gif, Grid, Image, Rgb};
+
+/// Frames exported at most when the last step is not given, the
+/// simulations of some days never end
+pub const MAX_FRAMES: usize = 1000;
+
+/// A puzzle that evolves one step at a time, drawn after every step
+pub trait Simulation {
+    /// Advance one step, false when the simulation is over and nothing
+    /// changed
+    fn step(&mut self) -> bool;
+
+    /// Picture of the current state, of the same size after every step
+    fn render(&self) -> Image;
+
+    /// The current state as the characters of the puzzle with their
+    /// colours, for the terminal
+    fn text(&self) -> Grid<(char, Rgb)>;
+
+    /// What happened during the last step that is worth stopping at, like
+    /// a crash
+    fn event(&self) -> Option<String> {
+        None
+    }
+}
+
+/// Steps of a simulation to export
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frames {
+    /// Export one step every `every`
+    pub every: usize,
+    /// First step to export, the initial state is step 0
+    pub from: usize,
+    /// Last step to export, otherwise until the end of the simulation or
+    /// `limit` frames
+    pub to: Option<usize>,
+    /// Most frames exported without `to`
+    pub limit: usize,
+    /// Side in pixels of the square drawn for every pixel of the frames
+    pub scale: usize,
+    /// Hundredths of a second of every frame of a GIF
+    pub delay: u16,
+}
+
+impl Default for Frames {
+    fn default() -> Frames {
+        Frames {
+            every: 1,
+            from: 0,
+            to: None,
+            limit: MAX_FRAMES,
+            scale: 1,
+            delay: 10,
+        }
+    }
+}
+
+/// Run the simulation exporting the steps selected by `frames`, the last
+/// step of a simulation that ends is always exported. A `.gif` file gets
+/// the steps as an animation, otherwise the step number is added to the
+/// name of every image: `out.png` becomes `out-000042.png`. Without a last
+/// step the export stops after `frames.limit` frames. Returns the number of
+/// frames
+pub fn export_frames(
+    simulation: &mut dyn Simulation,
+    frames: &Frames,
+    path: &Path,
+) -> Result<usize, String> {
+    let is_gif = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("gif"));
+
+    let mut images = Vec::new();
+    let mut count = 0;
+    let mut export = |step: usize, image: Image| -> Result<(), String> {
+        let image = image.scale(frames.scale);
+        if is_gif {
+            images.push(image);
+            Ok(())
+        } else {
+            image.save(&numbered(path, step))
+        }
+    };
+
+    let mut step = 0;
+    loop {
+        let selected = step >= frames.from && (step - frames.from).is_multiple_of(frames.every);
+        if selected {
+            export(step, simulation.render())?;
+            count += 1;
+        }
+        match frames.to {
+            Some(to) if step >= to => break,
+            None if count >= frames.limit => break,
+            _ => {}
+        }
+        if !simulation.step() {
+            if !selected && step >= frames.from {
+                export(step, simulation.render())?;
+                count += 1;
+            }
+            break;
+        }
+        step += 1;
+    }
+
+    if is_gif {
+        let gif = encode_gif(&images, frames.delay)?;
+        std::fs::write(path, gif).map_err(|e| format!("Cannot write {}: {}", path.display(), e))?;
+    }
+    Ok(count)
+}
+
+// `dir/out.png` numbered `dir/out-000042.png`
+fn numbered(path: &Path, step: usize) -> PathBuf {
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("frame");
+    let name = match path.extension().and_then(|ext| ext.to_str()) {
+        Some(ext) => format!("{}-{:06}.{}", stem, step, ext),
+        None => format!("{}-{:06}", stem, step),
+    };
+    path.with_file_name(name)
+}
+
+// the colours of the frames, at most 256 of them
+fn palette(images: &[Image]) -> Vec<Rgb> {
+    let mut colours: Vec<Rgb> = Vec::new();
+    let mut seen = HashMap::new();
+    let mut last = None;
+    for &colour in images.iter().flat_map(|image| image.pixels()) {
+        // the pixels come in runs of the same colour
+        if last == Some(colour) {
+            continue;
+        }
+        last = Some(colour);
+        seen.entry(colour).or_insert_with(|| colours.push(colour));
+    }
+    if colours.len() <= 256 {
+        return colours;
+    }
+    // too many colours, a cube of 6 levels of red and blue and 7 of green
+    (0..6)
+        .flat_map(|r| (0..7).flat_map(move |g| (0..6).map(move |b| (r, g, b))))
+        .map(|(r, g, b)| Rgb(r * 51, g * 42 + g / 2, b * 51))
+        .collect()
+}
+
+fn nearest(palette: &[Rgb], colour: Rgb) -> u8 {
+    let dist = |p: &Rgb| {
+        let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+        d(p.0, colour.0) + d(p.1, colour.1) + d(p.2, colour.2)
+    };
+    (0..palette.len())
+        .min_by_key(|&n| dist(&palette[n]))
+        .unwrap() as u8
+}
+
+fn encode_gif(images: &[Image], delay: u16) -> Result<Vec<u8>, String> {
+    let first = images.first().ok_or("No step to export")?;
+    let (width, height) = (first.width(), first.height());
+    if width > u16::MAX as usize || height > u16::MAX as usize {
+        return Err(format!(
+            "Frames of {}x{} too large for a GIF",
+            width, height
+        ));
+    }
+    if images
+        .iter()
+        .any(|image| (image.width(), image.height()) != (width, height))
+    {
+        return Err("The frames have different sizes".to_string());
+    }
+
+    let palette = palette(images);
+    let mut indices = HashMap::new();
+    let mut last = (palette[0], 0);
+    let frames: Vec<Vec<u8>> = images
+        .iter()
+        .map(|image| {
+            image
+                .pixels()
+                .iter()
+                .map(|&colour| {
+                    if last.0 != colour {
+                        let index = *indices
+                            .entry(colour)
+                            .or_insert_with(|| nearest(&palette, colour));
+                        last = (colour, index);
+                    }
+                    last.1
+                })
+                .collect()
+        })
+        .collect();
+    Ok(gif::encode(width, height, &palette, &frames, delay))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{export_frames, numbered, palette, Frames, Simulation};
+    use crate::{BoundingBox, Grid, Image, Point, Rgb};
+    use std::path::Path;
+
+    // a dot moving right on a row of pixels, over after `len` steps
+    struct Dot {
+        pos: usize,
+        len: usize,
+    }
+
+    impl Simulation for Dot {
+        fn step(&mut self) -> bool {
+            if self.pos + 1 < self.len {
+                self.pos += 1;
+                true
+            } else {
+                false
+            }
+        }
+
+        fn render(&self) -> Image {
+            let mut image = Image::new(self.len, 1, Rgb::BLACK);
+            image.set(self.pos, 0, Rgb::WHITE);
+            image
+        }
+
+        fn text(&self) -> Grid<(char, Rgb)> {
+            let bounds = BoundingBox::with_size(Point::new(0, 0), self.len, 1);
+            Grid::from_fn(bounds, |p| match p.x as usize == self.pos {
+                true => ('o', Rgb::WHITE),
+                false => ('.', Rgb::BLACK),
+            })
+        }
+    }
+
+    #[test]
+    fn test_numbered() {
+        assert_eq!(
+            numbered(Path::new("dir/out.png"), 42),
+            Path::new("dir/out-000042.png")
+        );
+        assert_eq!(numbered(Path::new("out"), 7), Path::new("out-000007"));
+    }
+
+    #[test]
+    fn test_export_frames() {
+        let dir = std::env::temp_dir().join(format!("frames-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        // steps 2, 5, 8 and the last one, 9
+        let frames = Frames {
+            every: 3,
+            from: 2,
+            scale: 2,
+            ..Frames::default()
+        };
+        let mut dot = Dot { pos: 0, len: 10 };
+        let count = export_frames(&mut dot, &frames, &dir.join("dot.ppm")).unwrap();
+        assert_eq!(count, 4);
+        let ppm = std::fs::read(dir.join("dot-000009.ppm")).unwrap();
+        assert!(ppm.starts_with(b"P6\n20 2\n"));
+        assert!(!dir.join("dot-000003.ppm").exists());
+
+        let frames = Frames {
+            to: Some(4),
+            ..Frames::default()
+        };
+        let mut dot = Dot { pos: 0, len: 10 };
+        let gif = dir.join("dot.gif");
+        assert_eq!(export_frames(&mut dot, &frames, &gif), Ok(5));
+        assert!(std::fs::read(&gif).unwrap().starts_with(b"GIF89a"));
+
+        // steps 0, 2 and 4, then the limit
+        let frames = Frames {
+            every: 2,
+            limit: 3,
+            ..Frames::default()
+        };
+        let mut dot = Dot { pos: 0, len: 10 };
+        assert_eq!(export_frames(&mut dot, &frames, &gif), Ok(3));
+        assert_eq!(dot.pos, 4);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_palette() {
+        let mut image = Image::new(20, 20, Rgb::BLACK);
+        assert_eq!(palette(&[image.clone()]), vec![Rgb::BLACK]);
+
+        for n in 0..400 {
+            image.set(n % 20, n / 20, Rgb((n % 256) as u8, (n / 256) as u8, 0));
+        }
+        let colours = palette(&[image]);
+        assert_eq!(colours.len(), 252);
+        assert!(colours.contains(&Rgb::WHITE));
+    }
+}
@@ -0,0 +1,137 @@
+use std::fmt::Display;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use crate::{Json, Solution};
+
+/// Answer of a part, printed as text with `Display`
+///
+/// Answers made of several values, like a coordinate with a size, also list
+/// them as separate fields for the JSON output so that they do not have to
+/// be parsed back from the text.
+pub trait Answer: Display {
+    /// Named values making up the answer, empty for plain answers
+    fn fields(&self) -> Vec<(&'static str, Json)> {
+        Vec::new()
+    }
+}
+
+macro_rules! plain_answer {
+    ($($t:ty)*) => {
+        $(impl Answer for $t {})*
+    };
+}
+
+plain_answer!(i32 i64 i128 u32 u64 usize String);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub fn number(self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+/// How the answers are printed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// The bare answers, one per line
+    Text,
+    /// A JSON object per line with the day, the part, the answer, its
+    /// fields and the time taken
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Format, String> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("Invalid format {}, expected text or json", s)),
+        }
+    }
+}
+
+/// Answer of a part, rendered, with the time taken to compute it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Solved {
+    pub part: Part,
+    pub answer: String,
+    pub fields: Vec<(&'static str, Json)>,
+    pub elapsed: Duration,
+}
+
+impl Solved {
+    /// `{"day":11,"part":1,"answer":"…","elapsed_ms":…}` followed by the
+    /// fields of the answer
+    pub fn to_json(&self, day: u8) -> Json {
+        let elapsed_ms = self.elapsed.as_secs_f64() * 1000.0;
+        let mut members = vec![
+            ("day", Json::from(day)),
+            ("part", Json::from(self.part.number())),
+            ("answer", Json::from(self.answer.trim_end())),
+            ("elapsed_ms", Json::Number(format!("{:.3}", elapsed_ms))),
+        ];
+        members.extend(self.fields.iter().cloned());
+        Json::object(members)
+    }
+}
+
+/// Compute a part of an already parsed input, timing it
+pub fn solve_part<S: Solution>(solution: &S, input: &S::Input, part: Part) -> Solved {
+    fn solved(part: Part, answer: impl Answer, start: Instant) -> Solved {
+        let elapsed = start.elapsed();
+        Solved {
+            part,
+            answer: answer.to_string(),
+            fields: answer.fields(),
+            elapsed,
+        }
+    }
+
+    let start = Instant::now();
+    match part {
+        Part::One => solved(part, solution.part1(input), start),
+        Part::Two => solved(part, solution.part2(input), start),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{Format, Part, Solved};
+    use crate::Json;
+
+    #[test]
+    fn test_to_json() {
+        let solved = Solved {
+            part: Part::Two,
+            answer: "(230, 272) of size 17 with a total of 125".to_string(),
+            fields: vec![("x", Json::from(230)), ("size", Json::from(17))],
+            elapsed: Duration::from_micros(1500),
+        };
+        assert_eq!(
+            solved.to_json(11).to_string(),
+            "{\"day\":11,\"part\":2,\
+             \"answer\":\"(230, 272) of size 17 with a total of 125\",\
+             \"elapsed_ms\":1.500,\"x\":230,\"size\":17}"
+        );
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!("json".parse(), Ok(Format::Json));
+        assert_eq!("text".parse(), Ok(Format::Text));
+        assert!("yaml".parse::<Format>().is_err());
+    }
+}
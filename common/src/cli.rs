@@ -0,0 +1,372 @@
+//! Command line of the binary of every day
+
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::process;
+
+use crate::{
+    export_frames, interact, parse, read_inputs, set_flags, solve_part, Flag, Format, Frames, Json,
+    Solution, Solved, Source,
+};
+
+// options of the binary of a day, the flags of the puzzle constants are
+// collected with their values
+#[derive(Debug, PartialEq)]
+struct Options {
+    format: Format,
+    sources: Vec<Source>,
+    flags: Vec<(String, String)>,
+    render: Option<PathBuf>,
+    csv: Option<PathBuf>,
+    frames: Option<PathBuf>,
+    steps: Frames,
+    step: bool,
+}
+
+// value of a numeric option, at least `min`
+fn parse_count(arg: &str, value: Option<String>, min: usize) -> Result<usize, String> {
+    let value = value.ok_or_else(|| format!("Missing value for {}", arg))?;
+    match value.parse() {
+        Ok(n) if n >= min => Ok(n),
+        _ => Err(format!(
+            "Invalid value {} for {}, expected a number from {}",
+            value, arg, min
+        )),
+    }
+}
+
+fn parse_options(
+    mut args: impl Iterator<Item = String>,
+    flags: &[Flag],
+) -> Result<Options, String> {
+    let mut options = Options {
+        format: Format::Text,
+        sources: Vec::new(),
+        flags: Vec::new(),
+        render: None,
+        csv: None,
+        frames: None,
+        steps: Frames::default(),
+        step: false,
+    };
+    while let Some(arg) = args.next() {
+        let missing = || format!("Missing value for {}", arg);
+        match arg.as_str() {
+            "--format" => options.format = args.next().ok_or_else(missing)?.parse()?,
+            "--input" => {
+                let path = PathBuf::from(args.next().ok_or_else(missing)?);
+                options.sources.push(Source::File(path));
+            }
+            "--inline" => {
+                let text = args.next().ok_or_else(missing)?;
+                options.sources.push(Source::Inline(text));
+            }
+            "--example" => options.sources.push(Source::Examples),
+            "--render" => options.render = Some(PathBuf::from(args.next().ok_or_else(missing)?)),
+            "--csv" => options.csv = Some(PathBuf::from(args.next().ok_or_else(missing)?)),
+            "--frames" => options.frames = Some(PathBuf::from(args.next().ok_or_else(missing)?)),
+            "--every" => options.steps.every = parse_count(&arg, args.next(), 1)?,
+            "--from" => options.steps.from = parse_count(&arg, args.next(), 0)?,
+            "--to" => options.steps.to = Some(parse_count(&arg, args.next(), 0)?),
+            "--scale" => options.steps.scale = parse_count(&arg, args.next(), 1)?,
+            "--step" => options.step = true,
+            _ if flags.iter().any(|flag| arg == format!("--{}", flag.name)) => {
+                let value = args.next().ok_or_else(missing)?;
+                options.flags.push((arg[2..].to_string(), value));
+            }
+            _ => return Err(format!("Unexpected argument {}", arg)),
+        }
+    }
+    Ok(options)
+}
+
+fn usage<S: Solution>() -> String {
+    let mut usage = format!(
+        "Usage: day{:02} [--input FILE]... [--inline INPUT]... [--example] [--format text|json]\n       \
+         [--render FILE.ppm|png|svg] [--csv FILE]\n       \
+         [--frames FILE.gif|ppm|png|svg] [--every N] [--from STEP] [--to STEP] [--scale N]\n       \
+         [--step]",
+        S::DAY
+    );
+    for flag in S::FLAGS {
+        usage += &format!(" [--{} {}]", flag.name, flag.value);
+    }
+    usage += "\nThe input is read from stdin when no input is given";
+    for flag in S::FLAGS {
+        usage += &format!("\n{}", flag.usage());
+    }
+    usage
+}
+
+// cells of the window of `--step`, from the size of the terminal when the
+// shell exports it, leaving room for the status line and the prompt
+fn window_size() -> (usize, usize) {
+    let var = |name: &str, default: usize| {
+        std::env::var(name)
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .filter(|&n| n > 2)
+            .unwrap_or(default)
+    };
+    (var("COLUMNS", 80), var("LINES", 24) - 2)
+}
+
+// `label part1: answer`, the answer on the following lines when it spans
+// several lines
+fn print_solved(
+    day: u8,
+    solved: &Solved,
+    label: Option<&str>,
+    expected: Option<&str>,
+    format: Format,
+) {
+    let answer = solved.answer.trim_end();
+    let mismatch = expected.filter(|&expected| expected != answer);
+    match format {
+        Format::Json => {
+            let mut json = solved.to_json(day);
+            if let Json::Object(members) = &mut json {
+                if let Some(label) = label {
+                    members.insert(1, ("input".to_string(), Json::from(label)));
+                }
+                if let Some(expected) = expected {
+                    members.push(("expected".to_string(), Json::from(expected)));
+                }
+            }
+            println!("{}", json);
+        }
+        Format::Text => {
+            if let Some(label) = label {
+                let sep = if answer.contains('\n') { '\n' } else { ' ' };
+                print!("{} part{}:{}", label, solved.part.number(), sep);
+            }
+            match mismatch {
+                Some(expected) => println!("{} (expected {})", answer, expected),
+                None => println!("{}", answer),
+            }
+        }
+    }
+}
+
+/// Solve a day and print the answers of both parts on stdout, as text or as
+/// JSON with `--format json`
+///
+/// The puzzle input is read from the files given with `--input`, from the
+/// command line with `--inline`, or from stdin when neither is given;
+/// `--example` solves the worked examples of the README instead. When there
+/// are several inputs the answers are labelled with the input they belong
+/// to. The puzzle constants can be changed with the flags of the day.
+///
+/// An invalid input is reported on stderr pointing at the offending text,
+/// the process then exits with an error once the other inputs are solved.
+///
+/// `--render` draws the only input in an image file, for the days that
+/// can draw their puzzle, `--csv` writes its table for the days that
+/// tabulate it. `--frames` exports the steps of the days that can be
+/// simulated, as an animated GIF or as numbered images, picking one step
+/// every `--every` from `--from` to `--to`, or up to a limit of frames
+/// without `--to`, and scaling the pixels by `--scale`. `--step` steps
+/// through the simulation in the terminal with the commands read from
+/// stdin.
+pub fn run<S: Solution>(mut solution: S) {
+    let options = parse_options(std::env::args().skip(1), S::FLAGS)
+        .and_then(|options| set_flags(&mut solution, &options.flags).map(|_| options))
+        .unwrap_or_else(|err| {
+            eprintln!("Error: {}\n{}", err, usage::<S>());
+            process::exit(2);
+        });
+
+    let inputs = read_inputs(&options.sources, S::README).unwrap_or_else(|err| {
+        eprintln!("Error: {}", err);
+        process::exit(1);
+    });
+
+    if options.render.is_some() && inputs.len() > 1 {
+        eprintln!("Error: --render draws a single input\n{}", usage::<S>());
+        process::exit(2);
+    }
+    if options.csv.is_some() && inputs.len() > 1 {
+        eprintln!("Error: --csv writes a single input\n{}", usage::<S>());
+        process::exit(2);
+    }
+    if options.frames.is_some() && inputs.len() > 1 {
+        eprintln!("Error: --frames draws a single input\n{}", usage::<S>());
+        process::exit(2);
+    }
+    if options.step && (inputs.len() > 1 || options.sources.is_empty()) {
+        eprintln!(
+            "Error: --step reads its commands from stdin, give a single input with --input, \
+             --inline or --example\n{}",
+            usage::<S>()
+        );
+        process::exit(2);
+    }
+    if options.steps.to.is_some_and(|to| to < options.steps.from) {
+        eprintln!("Error: --to before --from\n{}", usage::<S>());
+        process::exit(2);
+    }
+
+    let mut failed = false;
+    for input in &inputs {
+        let label = input.label.as_deref();
+        let parsed = match parse(&solution, &input.text) {
+            Ok(parsed) => parsed,
+            Err(err) => {
+                if let Some(label) = label {
+                    eprintln!("{}:", label);
+                }
+                eprint!("{}", err.diagnostic());
+                failed = true;
+                continue;
+            }
+        };
+
+        for (part, expected) in &input.parts {
+            let solved = solve_part(&solution, &parsed, *part);
+            print_solved(S::DAY, &solved, label, expected.as_deref(), options.format);
+        }
+
+        if let Some(path) = &options.render {
+            let rendered = match solution.render(&parsed) {
+                Some(image) => image.save(path),
+                None => Err(format!("Day {} has nothing to render", S::DAY)),
+            };
+            if let Err(err) = rendered {
+                eprintln!("Error: {}", err);
+                failed = true;
+            }
+        }
+
+        if let Some(path) = &options.csv {
+            let written = solution.table(&parsed).and_then(|table| {
+                fs::write(path, table)
+                    .map_err(|err| format!("Cannot write {}: {}", path.display(), err))
+            });
+            if let Err(err) = written {
+                eprintln!("Error: {}", err);
+                failed = true;
+            }
+        }
+
+        if let Some(path) = &options.frames {
+            let exported = match solution.simulate(&parsed) {
+                Some(mut simulation) => export_frames(simulation.as_mut(), &options.steps, path),
+                None => Err(format!("Day {} has nothing to simulate", S::DAY)),
+            };
+            match exported {
+                Ok(count) if options.steps.to.is_none() && count >= options.steps.limit => {
+                    eprintln!("{} frames exported, the most without --to", count)
+                }
+                Ok(count) => eprintln!("{} frames exported", count),
+                Err(err) => {
+                    eprintln!("Error: {}", err);
+                    failed = true;
+                }
+            }
+        }
+
+        if options.step {
+            if solution.simulate(&parsed).is_none() {
+                eprintln!("Error: Day {} has nothing to simulate", S::DAY);
+                failed = true;
+                continue;
+            }
+            let restart = || solution.simulate(&parsed).unwrap();
+            let stepped = interact(
+                restart,
+                io::stdin().lock(),
+                io::stdout().lock(),
+                window_size(),
+            );
+            if let Err(err) = stepped {
+                eprintln!("Error: {}", err);
+                failed = true;
+            }
+        }
+    }
+
+    if failed {
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_options, Options};
+    use crate::{Flag, Format, Frames, Source};
+
+    #[test]
+    fn test_parse_options() {
+        let flags = [Flag {
+            name: "workers",
+            value: "N",
+            help: "number of workers",
+        }];
+        let args = |args: &[&str]| parse_options(args.iter().map(|arg| arg.to_string()), &flags);
+
+        assert_eq!(
+            args(&["--workers", "2", "--format", "json"]),
+            Ok(Options {
+                format: Format::Json,
+                sources: Vec::new(),
+                flags: vec![("workers".to_string(), "2".to_string())],
+                render: None,
+                csv: None,
+                frames: None,
+                steps: Frames::default(),
+                step: false,
+            })
+        );
+        assert_eq!(
+            args(&[
+                "--input",
+                "a.txt",
+                "--example",
+                "--inline",
+                "18",
+                "--input",
+                "b.txt"
+            ])
+            .map(|options| options.sources),
+            Ok(vec![
+                Source::File("a.txt".into()),
+                Source::Examples,
+                Source::Inline("18".to_string()),
+                Source::File("b.txt".into()),
+            ])
+        );
+        assert_eq!(args(&[]).map(|options| options.format), Ok(Format::Text));
+        assert_eq!(
+            args(&["--render", "out.png"]).map(|options| options.render),
+            Ok(Some("out.png".into()))
+        );
+        assert_eq!(
+            args(&["--csv", "out.csv"]).map(|options| options.csv),
+            Ok(Some("out.csv".into()))
+        );
+        assert_eq!(
+            args(&["--frames", "out.gif", "--every", "5", "--to", "100", "--scale", "3"])
+                .map(|options| (options.frames, options.steps)),
+            Ok((
+                Some("out.gif".into()),
+                Frames {
+                    every: 5,
+                    to: Some(100),
+                    scale: 3,
+                    ..Frames::default()
+                }
+            ))
+        );
+        assert_eq!(args(&["--step"]).map(|options| options.step), Ok(true));
+        assert!(args(&["--every", "0"]).is_err());
+        assert!(args(&["--scale", "x"]).is_err());
+        assert!(args(&["--from"]).is_err());
+        assert!(args(&["--format"]).is_err());
+        assert!(args(&["--workers"]).is_err());
+        assert!(args(&["--inline"]).is_err());
+        assert!(args(&["--render"]).is_err());
+        assert!(args(&["--csv"]).is_err());
+        assert!(args(&["--part", "1"]).is_err());
+    }
+}
@@ -0,0 +1,142 @@
+//! Differential testing of the optimized solvers against naive oracles
+//!
+//! Both are run on generated cases, the first case where they disagree is
+//! shrunk to a smaller one that still shows the disagreement, so that the
+//! report is short enough to be debugged by hand.
+
+use std::fmt::{self, Debug, Display};
+
+use crate::Rng;
+
+/// A case on which the solver and the oracle disagree
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mismatch<T> {
+    /// Seed of the generated case, before it was minimized
+    pub seed: u64,
+    /// The smallest case found still disagreeing
+    pub input: T,
+    /// Answer of the oracle
+    pub expected: String,
+    /// Answer of the solver
+    pub actual: String,
+}
+
+impl<T: Debug> Display for Mismatch<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "mismatch on the case of seed {}, minimized to",
+            self.seed
+        )?;
+        writeln!(f, "{:#?}", self.input)?;
+        writeln!(f, "expected: {}", self.expected)?;
+        write!(f, "actual:   {}", self.actual)
+    }
+}
+
+/// Run the solver and the oracle on the cases generated with the seeds
+/// from 0 to `cases`, returning the first mismatch minimized: the case is
+/// replaced by the first of its `shrink` candidates that still disagrees
+/// until none does. The candidates must be valid inputs of the solver
+pub fn differential<T, A>(
+    cases: u64,
+    mut generate: impl FnMut(&mut Rng) -> T,
+    shrink: impl Fn(&T) -> Vec<T>,
+    oracle: impl Fn(&T) -> A,
+    solver: impl Fn(&T) -> A,
+) -> Result<(), Mismatch<T>>
+where
+    A: PartialEq + Debug,
+{
+    let disagree = |input: &T| {
+        let (expected, actual) = (oracle(input), solver(input));
+        (expected != actual).then_some((expected, actual))
+    };
+
+    for seed in 0..cases {
+        let mut input = generate(&mut Rng::new(seed));
+        let mut answers = match disagree(&input) {
+            Some(answers) => answers,
+            None => continue,
+        };
+        while let Some((smaller, smaller_answers)) = shrink(&input)
+            .into_iter()
+            .find_map(|candidate| disagree(&candidate).map(|answers| (candidate, answers)))
+        {
+            input = smaller;
+            answers = smaller_answers;
+        }
+        return Err(Mismatch {
+            seed,
+            input,
+            expected: format!("{:?}", answers.0),
+            actual: format!("{:?}", answers.1),
+        });
+    }
+    Ok(())
+}
+
+/// The items with a chunk removed, the halves first and then smaller and
+/// smaller chunks down to single items
+pub fn shrink_vec<T: Clone>(items: &[T]) -> Vec<Vec<T>> {
+    let mut candidates = Vec::new();
+    let mut chunk = items.len() / 2;
+    while chunk > 0 {
+        for start in (0..items.len()).step_by(chunk) {
+            let end = (start + chunk).min(items.len());
+            candidates.push([&items[..start], &items[end..]].concat());
+        }
+        chunk /= 2;
+    }
+    if items.len() == 1 {
+        candidates.push(Vec::new());
+    }
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{differential, shrink_vec, Mismatch};
+
+    #[test]
+    fn test_shrink_vec() {
+        let candidates = shrink_vec(&[1, 2, 3, 4]);
+        assert_eq!(candidates[..2], [vec![3, 4], vec![1, 2]]);
+        assert_eq!(candidates.len(), 2 + 4);
+        assert_eq!(shrink_vec(&[1]), vec![Vec::<i32>::new()]);
+        assert!(shrink_vec::<i32>(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_differential() {
+        let generate = |rng: &mut crate::Rng| -> Vec<i64> {
+            (0..rng.range(0..=50)).map(|_| rng.range(-9..=9)).collect()
+        };
+        let sum = |items: &Vec<i64>| items.iter().sum::<i64>();
+        let shrink = |items: &Vec<i64>| shrink_vec(items);
+
+        assert_eq!(differential(50, generate, shrink, sum, sum), Ok(()));
+
+        // a sum forgetting the items after the 3rd, down to 4 items of
+        // which only the last matters
+        let buggy = |items: &Vec<i64>| items.iter().take(3).sum::<i64>();
+        let mismatch = differential(50, generate, shrink, sum, buggy).unwrap_err();
+        assert_eq!(mismatch.input.len(), 4);
+        assert_ne!(mismatch.input[3], 0);
+        assert_eq!(
+            mismatch.to_string().lines().last(),
+            Some(format!("actual:   {}", buggy(&mismatch.input)).as_str())
+        );
+
+        let mismatch = Mismatch {
+            seed: 3,
+            input: 42,
+            expected: "1".to_string(),
+            actual: "2".to_string(),
+        };
+        assert_eq!(
+            mismatch.to_string(),
+            "mismatch on the case of seed 3, minimized to\n42\nexpected: 1\nactual:   2"
+        );
+    }
+}
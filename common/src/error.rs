@@ -0,0 +1,196 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+
+/// Error returned when a puzzle input cannot be parsed
+///
+/// Besides the message the error locates the offending text: the day it was
+/// found in, the line of the input and the span of the line at fault. Line
+/// parsers only know the column, the line and the day are filled in by the
+/// callers with [`ParseError::on_line`] and [`ParseError::with_day`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    message: String,
+    day: Option<u8>,
+    line: Option<usize>,
+    text: String,
+    column: usize,
+    width: usize,
+}
+
+impl ParseError {
+    /// Error not tied to a place in the input, like an empty input
+    pub fn new(message: impl Into<String>) -> ParseError {
+        ParseError {
+            message: message.into(),
+            day: None,
+            line: None,
+            text: String::new(),
+            column: 0,
+            width: 0,
+        }
+    }
+
+    /// Error spanning `width` bytes of a line from the byte offset `column`
+    pub fn at(column: usize, width: usize, message: impl Into<String>) -> ParseError {
+        ParseError {
+            column,
+            width: width.max(1),
+            ..ParseError::new(message)
+        }
+    }
+
+    /// Attach the line, by index from zero, where the error was found
+    pub fn on_line(mut self, idx: usize, text: &str) -> ParseError {
+        self.line = Some(idx + 1);
+        self.text = text.to_string();
+        self
+    }
+
+    /// Attach the day whose input was being parsed
+    pub fn with_day(mut self, day: u8) -> ParseError {
+        self.day = Some(day);
+        self
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn day(&self) -> Option<u8> {
+        self.day
+    }
+
+    /// Line of the error, counting from 1
+    pub fn line(&self) -> Option<usize> {
+        self.line
+    }
+
+    /// Column of the error, counting characters from 1
+    pub fn column(&self) -> Option<usize> {
+        self.line.map(|_| self.prefix().chars().count() + 1)
+    }
+
+    /// Text of the line where the error was found
+    pub fn text(&self) -> Option<&str> {
+        self.line.map(|_| self.text.as_str())
+    }
+
+    // part of the line before the error, the column may point past the end
+    // of the line when something is missing
+    fn prefix(&self) -> &str {
+        &self.text[..self.boundary(self.column)]
+    }
+
+    // offending text, empty when the column is past the end of the line
+    fn span(&self) -> &str {
+        let start = self.prefix().len();
+        &self.text[start..self.boundary(self.column + self.width).max(start)]
+    }
+
+    fn boundary(&self, offset: usize) -> usize {
+        let mut offset = offset.min(self.text.len());
+        while !self.text.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        offset
+    }
+
+    /// Multi-line rendering of the error pointing at the offending text
+    ///
+    /// ```text
+    /// error: expected ` @ `
+    ///  --> day 3, line 2, column 4
+    ///   |
+    /// 2 | #2 x 3,1: 4x4
+    ///   |    ^
+    /// ```
+    pub fn diagnostic(&self) -> String {
+        let mut out = format!("error: {}\n", self.message);
+        let line = match self.line {
+            Some(line) => line,
+            None => {
+                if let Some(day) = self.day {
+                    out += &format!(" --> day {}\n", day);
+                }
+                return out;
+            }
+        };
+
+        let gutter = " ".repeat(line.to_string().len());
+        let carets = self.span().chars().count().max(1);
+        out += &format!("{}--> {}\n", gutter, self.location());
+        out += &format!("{} |\n", gutter);
+        out += &format!("{} | {}\n", line, self.text);
+        out += &format!(
+            "{} | {}{}\n",
+            gutter,
+            " ".repeat(self.prefix().chars().count()),
+            "^".repeat(carets)
+        );
+        out
+    }
+
+    // "day 3, line 2, column 3" with the parts known of the location
+    fn location(&self) -> String {
+        let mut location = Vec::new();
+        if let Some(day) = self.day {
+            location.push(format!("day {}", day));
+        }
+        if let (Some(line), Some(column)) = (self.line, self.column()) {
+            location.push(format!("line {}", line));
+            location.push(format!("column {}", column));
+        }
+        location.join(", ")
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.location().as_str() {
+            "" => write!(f, "{}", self.message),
+            location => write!(f, "{}: {}", location, self.message),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::ParseError;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(ParseError::new("empty input").to_string(), "empty input");
+
+        let err = ParseError::new("empty input").with_day(5);
+        assert_eq!(err.to_string(), "day 5: empty input");
+
+        let err = ParseError::at(3, 1, "expected ` @ `")
+            .on_line(1, "#2 x 3,1: 4x4")
+            .with_day(3);
+        assert_eq!(err.line(), Some(2));
+        assert_eq!(err.column(), Some(4));
+        assert_eq!(err.to_string(), "day 3, line 2, column 4: expected ` @ `");
+    }
+
+    #[test]
+    fn test_diagnostic() {
+        let err = ParseError::at(5, 3, "expected a number")
+            .on_line(9, "#10 @ abc,1: 4x4")
+            .with_day(3);
+        let expected = "error: expected a number\n  \
+                        --> day 3, line 10, column 6\n   \
+                        |\n\
+                        10 | #10 @ abc,1: 4x4\n   \
+                        |      ^^^\n";
+        assert_eq!(err.diagnostic(), expected);
+
+        // missing text is pointed just past the end of the line
+        let err = ParseError::at(7, 1, "expected `x`").on_line(0, "#1 @ 4,");
+        assert!(err.diagnostic().ends_with("1 | #1 @ 4,\n  |        ^\n"));
+
+        let err = ParseError::new("empty input").with_day(5);
+        assert_eq!(err.diagnostic(), "error: empty input\n --> day 5\n");
+    }
+}
@@ -0,0 +1,423 @@
+//! Worked examples marked in the README of every day
+//!
+//! An example is an HTML comment, invisible once the README is rendered,
+//! with the expected answers of the parts it covers. Short inputs are
+//! written in the comment itself, `\n` separating the lines:
+//!
+//! ```text
+//! <!-- example input="+1\n-2\n+3\n+1" part1=3 part2=2 -->
+//! ```
+//!
+//! Otherwise the input is made of the README lines up to the end marker:
+//!
+//! ```text
+//! <!-- example part1=4 part2=3 -->
+//! #1 @ 1,3: 4x4
+//! #2 @ 3,1: 4x4
+//! #3 @ 5,5: 2x2
+//! <!-- end example -->
+//! ```
+//!
+//! When the README already shows the examples, one by line in the puzzle
+//! text, the marker goes around these lines and gives the pattern they
+//! follow instead. Every line up to the end marker is then an example, the
+//! blank ones aside:
+//!
+//! ```text
+//! <!-- example each="{input} results in {part1}" -->
+//!     +1, +1, +1 results in  3
+//!     -1, -2, -3 results in -6
+//! <!-- end example -->
+//! ```
+//!
+//! A `{name}` of the pattern captures the shortest text up to the rest of
+//! the pattern, `{}` matching without capturing; the lines matching none of
+//! the patterns, `each` being repeatable, are errors. The `input`, `part1`
+//! and `part2` values are then templates filled with the captures, trimmed,
+//! `{input}`, `{part1}` and `{part2}` by default when captured. A template
+//! listing one value by line, separated by `|`, covers the answers the
+//! lines state in words.
+//!
+//! Values with spaces are quoted, `\"` and `\\` escape quotes and
+//! backslashes inside the quotes.
+
+use crate::{ParseError, Scanner};
+
+const EXAMPLE: &str = "<!-- example";
+const END: &str = "<!-- end example -->";
+
+/// Input of a worked example with the expected answers
+#[derive(Debug, Clone, PartialEq)]
+pub struct Example {
+    /// Line of the example marker in the README, counting from 1
+    pub line: usize,
+    pub input: String,
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+// value of an attribute, quoted or up to the next whitespace
+fn parse_value(s: &mut Scanner) -> Result<String, ParseError> {
+    if !s.rest().starts_with('"') {
+        let pos = s.pos();
+        return match s.take_while(|c| !c.is_whitespace()) {
+            "" => Err(ParseError::at(pos, 1, "expected a value")),
+            value => Ok(value.to_string()),
+        };
+    }
+
+    s.literal("\"")?;
+    let mut value = String::new();
+    loop {
+        match s.char()? {
+            '"' => return Ok(value),
+            '\\' => {
+                let pos = s.pos();
+                match s.char()? {
+                    'n' => value.push('\n'),
+                    '"' => value.push('"'),
+                    '\\' => value.push('\\'),
+                    c => return Err(ParseError::at(pos, c.len_utf8(), "unknown escape")),
+                }
+            }
+            c => value.push(c),
+        }
+    }
+}
+
+// piece of an `each` pattern or of a template
+#[derive(Debug, Clone, PartialEq)]
+enum Piece {
+    Text(String),
+    /// `{name}`, `None` for `{}`
+    Capture(Option<String>),
+}
+
+fn parse_pieces(pattern: &str) -> Result<Vec<Piece>, String> {
+    let mut pieces = Vec::new();
+    let mut rest = pattern;
+    while !rest.is_empty() {
+        match rest.strip_prefix('{') {
+            Some(after) => {
+                let end = after.find('}').ok_or("unclosed `{`")?;
+                let name = Some(after[..end].to_string()).filter(|name| !name.is_empty());
+                pieces.push(Piece::Capture(name));
+                rest = &after[end + 1..];
+            }
+            None => {
+                let end = rest.find('{').unwrap_or(rest.len());
+                pieces.push(Piece::Text(rest[..end].to_string()));
+                rest = &rest[end..];
+            }
+        }
+    }
+    Ok(pieces)
+}
+
+// whether the line matches the pieces, every capture as short as possible;
+// the named captures are pushed to `captures`
+fn match_pieces<'p, 'a>(
+    pieces: &'p [Piece],
+    line: &'a str,
+    captures: &mut Vec<(&'p str, &'a str)>,
+) -> bool {
+    match pieces.split_first() {
+        None => line.is_empty(),
+        Some((Piece::Text(text), rest)) => line
+            .strip_prefix(text.as_str())
+            .is_some_and(|line| match_pieces(rest, line, captures)),
+        Some((Piece::Capture(name), rest)) => {
+            for end in (1..=line.len()).filter(|&end| line.is_char_boundary(end)) {
+                let count = captures.len();
+                if let Some(name) = name {
+                    captures.push((name, &line[..end]));
+                }
+                if match_pieces(rest, &line[end..], captures) {
+                    return true;
+                }
+                captures.truncate(count);
+            }
+            false
+        }
+    }
+}
+
+// the template of the line number `n` filled with the captures
+fn fill(template: &str, n: usize, captures: &[(&str, &str)]) -> Result<String, String> {
+    // one value by line when listed, as many as the lines
+    let template = if template.contains('|') {
+        template.split('|').nth(n).unwrap_or_default()
+    } else {
+        template
+    };
+    let mut filled = String::new();
+    for piece in parse_pieces(template)? {
+        match piece {
+            Piece::Text(text) => filled += &text,
+            Piece::Capture(name) => {
+                let name = name.unwrap_or_default();
+                match captures.iter().find(|&&(captured, _)| captured == name) {
+                    Some((_, value)) => filled += value.trim(),
+                    None => return Err(format!("nothing captured by `{{{}}}`", name)),
+                }
+            }
+        }
+    }
+    Ok(filled)
+}
+
+// an example marker, with the values of its attributes
+#[derive(Debug, Default)]
+struct Marker {
+    input: Option<String>,
+    part1: Option<String>,
+    part2: Option<String>,
+    each: Vec<Vec<Piece>>,
+}
+
+fn parse_marker(line: &str) -> Result<Marker, ParseError> {
+    let mut marker = Marker::default();
+
+    let mut s = Scanner::new(line);
+    s.literal(EXAMPLE)?;
+    loop {
+        s.skip_whitespace();
+        if s.rest().starts_with("-->") {
+            s.literal("-->")?;
+            s.skip_whitespace();
+            s.end()?;
+            return Ok(marker);
+        }
+
+        let pos = s.pos();
+        let key = s.take_while(|c| c.is_ascii_alphanumeric());
+        s.literal("=")?;
+        let start = s.pos();
+        let value = parse_value(&mut s)?;
+        match key {
+            "input" => marker.input = Some(value),
+            "part1" => marker.part1 = Some(value),
+            "part2" => marker.part2 = Some(value),
+            "each" => {
+                let pattern = parse_pieces(&value)
+                    .map_err(|message| ParseError::at(start, s.pos() - start, message))?;
+                marker.each.push(pattern);
+            }
+            _ => {
+                let message = format!("unknown attribute `{}`", key);
+                return Err(ParseError::at(pos, key.len(), message));
+            }
+        }
+    }
+}
+
+// example of the line number `n` following the marker, from the captures
+// of the first pattern it matches
+fn example_on_line(marker: &Marker, n: usize, text: &str) -> Result<Example, String> {
+    let mut captures = Vec::new();
+    if !marker
+        .each
+        .iter()
+        .any(|pattern| match_pieces(pattern, text, &mut captures))
+    {
+        return Err("line not matching the `each` patterns".to_string());
+    }
+
+    // the value of the attribute, or of the capture of the same name
+    let value = |attribute: &Option<String>, name: &str| -> Result<Option<String>, String> {
+        match attribute {
+            Some(template) => fill(template, n, &captures).map(Some),
+            None if captures.iter().any(|&(captured, _)| captured == name) => {
+                fill(&format!("{{{}}}", name), 0, &captures).map(Some)
+            }
+            None => Ok(None),
+        }
+    };
+    Ok(Example {
+        line: 0,
+        input: value(&marker.input, "input")?.unwrap_or_default(),
+        part1: value(&marker.part1, "part1")?,
+        part2: value(&marker.part2, "part2")?,
+    })
+}
+
+/// Extract the examples marked in a README, in order of appearance
+pub fn extract_examples(readme: &str) -> Result<Vec<Example>, ParseError> {
+    let mut examples = Vec::new();
+    let mut lines = readme.lines().enumerate();
+    while let Some((idx, line)) = lines.next() {
+        if !line.starts_with(EXAMPLE) {
+            continue;
+        }
+
+        let marker = parse_marker(line).map_err(|err| err.on_line(idx, line))?;
+        if marker.each.is_empty() {
+            if let Some(input) = &marker.input {
+                examples.push(Example {
+                    line: idx + 1,
+                    input: input.clone(),
+                    part1: marker.part1,
+                    part2: marker.part2,
+                });
+                continue;
+            }
+        }
+
+        let mut block = Vec::new();
+        loop {
+            match lines.next() {
+                Some((_, END)) => break,
+                Some(next) => block.push(next),
+                None => {
+                    let message = format!("example without `{}`", END);
+                    return Err(ParseError::at(0, line.len(), message).on_line(idx, line));
+                }
+            }
+        }
+
+        if marker.each.is_empty() {
+            examples.push(Example {
+                line: idx + 1,
+                input: block
+                    .iter()
+                    .map(|(_, text)| format!("{}\n", text))
+                    .collect(),
+                part1: marker.part1,
+                part2: marker.part2,
+            });
+            continue;
+        }
+        let texts: Vec<(usize, &str)> = block
+            .into_iter()
+            .filter(|(_, text)| !text.trim().is_empty())
+            .collect();
+        let templates = [&marker.input, &marker.part1, &marker.part2];
+        if let Some(list) = templates
+            .iter()
+            .filter_map(|template| template.as_ref())
+            .find(|template| template.contains('|') && template.split('|').count() != texts.len())
+        {
+            let message = format!("`{}` does not list one value by line", list);
+            return Err(ParseError::at(0, line.len(), message).on_line(idx, line));
+        }
+        for (n, &(idx, text)) in texts.iter().enumerate() {
+            let mut example = example_on_line(&marker, n, text.trim())
+                .map_err(|message| ParseError::at(0, text.len(), message).on_line(idx, text))?;
+            example.line = idx + 1;
+            examples.push(example);
+        }
+    }
+    Ok(examples)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{extract_examples, Example};
+
+    #[test]
+    fn test_extract_examples() {
+        let readme = "For example:\n\
+                      \n\
+                      <!-- example part1=4 part2=3 -->\n\
+                      #1 @ 1,3: 4x4\n\
+                      #2 @ 3,1: 4x4\n\
+                      <!-- end example -->\n\
+                      \n\
+                      <!-- example input=\"+1\\n-2\" part1=\"a \\\"b\\\" c\" -->\n";
+
+        assert_eq!(
+            extract_examples(readme),
+            Ok(vec![
+                Example {
+                    line: 3,
+                    input: "#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\n".to_string(),
+                    part1: Some("4".to_string()),
+                    part2: Some("3".to_string()),
+                },
+                Example {
+                    line: 8,
+                    input: "+1\n-2".to_string(),
+                    part1: Some("a \"b\" c".to_string()),
+                    part2: None,
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_extract_examples_errors() {
+        let err = extract_examples("<!-- example part3=4 -->").unwrap_err();
+        assert_eq!(
+            (err.column(), err.message()),
+            (Some(14), "unknown attribute `part3`")
+        );
+
+        let err = extract_examples("text\n<!-- example part1=4 -->\n#1 @ 1,3: 4x4").unwrap_err();
+        assert_eq!(err.line(), Some(2));
+
+        let err = extract_examples("<!-- example input=\"+1\\t\" -->").unwrap_err();
+        assert_eq!(err.message(), "unknown escape");
+
+        assert!(extract_examples("<!-- example part1= -->").is_err());
+        assert!(extract_examples("<!-- example input=\"+1 -->").is_err());
+    }
+
+    #[test]
+    fn test_extract_examples_each() {
+        let readme = "<!-- example each=\"{input} results in {part1}\" -->\n\
+                      \x20   +1, +1, +1 results in  3\n\
+                      \n\
+                      \x20   -1, -2, -3 results in -6\n\
+                      <!-- end example -->\n\
+                      <!-- example each=\"In {input}, {}\" each=\"{input} is {x},{y}\" \
+                      part2=\"none|({x}, {y})\" -->\n\
+                      In aA, they react.\n\
+                      42 is 1,2,3\n\
+                      <!-- end example -->\n";
+
+        let example =
+            |line: usize, input: &str, part1: Option<&str>, part2: Option<&str>| Example {
+                line,
+                input: input.to_string(),
+                part1: part1.map(str::to_string),
+                part2: part2.map(str::to_string),
+            };
+        assert_eq!(
+            extract_examples(readme),
+            Ok(vec![
+                example(2, "+1, +1, +1", Some("3"), None),
+                example(4, "-1, -2, -3", Some("-6"), None),
+                example(7, "aA", None, Some("none")),
+                example(8, "42", None, Some("(1, 2,3)")),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_extract_examples_each_errors() {
+        let err = extract_examples("<!-- example each=\"{input} is {x\" -->").unwrap_err();
+        assert_eq!((err.column(), err.message()), (Some(19), "unclosed `{`"));
+
+        let readme =
+            "<!-- example each=\"{input} is {part1}\" -->\n1 is 2\n1 was 2\n<!-- end example -->";
+        let err = extract_examples(readme).unwrap_err();
+        assert_eq!(
+            (err.line(), err.message()),
+            (Some(3), "line not matching the `each` patterns")
+        );
+
+        let readme = "<!-- example each=\"{input}\" part1=\"{x}\" -->\n1\n<!-- end example -->";
+        let err = extract_examples(readme).unwrap_err();
+        assert_eq!(
+            (err.line(), err.message()),
+            (Some(2), "nothing captured by `{x}`")
+        );
+
+        let readme = "<!-- example each=\"{input}\" part1=\"1|2\" -->\n1\n<!-- end example -->";
+        let err = extract_examples(readme).unwrap_err();
+        assert_eq!(
+            (err.line(), err.message()),
+            (Some(1), "`1|2` does not list one value by line")
+        );
+    }
+}
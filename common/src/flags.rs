@@ -0,0 +1,49 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::Solution;
+
+/// Command line flag setting a puzzle constant of a day, like
+/// `--workers 5`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Flag {
+    /// Name without the leading dashes
+    pub name: &'static str,
+    /// Placeholder of the value in the usage
+    pub value: &'static str,
+    pub help: &'static str,
+}
+
+impl Flag {
+    /// `--name VALUE  help` line of the usage
+    pub fn usage(&self) -> String {
+        format!(
+            "  {:<24}{}",
+            format!("--{} {}", self.name, self.value),
+            self.help
+        )
+    }
+}
+
+/// Parse the value of a flag, to be used by [`Solution::set_flag`]
+pub fn flag_value<T>(flag: &str, value: &str) -> Result<T, String>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    value
+        .parse()
+        .map_err(|err| format!("Invalid value {} for --{}: {}", value, flag, err))
+}
+
+/// Set the puzzle constants of a solution from `(flag, value)` pairs, the
+/// flags not among the ones of the day are rejected
+pub fn set_flags<S: Solution>(solution: &mut S, flags: &[(String, String)]) -> Result<(), String> {
+    for (flag, value) in flags {
+        if !S::FLAGS.iter().any(|f| f.name == flag) {
+            return Err(format!("Unknown flag --{} for day {}", flag, S::DAY));
+        }
+        solution.set_flag(flag, value)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,244 @@
+//! Animated GIF encoder
+//!
+//! Every frame covers the whole picture and indexes a global colour table
+//! of at most 256 colours. The frames are compressed with the variable
+//! length LZW of GIF, resetting the dictionary when it fills up.
+
+use crate::Rgb;
+
+// codes are at most 12 bits long
+const MAX_CODES: u16 = 4096;
+
+// packs codes of growing width into bytes, least significant bit first
+struct BitWriter {
+    bytes: Vec<u8>,
+    acc: u32,
+    bits: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: u16, width: u32) {
+        self.acc |= (code as u32) << self.bits;
+        self.bits += width;
+        while self.bits >= 8 {
+            self.bytes.push(self.acc as u8);
+            self.acc >>= 8;
+            self.bits -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.bytes.push(self.acc as u8);
+        }
+        self.bytes
+    }
+}
+
+/// LZW codes of the colour indices, `min_code_size` bits being enough for
+/// every index
+pub fn lzw(min_code_size: u8, indices: &[u8]) -> Vec<u8> {
+    let clear = 1u16 << min_code_size;
+    let end = clear + 1;
+    let mut out = BitWriter {
+        bytes: Vec::new(),
+        acc: 0,
+        bits: 0,
+    };
+    let mut width = min_code_size as u32 + 1;
+    let mut next = end + 1;
+    // the code of a string followed by an index, at `code << min_code_size
+    // | index`; 0 when not in the dictionary as it is never such a code
+    let mut dictionary = vec![0u16; (MAX_CODES as usize) << min_code_size];
+    let entry = |prefix: u16, index: u8| ((prefix as usize) << min_code_size) | index as usize;
+
+    out.write(clear, width);
+    let mut indices = indices.iter();
+    let mut prefix = match indices.next() {
+        Some(&first) => first as u16,
+        None => {
+            out.write(end, width);
+            return out.finish();
+        }
+    };
+    for &index in indices {
+        let code = dictionary[entry(prefix, index)];
+        if code != 0 {
+            prefix = code;
+            continue;
+        }
+        out.write(prefix, width);
+        if next < MAX_CODES {
+            dictionary[entry(prefix, index)] = next;
+            // the decoder widens the codes as soon as the next one does
+            // not fit
+            if next == 1 << width && width < 12 {
+                width += 1;
+            }
+            next += 1;
+        } else {
+            out.write(clear, width);
+            dictionary.iter_mut().for_each(|code| *code = 0);
+            width = min_code_size as u32 + 1;
+            next = end + 1;
+        }
+        prefix = index as u16;
+    }
+    out.write(prefix, width);
+    out.write(end, width);
+    out.finish()
+}
+
+// data split in sub-blocks of at most 255 bytes ending with an empty one
+fn push_sub_blocks(gif: &mut Vec<u8>, data: &[u8]) {
+    for block in data.chunks(255) {
+        gif.push(block.len() as u8);
+        gif.extend_from_slice(block);
+    }
+    gif.push(0);
+}
+
+/// GIF looping over the frames, each shown for `delay` hundredths of a
+/// second; the frames are the indices in the palette of their pixels, row
+/// after row
+pub fn encode(
+    width: usize,
+    height: usize,
+    palette: &[Rgb],
+    frames: &[Vec<u8>],
+    delay: u16,
+) -> Vec<u8> {
+    assert!(
+        !palette.is_empty() && palette.len() <= 256,
+        "The palette must have from 1 to 256 colours"
+    );
+    // the colour table has 2^(n + 1) entries
+    let table_bits = (1..=8).find(|bits| 1 << bits >= palette.len()).unwrap();
+    let min_code_size = table_bits.max(2) as u8;
+
+    let mut gif = b"GIF89a".to_vec();
+    gif.extend_from_slice(&(width as u16).to_le_bytes());
+    gif.extend_from_slice(&(height as u16).to_le_bytes());
+    // global colour table, 8 bits per channel
+    gif.push(0x80 | 0x70 | (table_bits - 1) as u8);
+    gif.extend_from_slice(&[0, 0]);
+    for n in 0..1 << table_bits {
+        let Rgb(r, g, b) = palette.get(n).copied().unwrap_or(Rgb::BLACK);
+        gif.extend_from_slice(&[r, g, b]);
+    }
+
+    // loop forever
+    gif.extend_from_slice(&[0x21, 0xff, 11]);
+    gif.extend_from_slice(b"NETSCAPE2.0");
+    gif.extend_from_slice(&[3, 1, 0, 0, 0]);
+
+    for frame in frames {
+        assert_eq!(frame.len(), width * height, "Wrong size of the frame");
+        // graphic control extension with the delay
+        gif.extend_from_slice(&[0x21, 0xf9, 4, 0]);
+        gif.extend_from_slice(&delay.to_le_bytes());
+        gif.extend_from_slice(&[0, 0]);
+
+        // image descriptor of the whole picture
+        gif.push(0x2c);
+        gif.extend_from_slice(&[0, 0, 0, 0]);
+        gif.extend_from_slice(&(width as u16).to_le_bytes());
+        gif.extend_from_slice(&(height as u16).to_le_bytes());
+        gif.push(0);
+
+        gif.push(min_code_size);
+        push_sub_blocks(&mut gif, &lzw(min_code_size, frame));
+    }
+    gif.push(0x3b);
+    gif
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{encode, lzw};
+    use crate::Rgb;
+
+    // decoder of the LZW codes to check the encoder
+    fn unlzw(min_code_size: u8, bytes: &[u8]) -> Vec<u8> {
+        let clear = 1usize << min_code_size;
+        let end = clear + 1;
+        let reset = || -> Vec<Vec<u8>> { (0..clear + 2).map(|n| vec![n as u8]).collect() };
+        let mut table = reset();
+        let mut width = min_code_size as u32 + 1;
+        let (mut acc, mut bits) = (0u32, 0);
+        let mut bytes = bytes.iter();
+        let mut prev: Option<Vec<u8>> = None;
+        let mut out = Vec::new();
+        loop {
+            while bits < width {
+                acc |= (*bytes.next().expect("Missing end code") as u32) << bits;
+                bits += 8;
+            }
+            let code = (acc & ((1 << width) - 1)) as usize;
+            acc >>= width;
+            bits -= width;
+
+            if code == clear {
+                table = reset();
+                width = min_code_size as u32 + 1;
+                prev = None;
+                continue;
+            }
+            if code == end {
+                return out;
+            }
+            let entry = match (table.get(code), &prev) {
+                (Some(entry), _) => entry.clone(),
+                (None, Some(prev)) => {
+                    let mut entry = prev.clone();
+                    entry.push(prev[0]);
+                    entry
+                }
+                (None, None) => panic!("Unknown first code"),
+            };
+            if let Some(mut prev) = prev {
+                if table.len() < 4096 {
+                    prev.push(entry[0]);
+                    table.push(prev);
+                }
+            }
+            if table.len() == 1 << width && width < 12 {
+                width += 1;
+            }
+            out.extend_from_slice(&entry);
+            prev = Some(entry);
+        }
+    }
+
+    #[test]
+    fn test_lzw() {
+        let indices = [1, 1, 1, 1, 1, 2, 2, 2, 2, 2, 1, 1, 1, 1, 1, 2, 2, 2, 2, 2];
+        assert_eq!(unlzw(2, &lzw(2, &indices)), indices);
+        assert_eq!(unlzw(2, &lzw(2, &[])), Vec::<u8>::new());
+
+        // enough codes to fill the dictionary
+        let mut seed = 7u32;
+        let noise: Vec<u8> = (0..50_000)
+            .map(|_| {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                (seed >> 16) as u8
+            })
+            .collect();
+        assert_eq!(unlzw(8, &lzw(8, &noise)), noise);
+        let stripes: Vec<u8> = (0..50_000).map(|n| (n / 7 % 3) as u8).collect();
+        assert_eq!(unlzw(2, &lzw(2, &stripes)), stripes);
+    }
+
+    #[test]
+    fn test_encode() {
+        let palette = [Rgb::BLACK, Rgb::WHITE, Rgb(255, 0, 0)];
+        let gif = encode(2, 2, &palette, &[vec![0, 1, 2, 0], vec![2, 2, 2, 2]], 10);
+        assert_eq!(&gif[..6], b"GIF89a");
+        assert_eq!(&gif[6..10], &[2, 0, 2, 0]);
+        // 4 colours in the table
+        assert_eq!(gif[10], 0xf1);
+        assert_eq!(&gif[13..25], &[0, 0, 0, 255, 255, 255, 255, 0, 0, 0, 0, 0]);
+        assert_eq!(gif.iter().filter(|&&b| b == 0x2c).count(), 2);
+        assert_eq!(gif.last(), Some(&0x3b));
+    }
+}
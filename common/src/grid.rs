@@ -0,0 +1,423 @@
+//! Points, bounding boxes and dense grids of the plane
+//!
+//! The puzzles place things on a plane with x growing to the right and y
+//! growing downwards, the way the inputs are written. Coordinates are
+//! signed so that the same types serve the inputs starting from 0, the
+//! ones starting from 1 and the ones spreading on negative coordinates.
+
+use std::fmt::{self, Display};
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, Sub, SubAssign};
+
+/// Point of the plane, ordered by x and then by y
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Point {
+    #[inline]
+    pub const fn new(x: i32, y: i32) -> Point {
+        Point { x, y }
+    }
+
+    /// Sum of the absolute differences of the coordinates
+    pub fn manhattan(self, other: Point) -> u32 {
+        (self.x - other.x).unsigned_abs() + (self.y - other.y).unsigned_abs()
+    }
+
+    /// The four points sharing a side with this one, in reading order:
+    /// above, left, right and below
+    pub fn neighbours(self) -> [Point; 4] {
+        let Point { x, y } = self;
+        [
+            Point::new(x, y - 1),
+            Point::new(x - 1, y),
+            Point::new(x + 1, y),
+            Point::new(x, y + 1),
+        ]
+    }
+
+    /// The eight points around this one, diagonals included, in reading
+    /// order
+    pub fn neighbours8(self) -> [Point; 8] {
+        let Point { x, y } = self;
+        [
+            Point::new(x - 1, y - 1),
+            Point::new(x, y - 1),
+            Point::new(x + 1, y - 1),
+            Point::new(x - 1, y),
+            Point::new(x + 1, y),
+            Point::new(x - 1, y + 1),
+            Point::new(x, y + 1),
+            Point::new(x + 1, y + 1),
+        ]
+    }
+}
+
+/// `(x, y)`, the way the puzzles write a coordinate
+impl Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl From<(i32, i32)> for Point {
+    fn from((x, y): (i32, i32)) -> Point {
+        Point { x, y }
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    #[inline]
+    fn add(self, other: Point) -> Point {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl AddAssign for Point {
+    #[inline]
+    fn add_assign(&mut self, other: Point) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    #[inline]
+    fn sub(self, other: Point) -> Point {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl SubAssign for Point {
+    #[inline]
+    fn sub_assign(&mut self, other: Point) {
+        *self = *self - other;
+    }
+}
+
+impl Mul<i32> for Point {
+    type Output = Point;
+
+    #[inline]
+    fn mul(self, n: i32) -> Point {
+        Point::new(self.x * n, self.y * n)
+    }
+}
+
+/// Rectangle of the plane with the sides parallel to the axes, both corners
+/// included
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BoundingBox {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl BoundingBox {
+    /// Box from its top-left and bottom-right corners, panics if `min` is
+    /// not above and on the left of `max`
+    pub fn new(min: Point, max: Point) -> BoundingBox {
+        assert!(
+            min.x <= max.x && min.y <= max.y,
+            "Corners {} and {} out of order",
+            min,
+            max
+        );
+        BoundingBox { min, max }
+    }
+
+    /// Box of `width` by `height` points from its top-left corner, panics
+    /// if empty
+    pub fn with_size(min: Point, width: usize, height: usize) -> BoundingBox {
+        assert!(width > 0 && height > 0, "Empty bounding box");
+        let max = min + Point::new(width as i32 - 1, height as i32 - 1);
+        BoundingBox::new(min, max)
+    }
+
+    /// Smallest box containing the points, none when there are no points
+    pub fn from_points(points: impl IntoIterator<Item = Point>) -> Option<BoundingBox> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        Some(
+            points.fold(BoundingBox::new(first, first), |bbox, p| BoundingBox {
+                min: Point::new(bbox.min.x.min(p.x), bbox.min.y.min(p.y)),
+                max: Point::new(bbox.max.x.max(p.x), bbox.max.y.max(p.y)),
+            }),
+        )
+    }
+
+    #[inline]
+    pub fn width(&self) -> usize {
+        (self.max.x - self.min.x) as usize + 1
+    }
+
+    #[inline]
+    pub fn height(&self) -> usize {
+        (self.max.y - self.min.y) as usize + 1
+    }
+
+    /// Number of points in the box
+    pub fn area(&self) -> usize {
+        self.width() * self.height()
+    }
+
+    #[inline]
+    pub fn contains(&self, p: Point) -> bool {
+        (self.min.x..=self.max.x).contains(&p.x) && (self.min.y..=self.max.y).contains(&p.y)
+    }
+
+    /// Whether the point is on one of the sides of the box
+    pub fn is_on_border(&self, p: Point) -> bool {
+        p.x == self.min.x || p.y == self.min.y || p.x == self.max.x || p.y == self.max.y
+    }
+
+    /// Box grown by `n` points on every side, shrunk if `n` is negative;
+    /// panics if nothing is left
+    pub fn grow(&self, n: i32) -> BoundingBox {
+        BoundingBox::new(self.min - Point::new(n, n), self.max + Point::new(n, n))
+    }
+
+    /// Index of the point when the points of the box are listed row after
+    /// row
+    #[inline]
+    pub fn row_major(&self, p: Point) -> Option<usize> {
+        // a point on the left or above wraps to a large offset
+        let offset = p - self.min;
+        let (x, y) = (offset.x as usize, offset.y as usize);
+        if x < self.width() && y < self.height() {
+            Some(y * self.width() + x)
+        } else {
+            None
+        }
+    }
+
+    /// Index of the point when the points of the box are listed column
+    /// after column
+    #[inline]
+    pub fn column_major(&self, p: Point) -> Option<usize> {
+        let offset = p - self.min;
+        let (x, y) = (offset.x as usize, offset.y as usize);
+        if x < self.width() && y < self.height() {
+            Some(x * self.height() + y)
+        } else {
+            None
+        }
+    }
+
+    /// Points of the box row after row, in reading order
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let BoundingBox { min, max } = *self;
+        (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| Point::new(x, y)))
+    }
+
+    /// Points of the box column after column
+    pub fn points_by_column(&self) -> impl Iterator<Item = Point> {
+        let BoundingBox { min, max } = *self;
+        (min.x..=max.x).flat_map(move |x| (min.y..=max.y).map(move |y| Point::new(x, y)))
+    }
+}
+
+/// Value for every point of a bounding box, stored row after row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    bounds: BoundingBox,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Grid with the same value everywhere
+    pub fn new(bounds: BoundingBox, value: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid {
+            bounds,
+            cells: vec![value; bounds.area()],
+        }
+    }
+
+    /// Grid with the value of every point computed from the point
+    pub fn from_fn(bounds: BoundingBox, mut f: impl FnMut(Point) -> T) -> Grid<T> {
+        let mut cells = Vec::with_capacity(bounds.area());
+        for y in bounds.min.y..=bounds.max.y {
+            for x in bounds.min.x..=bounds.max.x {
+                cells.push(f(Point::new(x, y)));
+            }
+        }
+        Grid { bounds, cells }
+    }
+
+    pub fn bounds(&self) -> BoundingBox {
+        self.bounds
+    }
+
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.bounds.width()
+    }
+
+    #[inline]
+    pub fn height(&self) -> usize {
+        self.bounds.height()
+    }
+
+    #[inline]
+    pub fn contains(&self, p: Point) -> bool {
+        self.bounds.contains(p)
+    }
+
+    #[inline]
+    pub fn get(&self, p: Point) -> Option<&T> {
+        self.bounds.row_major(p).map(|idx| &self.cells[idx])
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+        self.bounds
+            .row_major(p)
+            .map(move |idx| &mut self.cells[idx])
+    }
+
+    /// Points with their values in reading order
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.bounds.points().zip(self.cells.iter())
+    }
+
+    /// Values row after row
+    pub fn values(&self) -> &[T] {
+        &self.cells
+    }
+
+    /// Values column after column
+    pub fn values_by_column(&self) -> impl Iterator<Item = &T> {
+        self.bounds.points_by_column().map(move |p| &self[p])
+    }
+
+    /// Values of every row, from the top
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width())
+    }
+
+    /// Neighbours of a point sharing a side with it, inside the grid
+    pub fn neighbours(&self, p: Point) -> impl Iterator<Item = (Point, &T)> {
+        IntoIterator::into_iter(p.neighbours())
+            .filter_map(move |n| self.get(n).map(|value| (n, value)))
+    }
+
+    /// Neighbours of a point, diagonals included, inside the grid
+    pub fn neighbours8(&self, p: Point) -> impl Iterator<Item = (Point, &T)> {
+        IntoIterator::into_iter(p.neighbours8())
+            .filter_map(move |n| self.get(n).map(|value| (n, value)))
+    }
+
+    /// Grid of the same points with the values mapped
+    pub fn map<U>(&self, mut f: impl FnMut(Point, &T) -> U) -> Grid<U> {
+        Grid {
+            bounds: self.bounds,
+            cells: self.iter().map(|(p, value)| f(p, value)).collect(),
+        }
+    }
+}
+
+// kept out of the indexing that has to stay small to be inlined
+#[cold]
+#[inline(never)]
+fn outside_of_grid(p: Point) -> ! {
+    panic!("Point {} outside of the grid", p)
+}
+
+/// Value of a point of the grid, panics if the point is outside
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, p: Point) -> &T {
+        match self.bounds.row_major(p) {
+            Some(idx) => &self.cells[idx],
+            None => outside_of_grid(p),
+        }
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    #[inline]
+    fn index_mut(&mut self, p: Point) -> &mut T {
+        match self.bounds.row_major(p) {
+            Some(idx) => &mut self.cells[idx],
+            None => outside_of_grid(p),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BoundingBox, Grid, Point};
+
+    #[test]
+    fn test_point() {
+        let p = Point::new(3, -2);
+        assert_eq!(p + Point::new(1, 1), Point::new(4, -1));
+        assert_eq!(p - Point::new(1, 1), Point::new(2, -3));
+        assert_eq!(p * 2, Point::new(6, -4));
+        assert_eq!(p.manhattan(Point::new(-1, 1)), 7);
+        assert_eq!(p.to_string(), "(3, -2)");
+        assert_eq!(p.neighbours()[0], Point::new(3, -3));
+        assert_eq!(p.neighbours8().len(), 8);
+        assert!(Point::new(1, 9) < Point::new(2, 0));
+    }
+
+    #[test]
+    fn test_bounding_box() {
+        let points = vec![Point::new(1, -1), Point::new(-2, 3), Point::new(0, 0)];
+        let bbox = BoundingBox::from_points(points).unwrap();
+        assert_eq!(bbox, BoundingBox::new(Point::new(-2, -1), Point::new(1, 3)));
+        assert_eq!((bbox.width(), bbox.height(), bbox.area()), (4, 5, 20));
+        assert!(bbox.contains(Point::new(-2, 3)));
+        assert!(!bbox.contains(Point::new(2, 3)));
+        assert!(bbox.is_on_border(Point::new(0, -1)));
+        assert!(!bbox.is_on_border(Point::new(0, 0)));
+        assert_eq!(BoundingBox::from_points(vec![]), None);
+
+        let bbox = BoundingBox::with_size(Point::new(1, 1), 3, 2);
+        assert_eq!(bbox.max, Point::new(3, 2));
+        assert_eq!(bbox.grow(1).min, Point::new(0, 0));
+        assert_eq!(bbox.row_major(Point::new(2, 2)), Some(4));
+        assert_eq!(bbox.column_major(Point::new(2, 2)), Some(3));
+        assert_eq!(bbox.row_major(Point::new(0, 2)), None);
+
+        let points: Vec<_> = bbox.points().collect();
+        assert_eq!(points[1], Point::new(2, 1));
+        let points: Vec<_> = bbox.points_by_column().collect();
+        assert_eq!(points[1], Point::new(1, 2));
+    }
+
+    #[test]
+    fn test_grid() {
+        let bbox = BoundingBox::with_size(Point::new(-1, 0), 3, 2);
+        let mut grid = Grid::from_fn(bbox, |p| p.x * 10 + p.y);
+        assert_eq!(grid.values(), &[-10, 0, 10, -9, 1, 11]);
+        assert_eq!(
+            grid.values_by_column().copied().collect::<Vec<_>>(),
+            vec![-10, -9, 0, 1, 10, 11]
+        );
+        assert_eq!(grid.rows().nth(1), Some(&[-9, 1, 11][..]));
+
+        grid[Point::new(0, 1)] = 5;
+        assert_eq!(grid.get(Point::new(0, 1)), Some(&5));
+        assert_eq!(grid.get(Point::new(2, 1)), None);
+
+        let neighbours: Vec<_> = grid.neighbours(Point::new(-1, 0)).collect();
+        assert_eq!(
+            neighbours,
+            vec![(Point::new(0, 0), &0), (Point::new(-1, 1), &-9)]
+        );
+        assert_eq!(grid.neighbours8(Point::new(0, 0)).count(), 5);
+
+        let grid = grid.map(|_, value| *value > 0);
+        assert_eq!(grid.iter().filter(|(_, positive)| **positive).count(), 3);
+    }
+}
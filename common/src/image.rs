@@ -0,0 +1,290 @@
+//! Pictures of the puzzles for `--render`
+//!
+//! The days draw on an [`Image`] of RGB pixels that is written as PPM, PNG
+//! or SVG depending on the extension of the file.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use crate::{png, Grid, Point};
+
+/// Colour of a pixel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+
+    /// Colour `t` of the way towards `other`, `t` from 0 to 1
+    pub fn mix(self, other: Rgb, t: f64) -> Rgb {
+        let t = t.clamp(0.0, 1.0);
+        let channel = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+        Rgb(
+            channel(self.0, other.0),
+            channel(self.1, other.1),
+            channel(self.2, other.2),
+        )
+    }
+
+    /// Colour of a heatmap from blue for 0 through green to red for 1
+    pub fn heat(t: f64) -> Rgb {
+        let (blue, green, red) = (Rgb(40, 60, 200), Rgb(60, 190, 90), Rgb(220, 50, 40));
+        let t = t.clamp(0.0, 1.0);
+        if t < 0.5 {
+            blue.mix(green, t * 2.0)
+        } else {
+            green.mix(red, t * 2.0 - 1.0)
+        }
+    }
+
+    /// The `n`-th of a sequence of colours telling apart neighbouring
+    /// areas, the hue turns by the golden angle every step
+    pub fn palette(n: usize) -> Rgb {
+        let hue = (n as f64 * 137.508) % 360.0;
+        let sector = hue / 60.0;
+        // saturation 0.6 and value 0.9
+        let (max, min) = (0.9, 0.9 * 0.4);
+        let mid = min + (max - min) * (1.0 - (sector % 2.0 - 1.0).abs());
+        let (r, g, b) = match sector as u32 {
+            0 => (max, mid, min),
+            1 => (mid, max, min),
+            2 => (min, max, mid),
+            3 => (min, mid, max),
+            4 => (mid, min, max),
+            _ => (max, min, mid),
+        };
+        let channel = |c: f64| (c * 255.0).round() as u8;
+        Rgb(channel(r), channel(g), channel(b))
+    }
+
+    fn hex(self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+/// Rectangle of pixels, stored row after row from the top-left corner
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Image {
+    /// Image filled with the background colour, panics if empty
+    pub fn new(width: usize, height: usize, background: Rgb) -> Image {
+        assert!(width > 0 && height > 0, "Empty image");
+        Image {
+            width,
+            height,
+            pixels: vec![background; width * height],
+        }
+    }
+
+    /// One pixel for every point of the grid, coloured by `colour`
+    pub fn from_grid<T>(grid: &Grid<T>, mut colour: impl FnMut(Point, &T) -> Rgb) -> Image {
+        Image {
+            width: grid.width(),
+            height: grid.height(),
+            pixels: grid.iter().map(|(p, value)| colour(p, value)).collect(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The pixels row after row
+    pub fn pixels(&self) -> &[Rgb] {
+        &self.pixels
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<Rgb> {
+        if x < self.width && y < self.height {
+            Some(self.pixels[y * self.width + x])
+        } else {
+            None
+        }
+    }
+
+    /// Colour a pixel, the ones outside of the image are ignored
+    pub fn set(&mut self, x: usize, y: usize, colour: Rgb) {
+        if x < self.width && y < self.height {
+            self.pixels[y * self.width + x] = colour;
+        }
+    }
+
+    /// Colour the pixels of a rectangle, clipped to the image
+    pub fn fill(&mut self, x: usize, y: usize, width: usize, height: usize, colour: Rgb) {
+        for y in y..(y + height).min(self.height) {
+            for x in x..(x + width).min(self.width) {
+                self.pixels[y * self.width + x] = colour;
+            }
+        }
+    }
+
+    /// Image with every pixel turned in a square of `factor` pixels
+    pub fn scale(&self, factor: usize) -> Image {
+        assert!(factor > 0, "Cannot scale by 0");
+        let width = self.width * factor;
+        let mut pixels = Vec::with_capacity(width * self.height * factor);
+        for row in self.pixels.chunks(self.width) {
+            let start = pixels.len();
+            for &p in row {
+                pixels.extend(std::iter::repeat_n(p, factor));
+            }
+            for _ in 1..factor {
+                pixels.extend_from_within(start..start + width);
+            }
+        }
+        Image {
+            width,
+            height: self.height * factor,
+            pixels,
+        }
+    }
+
+    /// Binary PPM, the simplest format that image viewers read
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let mut ppm = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        ppm.extend(self.pixels.iter().flat_map(|p| [p.0, p.1, p.2]));
+        ppm
+    }
+
+    pub fn to_png(&self) -> Vec<u8> {
+        let rgb: Vec<u8> = self.pixels.iter().flat_map(|p| [p.0, p.1, p.2]).collect();
+        png::encode(self.width, self.height, &rgb)
+    }
+
+    /// SVG with a rectangle for every run of pixels of the same colour on
+    /// a row, drawn on the most common colour
+    pub fn to_svg(&self) -> String {
+        let mut counts = HashMap::new();
+        for p in &self.pixels {
+            *counts.entry(*p).or_insert(0) += 1;
+        }
+        let background = counts
+            .into_iter()
+            .max_by_key(|&(colour, n)| (n, colour.0, colour.1, colour.2))
+            .map(|(colour, _)| colour)
+            .unwrap_or(Rgb::BLACK);
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" \
+             viewBox=\"0 0 {w} {h}\" shape-rendering=\"crispEdges\">\n\
+             <rect width=\"{w}\" height=\"{h}\" fill=\"{}\"/>\n",
+            background.hex(),
+            w = self.width,
+            h = self.height
+        );
+        for (y, row) in self.pixels.chunks(self.width).enumerate() {
+            let mut x = 0;
+            while x < row.len() {
+                let run = row[x..].iter().take_while(|&&p| p == row[x]).count();
+                if row[x] != background {
+                    writeln!(
+                        svg,
+                        "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"1\" fill=\"{}\"/>",
+                        x,
+                        y,
+                        run,
+                        row[x].hex()
+                    )
+                    .unwrap();
+                }
+                x += run;
+            }
+        }
+        svg += "</svg>\n";
+        svg
+    }
+
+    /// Write the image in the format of the extension of the file: `.ppm`,
+    /// `.png` or `.svg`
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let extension = path.extension().and_then(|ext| ext.to_str());
+        let bytes = match extension.map(|ext| ext.to_ascii_lowercase()).as_deref() {
+            Some("ppm") => self.to_ppm(),
+            Some("png") => self.to_png(),
+            Some("svg") => self.to_svg().into_bytes(),
+            _ => {
+                return Err(format!(
+                    "Unknown image format of {}, expected .ppm, .png or .svg",
+                    path.display()
+                ))
+            }
+        };
+        fs::write(path, bytes).map_err(|e| format!("Cannot write {}: {}", path.display(), e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Image, Rgb};
+    use crate::{BoundingBox, Grid, Point};
+    use std::path::Path;
+
+    #[test]
+    fn test_colours() {
+        assert_eq!(Rgb::BLACK.mix(Rgb::WHITE, 0.5), Rgb(128, 128, 128));
+        assert_eq!(Rgb::heat(0.0), Rgb(40, 60, 200));
+        assert_eq!(Rgb::heat(2.0), Rgb(220, 50, 40));
+        assert_eq!(Rgb::palette(0), Rgb(230, 92, 92));
+        assert_ne!(Rgb::palette(1), Rgb::palette(2));
+    }
+
+    #[test]
+    fn test_draw() {
+        let bounds = BoundingBox::with_size(Point::new(-1, -1), 3, 2);
+        let grid = Grid::from_fn(bounds, |p| p.x == p.y);
+        let mut image = Image::from_grid(
+            &grid,
+            |_, &diagonal| {
+                if diagonal {
+                    Rgb::WHITE
+                } else {
+                    Rgb::BLACK
+                }
+            },
+        );
+        assert_eq!((image.width(), image.height()), (3, 2));
+        assert_eq!(image.get(0, 0), Some(Rgb::WHITE));
+        assert_eq!(image.get(1, 1), Some(Rgb::WHITE));
+        assert_eq!(image.get(3, 0), None);
+
+        image.fill(1, 0, 5, 5, Rgb(1, 2, 3));
+        assert_eq!(image.get(2, 1), Some(Rgb(1, 2, 3)));
+        assert_eq!(image.get(0, 1), Some(Rgb::BLACK));
+
+        let image = image.scale(2);
+        assert_eq!((image.width(), image.height()), (6, 4));
+        assert_eq!(image.get(1, 1), Some(Rgb::WHITE));
+        assert_eq!(image.get(2, 0), Some(Rgb(1, 2, 3)));
+    }
+
+    #[test]
+    fn test_encode() {
+        let mut image = Image::new(3, 1, Rgb::WHITE);
+        image.set(1, 0, Rgb(255, 0, 0));
+        image.set(2, 0, Rgb(255, 0, 0));
+
+        assert_eq!(
+            image.to_ppm(),
+            b"P6\n3 1\n255\n\xff\xff\xff\xff\x00\x00\xff\x00\x00".to_vec()
+        );
+        assert_eq!(&image.to_png()[1..4], b"PNG");
+
+        let svg = image.to_svg();
+        assert!(svg.contains("<rect width=\"3\" height=\"1\" fill=\"#ff0000\"/>"));
+        assert!(svg.contains("<rect x=\"0\" y=\"0\" width=\"1\" height=\"1\" fill=\"#ffffff\"/>"));
+
+        assert!(image.save(Path::new("picture.gif")).is_err());
+    }
+}
@@ -0,0 +1,129 @@
+use std::fs;
+use std::io::{self, Read};
+use std::path::PathBuf;
+
+use crate::{extract_examples, Part};
+
+/// Where a puzzle input comes from
+#[derive(Debug, Clone, PartialEq)]
+pub enum Source {
+    Stdin,
+    File(PathBuf),
+    /// Input given on the command line
+    Inline(String),
+    /// The worked examples marked in the README of the day
+    Examples,
+}
+
+/// Puzzle input read from its source, with the parts to solve
+#[derive(Debug, Clone, PartialEq)]
+pub struct Input {
+    /// Name of the input in the output, missing when it is the only one
+    pub label: Option<String>,
+    pub text: String,
+    /// Parts to solve with their expected answer, known for the examples
+    /// that are only solved for the parts they have an answer for
+    pub parts: Vec<(Part, Option<String>)>,
+}
+
+impl Input {
+    fn new(label: String, text: String) -> Input {
+        Input {
+            label: Some(label),
+            text,
+            parts: vec![(Part::One, None), (Part::Two, None)],
+        }
+    }
+}
+
+/// Read the inputs of the sources in order, stdin when there are none;
+/// `readme` is where the examples are taken from. The inputs are labelled
+/// unless a single one is read
+pub fn read_inputs(sources: &[Source], readme: &str) -> Result<Vec<Input>, String> {
+    let stdin = [Source::Stdin];
+    let sources = if sources.is_empty() { &stdin } else { sources };
+
+    let mut inputs = Vec::new();
+    let mut inline = 0;
+    for source in sources {
+        match source {
+            Source::Stdin => {
+                let mut text = String::new();
+                io::stdin()
+                    .read_to_string(&mut text)
+                    .map_err(|e| format!("Cannot read stdin: {}", e))?;
+                inputs.push(Input::new("stdin".to_string(), text));
+            }
+            Source::File(path) => {
+                let text = fs::read_to_string(path)
+                    .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+                inputs.push(Input::new(path.display().to_string(), text));
+            }
+            Source::Inline(text) => {
+                inline += 1;
+                inputs.push(Input::new(format!("inline {}", inline), text.clone()));
+            }
+            Source::Examples => {
+                let examples = extract_examples(readme)
+                    .map_err(|err| format!("Invalid example in the README, {}", err))?;
+                if examples.is_empty() {
+                    return Err("No worked example in the README".to_string());
+                }
+                inputs.extend(examples.into_iter().map(|example| {
+                    Input {
+                        label: Some(format!("example at README.md:{}", example.line)),
+                        text: example.input,
+                        parts: vec![(Part::One, example.part1), (Part::Two, example.part2)]
+                            .into_iter()
+                            .filter(|(_, expected)| expected.is_some())
+                            .collect(),
+                    }
+                }));
+            }
+        }
+    }
+
+    if let [input] = &mut inputs[..] {
+        if input.parts.iter().all(|(_, expected)| expected.is_none()) {
+            input.label = None;
+        }
+    }
+    Ok(inputs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_inputs, Source};
+    use crate::Part;
+
+    #[test]
+    fn test_read_inputs() {
+        let inputs = read_inputs(&[Source::Inline("18".to_string())], "").unwrap();
+        assert_eq!(inputs.len(), 1);
+        assert_eq!(
+            (inputs[0].label.as_deref(), inputs[0].text.as_str()),
+            (None, "18")
+        );
+
+        let sources = [
+            Source::Inline("18".to_string()),
+            Source::Inline("42".to_string()),
+        ];
+        let inputs = read_inputs(&sources, "").unwrap();
+        assert_eq!(inputs[1].label.as_deref(), Some("inline 2"));
+        assert_eq!(inputs[1].parts.len(), 2);
+    }
+
+    #[test]
+    fn test_read_examples() {
+        let readme = "Some text\n\
+                      <!-- example input=\"+1\\n+1\" part1=2 -->\n";
+        let inputs = read_inputs(&[Source::Examples], readme).unwrap();
+        assert_eq!(inputs[0].label.as_deref(), Some("example at README.md:2"));
+        assert_eq!(inputs[0].text, "+1\n+1");
+        assert_eq!(inputs[0].parts, vec![(Part::One, Some("2".to_string()))]);
+
+        assert!(read_inputs(&[Source::Examples], "no examples").is_err());
+        assert!(read_inputs(&[Source::File("missing/input".into())], "").is_err());
+    }
+}
@@ -0,0 +1,151 @@
+use std::fmt::{self, Display, Write};
+
+/// Minimal JSON value, just enough to print the answers as one object per
+/// line for the tools scraping the output
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    /// Number already formatted, integers are kept exact
+    Number(String),
+    String(String),
+    Array(Vec<Json>),
+    /// Members in insertion order
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// Object from its members, in order
+    pub fn object<K: Into<String>>(members: impl IntoIterator<Item = (K, Json)>) -> Json {
+        Json::Object(
+            members
+                .into_iter()
+                .map(|(key, value)| (key.into(), value))
+                .collect(),
+        )
+    }
+}
+
+macro_rules! json_from_integer {
+    ($($t:ty)*) => {
+        $(
+            impl From<$t> for Json {
+                fn from(n: $t) -> Json {
+                    Json::Number(n.to_string())
+                }
+            }
+        )*
+    };
+}
+
+json_from_integer!(i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize);
+
+impl From<f64> for Json {
+    fn from(n: f64) -> Json {
+        if n.is_finite() {
+            Json::Number(n.to_string())
+        } else {
+            Json::Null
+        }
+    }
+}
+
+impl From<bool> for Json {
+    fn from(b: bool) -> Json {
+        Json::Bool(b)
+    }
+}
+
+impl From<&str> for Json {
+    fn from(s: &str) -> Json {
+        Json::String(s.to_string())
+    }
+}
+
+impl From<String> for Json {
+    fn from(s: String) -> Json {
+        Json::String(s)
+    }
+}
+
+impl<T: Into<Json>> From<Option<T>> for Json {
+    fn from(value: Option<T>) -> Json {
+        value.map_or(Json::Null, Into::into)
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter, s: &str) -> fmt::Result {
+    f.write_char('"')?;
+    for c in s.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_char('"')
+}
+
+/// Compact serialization, without whitespace
+impl Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Json::Null => f.write_str("null"),
+            Json::Bool(b) => write!(f, "{}", b),
+            Json::Number(n) => f.write_str(n),
+            Json::String(s) => write_string(f, s),
+            Json::Array(values) => {
+                f.write_char('[')?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        f.write_char(',')?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                f.write_char(']')
+            }
+            Json::Object(members) => {
+                f.write_char('{')?;
+                for (i, (key, value)) in members.iter().enumerate() {
+                    if i > 0 {
+                        f.write_char(',')?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                f.write_char('}')
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Json;
+
+    #[test]
+    fn test_display() {
+        let json = Json::object(vec![
+            ("day", Json::from(11)),
+            ("answer", Json::from("(20, 51)")),
+            ("size", Json::from(None::<usize>)),
+            ("ok", Json::from(true)),
+            ("xs", Json::Array(vec![Json::from(-1), Json::from(2.5)])),
+        ]);
+        assert_eq!(
+            json.to_string(),
+            r#"{"day":11,"answer":"(20, 51)","size":null,"ok":true,"xs":[-1,2.5]}"#
+        );
+    }
+
+    #[test]
+    fn test_escapes() {
+        let json = Json::from("a \"b\"\\\n#.\u{1}");
+        assert_eq!(json.to_string(), r#""a \"b\"\\\n#.\u0001""#);
+        assert_eq!(Json::from(f64::NAN), Json::Null);
+    }
+}
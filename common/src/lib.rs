@@ -0,0 +1,91 @@
+//! Pieces shared by the days of the calendar
+
+mod animation;
+mod answer;
+mod cli;
+mod differential;
+mod error;
+mod examples;
+mod flags;
+mod gif;
+mod grid;
+mod image;
+mod input;
+mod json;
+mod parse;
+mod png;
+mod rng;
+mod terminal;
+
+pub use animation::{export_frames, Frames, Simulation};
+pub use answer::{solve_part, Answer, Format, Part, Solved};
+pub use cli::run;
+pub use differential::{differential, shrink_vec, Mismatch};
+pub use error::ParseError;
+pub use examples::{extract_examples, Example};
+pub use flags::{flag_value, set_flags, Flag};
+pub use grid::{BoundingBox, Grid, Point};
+pub use image::{Image, Rgb};
+pub use input::{read_inputs, Input, Source};
+pub use json::Json;
+pub use parse::{parse_lines, single_line, Scanner};
+pub use rng::Rng;
+pub use terminal::{interact, Stepper};
+
+/// A day of the calendar split in its phases: the puzzle input is parsed
+/// once and both parts are computed from the parsed input
+pub trait Solution {
+    /// Day of the calendar, from 1 to 25
+    const DAY: u8;
+
+    type Input;
+    type Part1: Answer;
+    type Part2: Answer;
+
+    /// README of the day with the worked examples marked, see
+    /// [`extract_examples`]
+    const README: &'static str = "";
+
+    /// Flags setting the puzzle constants of the day, the defaults are the
+    /// values of the puzzle
+    const FLAGS: &'static [Flag] = &[];
+
+    /// Set the constant of one of `FLAGS`, named without the dashes
+    fn set_flag(&mut self, flag: &str, _value: &str) -> Result<(), String> {
+        Err(format!("Unknown flag --{}", flag))
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Input, ParseError>;
+
+    fn part1(&self, input: &Self::Input) -> Self::Part1;
+
+    fn part2(&self, input: &Self::Input) -> Self::Part2;
+
+    /// Random valid input made up with `rng`, larger as `size` grows; for
+    /// most days `size` is the number of lines
+    fn generate(&self, rng: &mut Rng, size: usize) -> String;
+
+    /// Picture of the puzzle for `--render`, none for the days that have
+    /// nothing to draw
+    fn render(&self, _input: &Self::Input) -> Option<Image> {
+        None
+    }
+
+    /// Table of the puzzle as CSV with a header line for `--csv`, an error
+    /// for the days that have nothing to tabulate or when the table cannot
+    /// be made
+    fn table(&self, _input: &Self::Input) -> Result<String, String> {
+        Err(format!("Day {} has nothing to tabulate", Self::DAY))
+    }
+
+    /// Step by step simulation of the puzzle for `--frames` and `--step`,
+    /// none for the days that do not evolve
+    fn simulate(&self, _input: &Self::Input) -> Option<Box<dyn Simulation>> {
+        None
+    }
+}
+
+/// Parse the input of a day, the errors are tagged with the day
+pub fn parse<S: Solution>(solution: &S, input: &str) -> Result<S::Input, ParseError> {
+    solution.parse(input).map_err(|err| err.with_day(S::DAY))
+}
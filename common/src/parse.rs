@@ -0,0 +1,232 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::ParseError;
+
+/// Parse every line of the input, locating the errors on their line
+pub fn parse_lines<T, F>(input: &str, mut parse_line: F) -> Result<Vec<T>, ParseError>
+where
+    F: FnMut(&str) -> Result<T, ParseError>,
+{
+    input
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| parse_line(line).map_err(|err| err.on_line(idx, line)))
+        .collect()
+}
+
+/// The only line of an input made of a single line, like a number or a
+/// polymer, trailing blank lines are allowed
+pub fn single_line(input: &str) -> Result<&str, ParseError> {
+    let mut lines = input.lines().enumerate();
+    let line = lines
+        .next()
+        .map(|(_, line)| line)
+        .ok_or_else(|| ParseError::new("empty input"))?;
+    match lines.find(|(_, line)| !line.trim().is_empty()) {
+        Some((idx, line)) => {
+            Err(ParseError::at(0, line.len(), "expected a single line").on_line(idx, line))
+        }
+        None => Ok(line),
+    }
+}
+
+/// Cursor over a line reading the pieces of a puzzle input one after the
+/// other, the errors point at the column where the line stops matching
+#[derive(Debug, Clone)]
+pub struct Scanner<'a> {
+    line: &'a str,
+    pos: usize,
+}
+
+impl<'a> Scanner<'a> {
+    pub fn new(line: &'a str) -> Scanner<'a> {
+        Scanner { line, pos: 0 }
+    }
+
+    /// Byte offset of the cursor in the line
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
+    /// Part of the line not read yet
+    pub fn rest(&self) -> &'a str {
+        &self.line[self.pos..]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rest().is_empty()
+    }
+
+    pub fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// Read exactly `literal`, pointing at the first character differing
+    pub fn literal(&mut self, literal: &str) -> Result<(), ParseError> {
+        let rest = self.rest();
+        if rest.starts_with(literal) {
+            self.pos += literal.len();
+            return Ok(());
+        }
+
+        let matching = rest
+            .char_indices()
+            .zip(literal.chars())
+            .find(|&((_, a), b)| a != b)
+            .map_or(rest.len(), |((i, _), _)| i);
+        Err(self.error_at(self.pos + matching, format!("expected `{}`", literal)))
+    }
+
+    /// Read the next character
+    pub fn char(&mut self) -> Result<char, ParseError> {
+        match self.rest().chars().next() {
+            Some(c) => {
+                self.pos += c.len_utf8();
+                Ok(c)
+            }
+            None => Err(self.error_at(self.pos, "unexpected end of line")),
+        }
+    }
+
+    /// Read the next character if it satisfies `pred`, `expected` describes
+    /// it in the error
+    pub fn char_if(
+        &mut self,
+        pred: impl Fn(char) -> bool,
+        expected: &str,
+    ) -> Result<char, ParseError> {
+        match self.rest().chars().next() {
+            Some(c) if pred(c) => self.char(),
+            _ => Err(self.error_at(self.pos, format!("expected {}", expected))),
+        }
+    }
+
+    /// Read the characters up to the first one not satisfying `pred`
+    pub fn take_while(&mut self, pred: impl Fn(char) -> bool) -> &'a str {
+        let rest = self.rest();
+        let taken = &rest[..rest.len() - rest.trim_start_matches(pred).len()];
+        self.pos += taken.len();
+        taken
+    }
+
+    /// Read a number made of decimal digits with an optional sign
+    pub fn number<T>(&mut self) -> Result<T, ParseError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let rest = self.rest();
+        let sign = if rest.starts_with('+') || rest.starts_with('-') {
+            1
+        } else {
+            0
+        };
+        let digits = rest[sign..].len()
+            - rest[sign..]
+                .trim_start_matches(|c: char| c.is_ascii_digit())
+                .len();
+        if digits == 0 {
+            return Err(self.error_at(self.pos + sign, "expected a number"));
+        }
+
+        let number = &rest[..sign + digits];
+        let value = number.parse::<T>().map_err(|err| {
+            ParseError::at(self.pos, number.len(), format!("invalid number: {}", err))
+        })?;
+        self.pos += number.len();
+        Ok(value)
+    }
+
+    /// Check that the whole line has been read
+    pub fn end(&self) -> Result<(), ParseError> {
+        match self.rest() {
+            "" => Ok(()),
+            rest => Err(ParseError::at(
+                self.pos,
+                rest.len(),
+                "unexpected text at the end of the line",
+            )),
+        }
+    }
+
+    // error on the word starting at `pos`, or on the missing text past the
+    // end of the line
+    fn error_at(&self, pos: usize, message: impl Into<String>) -> ParseError {
+        let word = self.line[pos..]
+            .split(|c: char| !c.is_alphanumeric())
+            .next()
+            .map_or(0, str::len);
+        ParseError::at(pos, word, message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_lines, single_line, Scanner};
+
+    #[test]
+    fn test_scanner() {
+        let mut s = Scanner::new("#12 @ -3,+4: 5x6");
+        assert_eq!(s.literal("#"), Ok(()));
+        assert_eq!(s.number::<u32>(), Ok(12));
+        assert_eq!(s.literal(" @ "), Ok(()));
+        assert_eq!(s.number::<i32>(), Ok(-3));
+        assert_eq!(s.char(), Ok(','));
+        assert_eq!(s.number::<i32>(), Ok(4));
+        assert_eq!(s.literal(": "), Ok(()));
+        assert_eq!(s.char_if(|c| c.is_ascii_digit(), "a digit"), Ok('5'));
+        assert_eq!(s.take_while(|c| c != '6'), "x");
+        assert_eq!(s.rest(), "6");
+        assert!(s.end().is_err());
+        s.skip_whitespace();
+        assert_eq!(s.pos(), 15);
+    }
+
+    #[test]
+    fn test_scanner_errors() {
+        let line = "#1 x 3,1: 4x4";
+        let mut s = Scanner::new(line);
+        s.literal("#").unwrap();
+        s.number::<u32>().unwrap();
+        let err = s.literal(" @ ").unwrap_err().on_line(0, line);
+        assert_eq!((err.column(), err.message()), (Some(4), "expected ` @ `"));
+
+        let err = Scanner::new("abc").number::<u32>().unwrap_err();
+        assert_eq!(err.on_line(0, "abc").column(), Some(1));
+
+        let err = Scanner::new("-1").number::<u32>().unwrap_err();
+        assert_eq!(
+            err.message(),
+            "invalid number: invalid digit found in string"
+        );
+
+        let err = Scanner::new("99999999999").number::<u32>().unwrap_err();
+        assert!(err.message().starts_with("invalid number"));
+
+        assert!(Scanner::new("").char().is_err());
+    }
+
+    #[test]
+    fn test_parse_lines() {
+        let parse_line = |line: &str| {
+            let mut s = Scanner::new(line);
+            let n = s.number::<i32>()?;
+            s.end().map(|_| n)
+        };
+        assert_eq!(parse_lines("+1\n-2\n", parse_line), Ok(vec![1, -2]));
+
+        let err = parse_lines("+1\n-2\n+3a", parse_line).unwrap_err();
+        assert_eq!(err.line(), Some(3));
+        assert_eq!(err.column(), Some(3));
+        assert_eq!(err.text(), Some("+3a"));
+    }
+
+    #[test]
+    fn test_single_line() {
+        assert_eq!(single_line("dabAcCaCBAcCcaDA\n\n"), Ok("dabAcCaCBAcCcaDA"));
+        assert!(single_line("").is_err());
+        assert_eq!(single_line("1\n\n2").unwrap_err().line(), Some(3));
+    }
+}
@@ -0,0 +1,101 @@
+//! Seeded pseudo random numbers to make up puzzle inputs
+//!
+//! The generator is SplitMix64: tiny, fast and good enough for inputs and
+//! tests, the same seed always giving the same numbers on every platform.
+
+use std::ops::RangeInclusive;
+
+/// Pseudo random number generator
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Number from 0 to `n` excluded, panics if `n` is 0
+    pub fn below(&mut self, n: u64) -> u64 {
+        assert!(n > 0, "Empty range");
+        // the high bits of the product, the bias is negligible for the
+        // small ranges of the inputs
+        ((self.next_u64() as u128 * n as u128) >> 64) as u64
+    }
+
+    /// Number in the range, panics if empty
+    pub fn range(&mut self, range: RangeInclusive<i64>) -> i64 {
+        let (start, end) = range.into_inner();
+        assert!(start <= end, "Empty range");
+        match end.wrapping_sub(start) as u64 {
+            u64::MAX => self.next_u64() as i64,
+            span => start.wrapping_add(self.below(span + 1) as i64),
+        }
+    }
+
+    /// Index in a slice of `len` items, panics if empty
+    pub fn index(&mut self, len: usize) -> usize {
+        self.below(len as u64) as usize
+    }
+
+    /// True with probability `p`
+    pub fn chance(&mut self, p: f64) -> bool {
+        // 53 random bits, the precision of a float
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < p
+    }
+
+    /// One of the items, panics if empty
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.index(items.len())]
+    }
+
+    /// Shuffle the items in place, every order being equally likely
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.index(i + 1));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Rng;
+
+    #[test]
+    fn test_rng() {
+        // the reference output of SplitMix64 seeded with 0
+        let mut rng = Rng::new(0);
+        assert_eq!(rng.next_u64(), 0xe220_a839_7b1d_cdaf);
+        assert_eq!(rng.next_u64(), 0x6e78_9e6a_a1b9_65f4);
+
+        let mut rng = Rng::new(42);
+        let mut counts = [0; 5];
+        for _ in 0..10_000 {
+            let n = rng.range(-2..=2);
+            counts[(n + 2) as usize] += 1;
+        }
+        assert!(counts.iter().all(|&count| count > 1_800 && count < 2_200));
+        assert_eq!(rng.range(7..=7), 7);
+        // the whole range does not overflow
+        rng.range(i64::MIN..=i64::MAX);
+
+        let mut items: Vec<u32> = (0..20).collect();
+        rng.shuffle(&mut items);
+        assert_ne!(items, (0..20).collect::<Vec<_>>());
+        items.sort_unstable();
+        assert_eq!(items, (0..20).collect::<Vec<_>>());
+
+        assert!(!rng.chance(0.0));
+        assert!(rng.chance(1.0));
+        assert_eq!(Rng::new(3).next_u64(), Rng::new(3).next_u64());
+    }
+}
@@ -0,0 +1,357 @@
+//! Terminal stepper of the simulations for `--step`
+//!
+//! The state is drawn with ANSI escape codes in a window scrolled over the
+//! grid, after every command. The commands are read a line at a time so
+//! that they can also be piped in.
+
+use std::io::{self, BufRead, Write};
+
+use crate::{Point, Rgb, Simulation};
+
+/// Steps made at most by `c`, the simulations of some days never end or
+/// have no events
+const CONTINUE_STEPS: usize = 100_000;
+
+const HELP: &str = "n [N] next, b [N] back, g STEP go to, c continue to the next event, \
+                    h/j/k/l [N] scroll, p stop at events on/off, q quit";
+
+/// A simulation that can also step back, by replaying it from the start
+pub struct Stepper<'a> {
+    restart: Box<dyn Fn() -> Box<dyn Simulation> + 'a>,
+    simulation: Box<dyn Simulation>,
+    step: usize,
+    over: bool,
+}
+
+impl<'a> Stepper<'a> {
+    /// Stepper of the simulations made by `restart`, at their initial state
+    pub fn new(restart: impl Fn() -> Box<dyn Simulation> + 'a) -> Stepper<'a> {
+        let simulation = restart();
+        Stepper {
+            restart: Box::new(restart),
+            simulation,
+            step: 0,
+            over: false,
+        }
+    }
+
+    /// Steps made since the initial state
+    pub fn step(&self) -> usize {
+        self.step
+    }
+
+    /// Whether the simulation is known to be over
+    pub fn is_over(&self) -> bool {
+        self.over
+    }
+
+    pub fn simulation(&self) -> &dyn Simulation {
+        self.simulation.as_ref()
+    }
+
+    /// Make up to `n` steps, stopping after a step with an event when
+    /// `stop_at_events`; returns the steps made
+    pub fn forward(&mut self, n: usize, stop_at_events: bool) -> usize {
+        let mut made = 0;
+        while made < n && !self.over {
+            if !self.simulation.step() {
+                self.over = true;
+                break;
+            }
+            self.step += 1;
+            made += 1;
+            if stop_at_events && self.simulation.event().is_some() {
+                break;
+            }
+        }
+        made
+    }
+
+    /// Go `n` steps back, to the initial state at most
+    pub fn back(&mut self, n: usize) {
+        self.seek(self.step.saturating_sub(n));
+    }
+
+    /// Go to a step, or to the last one when the simulation ends before
+    pub fn seek(&mut self, step: usize) {
+        if step < self.step {
+            self.simulation = (self.restart)();
+            self.step = 0;
+            self.over = false;
+        }
+        self.forward(step - self.step, false);
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Command {
+    Forward(usize),
+    Back(usize),
+    Seek(usize),
+    Continue,
+    Scroll(i32, i32),
+    ToggleStop,
+    Quit,
+}
+
+fn parse_command(line: &str) -> Result<Command, String> {
+    let mut words = line.split_whitespace();
+    let name = words.next().unwrap_or("n");
+    let count = match words.next() {
+        Some(word) => Some(
+            word.parse::<usize>()
+                .map_err(|_| format!("Invalid number {}", word))?,
+        ),
+        None => None,
+    };
+    if let Some(word) = words.next() {
+        return Err(format!("Unexpected {}", word));
+    }
+    let scroll = count.unwrap_or(8) as i32;
+    let command = match name {
+        "n" => Command::Forward(count.unwrap_or(1)),
+        "b" => Command::Back(count.unwrap_or(1)),
+        "g" => Command::Seek(count.ok_or("Missing the step to go to")?),
+        "c" => Command::Continue,
+        "h" => Command::Scroll(-scroll, 0),
+        "j" => Command::Scroll(0, scroll),
+        "k" => Command::Scroll(0, -scroll),
+        "l" => Command::Scroll(scroll, 0),
+        "p" => Command::ToggleStop,
+        "q" => Command::Quit,
+        _ => return Err(format!("Unknown command {}", name)),
+    };
+    Ok(command)
+}
+
+// the status line then the window of `width` x `height` cells from the
+// offset `view` of the top-left corner of the grid
+fn draw(
+    stepper: &Stepper,
+    view: &mut Point,
+    (width, height): (usize, usize),
+    status: &str,
+) -> String {
+    let text = stepper.simulation().text();
+    let bounds = text.bounds();
+    // keep the window over the grid
+    let max = Point::new(
+        bounds.width().saturating_sub(width) as i32,
+        bounds.height().saturating_sub(height) as i32,
+    );
+    *view = Point::new(view.x.clamp(0, max.x), view.y.clamp(0, max.y));
+
+    let mut screen = "\x1b[H\x1b[2J".to_string();
+    screen += &format!("\x1b[7m step {} ", stepper.step());
+    if stepper.is_over() {
+        screen += "(over) ";
+    }
+    if let Some(event) = stepper.simulation().event() {
+        screen += &format!("| {} ", event);
+    }
+    screen += &format!(
+        "| cells {}..{} of {}x{} \x1b[0m {}\n",
+        *view,
+        *view + Point::new(width as i32 - 1, height as i32 - 1),
+        bounds.width(),
+        bounds.height(),
+        status
+    );
+
+    for y in 0..height.min(bounds.height()) {
+        let mut colour = None;
+        for x in 0..width.min(bounds.width()) {
+            let p = bounds.min + *view + Point::new(x as i32, y as i32);
+            let (c, Rgb(r, g, b)) = text[p];
+            if colour != Some((r, g, b)) {
+                screen += &format!("\x1b[38;2;{};{};{}m", r, g, b);
+                colour = Some((r, g, b));
+            }
+            screen.push(c);
+        }
+        screen += "\x1b[0m\n";
+    }
+    screen += "> ";
+    screen
+}
+
+/// Step through the simulations made by `restart` with the commands read
+/// from `input`, drawing a window of `size` cells on `output` after every
+/// command; ends with `q` or at the end of the input
+pub fn interact(
+    restart: impl Fn() -> Box<dyn Simulation>,
+    input: impl BufRead,
+    mut output: impl Write,
+    size: (usize, usize),
+) -> io::Result<()> {
+    let mut stepper = Stepper::new(restart);
+    let mut view = Point::new(0, 0);
+    let mut stop_at_events = true;
+    write!(output, "{}", draw(&stepper, &mut view, size, HELP))?;
+    output.flush()?;
+
+    for line in input.lines() {
+        let mut status = String::new();
+        match parse_command(&line?) {
+            Ok(Command::Forward(n)) => {
+                stepper.forward(n, stop_at_events);
+            }
+            Ok(Command::Back(n)) => stepper.back(n),
+            Ok(Command::Seek(step)) => stepper.seek(step),
+            Ok(Command::Continue) => {
+                let made = stepper.forward(CONTINUE_STEPS, true);
+                if made == CONTINUE_STEPS && stepper.simulation().event().is_none() {
+                    status = format!("No event in {} steps, c to go on", CONTINUE_STEPS);
+                }
+            }
+            Ok(Command::Scroll(dx, dy)) => view += Point::new(dx, dy),
+            Ok(Command::ToggleStop) => {
+                stop_at_events = !stop_at_events;
+                status = match stop_at_events {
+                    true => "stopping at events".to_string(),
+                    false => "not stopping at events".to_string(),
+                };
+            }
+            Ok(Command::Quit) => break,
+            Err(err) => status = format!("{}; {}", err, HELP),
+        }
+        write!(output, "{}", draw(&stepper, &mut view, size, &status))?;
+        output.flush()?;
+    }
+    writeln!(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{interact, parse_command, Command, Stepper};
+    use crate::{BoundingBox, Grid, Image, Point, Rgb, Simulation};
+
+    // a counter up to 10 with an event at every multiple of 4
+    struct Counter(usize);
+
+    impl Simulation for Counter {
+        fn step(&mut self) -> bool {
+            if self.0 == 10 {
+                return false;
+            }
+            self.0 += 1;
+            true
+        }
+
+        fn render(&self) -> Image {
+            Image::new(1, 1, Rgb::BLACK)
+        }
+
+        fn text(&self) -> Grid<(char, Rgb)> {
+            let bounds = BoundingBox::with_size(Point::new(-1, 0), 12, 2);
+            Grid::from_fn(bounds, |p| match p.x as usize == self.0 {
+                true => ('#', Rgb::WHITE),
+                false => ('.', Rgb::BLACK),
+            })
+        }
+
+        fn event(&self) -> Option<String> {
+            if self.0.is_multiple_of(4) {
+                Some(format!("{} reached", self.0))
+            } else {
+                None
+            }
+        }
+    }
+
+    fn counter() -> Box<dyn Simulation> {
+        Box::new(Counter(0))
+    }
+
+    // steps forever without an event
+    struct Idle;
+
+    impl Simulation for Idle {
+        fn step(&mut self) -> bool {
+            true
+        }
+
+        fn render(&self) -> Image {
+            Image::new(1, 1, Rgb::BLACK)
+        }
+
+        fn text(&self) -> Grid<(char, Rgb)> {
+            Grid::from_fn(BoundingBox::with_size(Point::new(0, 0), 1, 1), |_| {
+                ('.', Rgb::BLACK)
+            })
+        }
+    }
+
+    #[test]
+    fn test_stepper() {
+        let mut stepper = Stepper::new(counter);
+        assert_eq!(stepper.forward(3, true), 3);
+        assert_eq!(stepper.forward(3, true), 1);
+        assert_eq!(stepper.simulation().event(), Some("4 reached".to_string()));
+        assert_eq!(stepper.forward(3, false), 3);
+        assert_eq!(stepper.step(), 7);
+
+        stepper.back(5);
+        assert_eq!(stepper.step(), 2);
+        assert!(stepper.simulation().event().is_none());
+        stepper.back(5);
+        assert_eq!(stepper.step(), 0);
+
+        stepper.seek(20);
+        assert_eq!(stepper.step(), 10);
+        assert!(stepper.is_over());
+        assert_eq!(stepper.forward(1, false), 0);
+        stepper.seek(9);
+        assert!(!stepper.is_over());
+    }
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!(parse_command(""), Ok(Command::Forward(1)));
+        assert_eq!(parse_command("n 10"), Ok(Command::Forward(10)));
+        assert_eq!(parse_command(" b "), Ok(Command::Back(1)));
+        assert_eq!(parse_command("g 42"), Ok(Command::Seek(42)));
+        assert_eq!(parse_command("h"), Ok(Command::Scroll(-8, 0)));
+        assert_eq!(parse_command("j 2"), Ok(Command::Scroll(0, 2)));
+        assert!(parse_command("g").is_err());
+        assert!(parse_command("n x").is_err());
+        assert!(parse_command("n 1 2").is_err());
+        assert!(parse_command("x").is_err());
+    }
+
+    #[test]
+    fn test_interact() {
+        let mut output = Vec::new();
+        interact(
+            counter,
+            "c\nl 5\nb\nwhat\nq\nn\n".as_bytes(),
+            &mut output,
+            (6, 1),
+        )
+        .unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let screens: Vec<&str> = output.split("\x1b[H\x1b[2J").skip(1).collect();
+        assert_eq!(screens.len(), 5);
+
+        // stopped at the first event, then the window moved to the right
+        // until the end of the grid
+        assert!(screens[1].contains(" step 4 | 4 reached | cells (0, 0)..(5, 0) of 12x2 "));
+        assert!(screens[2].contains(" step 4 | 4 reached | cells (5, 0)..(10, 0) "));
+        let row = screens[2].lines().nth(1).unwrap();
+        assert!(row.starts_with("\x1b[38;2;255;255;255m#\x1b[38;2;0;0;0m....."));
+        assert!(screens[3].contains(" step 3 | cells (5, 0)"));
+        assert!(screens[4].contains("Unknown command what; n [N] next"));
+    }
+
+    #[test]
+    fn test_interact_continue_limit() {
+        let mut output = Vec::new();
+        let idle = || Box::new(Idle) as Box<dyn Simulation>;
+        interact(idle, "c\nc\n".as_bytes(), &mut output, (1, 1)).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let screens: Vec<&str> = output.split("\x1b[H\x1b[2J").skip(1).collect();
+        assert!(screens[1].contains(" step 100000 | cells "));
+        assert!(screens[1].contains("No event in 100000 steps, c to go on"));
+        assert!(screens[2].contains(" step 200000 | cells "));
+    }
+}
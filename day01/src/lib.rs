@@ -0,0 +1,1099 @@
+//! Day 1: Chronal Calibration
+
+use common::{flag_value, Answer, Flag, Json, ParseError, Rng, Scanner, Solution};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap};
+use std::convert::TryFrom;
+use std::fmt::{self, Debug, Display};
+use std::hash::Hash;
+use std::iter::Iterator;
+use std::num::ParseIntError;
+use std::str::FromStr;
+
+/// Integer type the frequencies are computed with: the changes must fit in
+/// it, and so must the frequencies of the first pass
+pub trait Frequency:
+    Copy + Ord + Hash + Debug + Display + FromStr<Err = ParseIntError> + Into<i128>
+{
+    /// Name of the type in the errors
+    const NAME: &'static str;
+    const ZERO: Self;
+    const MIN: Self;
+    const MAX: Self;
+
+    fn checked_add(self, other: Self) -> Option<Self>;
+
+    /// Remainder of the frequency modulo the drift, never negative
+    fn group(self, drift: Self) -> Self;
+
+    /// Number of drifts between the frequency and `other`
+    fn drifts_to(self, other: Self, drift: Self) -> u128;
+}
+
+macro_rules! frequency {
+    ($($t:ident)*) => {
+        $(
+            impl Frequency for $t {
+                const NAME: &'static str = stringify!($t);
+                const ZERO: $t = 0;
+                const MIN: $t = $t::MIN;
+                const MAX: $t = $t::MAX;
+
+                fn checked_add(self, other: $t) -> Option<$t> {
+                    $t::checked_add(self, other)
+                }
+
+                fn group(self, drift: $t) -> $t {
+                    // only overflowing for the minimum modulo -1
+                    self.checked_rem_euclid(drift).unwrap_or(0)
+                }
+
+                fn drifts_to(self, other: $t, drift: $t) -> u128 {
+                    (self.abs_diff(other) / drift.unsigned_abs()) as u128
+                }
+            }
+        )*
+    };
+}
+
+frequency!(i32 i64 i128);
+
+/// Parse a frequency change like `+3` or `-2`
+pub fn parse_change<T: Frequency>(line: &str) -> Result<T, ParseError> {
+    let mut scanner = Scanner::new(line);
+    let change = scanner.number()?;
+    scanner.end()?;
+    Ok(change)
+}
+
+fn is_separator(c: char) -> bool {
+    c == ',' || c.is_whitespace()
+}
+
+// the entries of a line between the separators, with their byte offsets
+fn entries(line: &str) -> impl Iterator<Item = (usize, &str)> {
+    line.split(is_separator)
+        .scan(0, |column, entry| {
+            let start = *column;
+            *column += entry.len() + 1;
+            Some((start, entry))
+        })
+        .filter(|(_, entry)| !entry.is_empty())
+}
+
+/// Parse the frequency changes separated by newlines, commas or whitespace,
+/// like `+1, -2, +3, +1`, the blank entries being skipped. The errors point
+/// at the entry at fault, numbered from 1 in the message, including the
+/// change that makes the frequency overflow `T` on the first pass
+pub fn get_nums<T: Frequency>(input: &str) -> Result<Vec<T>, ParseError> {
+    let mut nums = Vec::new();
+    // the line and the span of every change
+    let mut places = Vec::new();
+    for (idx, line) in input.lines().enumerate() {
+        for (column, entry) in entries(line) {
+            let num = parse_change(entry).map_err(|err| {
+                let message = format!(
+                    "invalid change `{}` (entry {}): {}",
+                    entry,
+                    nums.len() + 1,
+                    err.message()
+                );
+                ParseError::at(column, entry.len(), message).on_line(idx, line)
+            })?;
+            nums.push(num);
+            places.push((idx, line, column, entry.len()));
+        }
+    }
+    match sum_freq(&nums) {
+        Ok(_) => Ok(nums),
+        Err(n) => {
+            let (idx, line, column, width) = places[n];
+            let message = format!("the frequency overflows {} (entry {})", T::NAME, n + 1);
+            Err(ParseError::at(column, width, message).on_line(idx, line))
+        }
+    }
+}
+
+/// Resulting frequency after all the changes, starting from zero, or the
+/// index of the change overflowing `T`
+pub fn sum_freq<T: Frequency>(nums: &[T]) -> Result<T, usize> {
+    nums.iter()
+        .enumerate()
+        .try_fold(T::ZERO, |freq, (idx, &n)| freq.checked_add(n).ok_or(idx))
+}
+
+/// When a frequency is reached while repeating the list of changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Occurrence {
+    /// Changes applied so far, 0 for the frequency at the start
+    pub changes: u64,
+    /// Pass over the list of the last change applied, from 0
+    pub pass: u64,
+    /// Index in the list of the last change applied, none at the start
+    pub index: Option<usize>,
+}
+
+impl Occurrence {
+    /// The frequency after `changes` changes of a list of `len`
+    pub fn after(changes: u64, len: usize) -> Occurrence {
+        match changes.checked_sub(1) {
+            Some(last) => Occurrence {
+                changes,
+                pass: last / len as u64,
+                index: Some((last % len as u64) as usize),
+            },
+            None => Occurrence {
+                changes,
+                pass: 0,
+                index: None,
+            },
+        }
+    }
+}
+
+/// Printed like `after 12 changes (pass 1, line 3)`, lines counted from 1
+impl Display for Occurrence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.index {
+            Some(index) => write!(
+                f,
+                "after {} changes (pass {}, line {})",
+                self.changes,
+                self.pass,
+                index + 1
+            ),
+            None => write!(f, "at the start"),
+        }
+    }
+}
+
+/// A frequency reached a second time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Repetition<T = i32> {
+    pub freq: T,
+    /// When it is reached the second time
+    pub at: Occurrence,
+    /// When it was reached the first time
+    pub earlier: Occurrence,
+}
+
+impl<T: Frequency> Repetition<T> {
+    /// The same repetition with the frequency as an `i128`
+    pub fn widen(self) -> Repetition<i128> {
+        Repetition {
+            freq: self.freq.into(),
+            at: self.at,
+            earlier: self.earlier,
+        }
+    }
+}
+
+impl<T: Display> Display for Repetition<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {}, first reached {}",
+            self.freq, self.at, self.earlier
+        )
+    }
+}
+
+/// The frequency overflowing its integer type while repeating the list of
+/// changes, printed like `the frequency overflows i32 after 5 changes (pass
+/// 2, line 1)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overflow {
+    /// Name of the integer type
+    pub width: &'static str,
+    /// When the frequency would be out of range
+    pub at: Occurrence,
+}
+
+impl Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the frequency overflows {} {}", self.width, self.at)
+    }
+}
+
+// the changes after which the frequency first overflows `T`, given the
+// frequencies before every change of the first pass; none when it never
+// does or after more changes than a u64 counts
+fn first_overflow<T: Frequency>(freqs: &[T], drift: T) -> Option<u64> {
+    let limit = match drift.cmp(&T::ZERO) {
+        Ordering::Greater => T::MAX,
+        Ordering::Less => T::MIN,
+        Ordering::Equal => return None,
+    };
+    // the frequency before the change `i` is out of range after one pass
+    // more than the drifts fitting between it and the limit
+    let len = freqs.len() as u128;
+    freqs
+        .iter()
+        .enumerate()
+        .filter_map(|(i, &freq)| {
+            let passes = limit.drifts_to(freq, drift).checked_add(1)?;
+            let changes = passes.checked_mul(len)?.checked_add(i as u128)?;
+            u64::try_from(changes).ok()
+        })
+        .min()
+}
+
+// the frequencies `freq + m * drift` for `m` below `count`, if any, reached
+// a first and a second time after the given changes, and `len` changes
+// later with every `m`
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Stream<T> {
+    second: u64,
+    first: u64,
+    freq: T,
+    count: Option<u64>,
+}
+
+/// The frequencies reached twice in the order they repeat, see
+/// [`repetitions`]
+pub struct Repetitions<T> {
+    len: usize,
+    drift: T,
+    streams: BinaryHeap<Reverse<Stream<T>>>,
+    /// changes after which the frequency overflows `T`
+    overflow: Option<u64>,
+    /// whether a stream stopped drifting out of range
+    out_of_range: bool,
+}
+
+impl<T: Frequency> Iterator for Repetitions<T> {
+    type Item = Result<Repetition<T>, Overflow>;
+
+    fn next(&mut self) -> Option<Result<Repetition<T>, Overflow>> {
+        // nothing is reached past the overflow, where the frequencies
+        // drifting out of range would repeat
+        let past_overflow = match self.streams.peek() {
+            Some(Reverse(stream)) => self
+                .overflow
+                .is_some_and(|changes| changes <= stream.second),
+            None => self.out_of_range,
+        };
+        if past_overflow {
+            self.streams.clear();
+            self.out_of_range = false;
+            let changes = self.overflow.take()?;
+            return Some(Err(Overflow {
+                width: T::NAME,
+                at: Occurrence::after(changes, self.len),
+            }));
+        }
+
+        let Reverse(stream) = self.streams.pop()?;
+        let len = self.len as u64;
+        // a stream stops when its frequencies drift out of range, or when
+        // the changes can no longer be counted
+        let advanced = match (
+            stream.second.checked_add(len),
+            stream.freq.checked_add(self.drift),
+        ) {
+            _ if stream.count == Some(1) => None,
+            (Some(second), Some(freq)) => Some(Stream {
+                second,
+                first: stream.first + len,
+                freq,
+                count: stream.count.map(|count| count - 1),
+            }),
+            (Some(_), None) => {
+                self.out_of_range = true;
+                None
+            }
+            (None, _) => None,
+        };
+        let repetition = Repetition {
+            freq: stream.freq,
+            at: Occurrence::after(stream.second, self.len),
+            earlier: Occurrence::after(stream.first, self.len),
+        };
+        if let Some(advanced) = advanced {
+            self.streams.push(Reverse(advanced));
+        }
+        Some(Ok(repetition))
+    }
+}
+
+/// Every frequency reached twice while repeating the list of changes, in
+/// the order they repeat
+///
+/// Rather than going through the passes, the frequencies of the first pass
+/// are grouped by their remainder modulo the drift of a pass: the one
+/// after `i` changes comes back as the frequencies of its group in the
+/// direction of the drift, `k` passes later after `k * len + i` changes.
+/// A frequency is then reached first from the nearest frequency of its
+/// group and a second time from the next nearest one, and the frequencies
+/// sharing both come in order: they make up a stream merged with the
+/// others as the iteration goes on, O(n log n) for the first repetition.
+///
+/// The iteration ends with the overflow of the frequency when the next
+/// repetition comes after it. Panics if the frequency overflows `T` on the
+/// first pass, which [`get_nums`] rules out.
+pub fn repetitions<T: Frequency>(nums: &[T]) -> Repetitions<T> {
+    let len = nums.len();
+    let drift = sum_freq(nums).expect("frequency overflowing on the first pass");
+    // the frequencies before every change of the first pass
+    let freqs: Vec<T> = std::iter::once(T::ZERO)
+        .chain(nums.iter().scan(T::ZERO, |acc, &n| {
+            *acc = acc.checked_add(n)?;
+            Some(*acc)
+        }))
+        .take(len)
+        .collect();
+
+    let mut streams = BinaryHeap::new();
+    if drift == T::ZERO {
+        // every pass goes through the same frequencies
+        let mut changes: HashMap<T, Vec<u64>> = HashMap::new();
+        for (i, &freq) in freqs.iter().enumerate() {
+            changes.entry(freq).or_default().push(i as u64);
+        }
+        for (freq, changes) in changes {
+            streams.push(Reverse(Stream {
+                second: changes.get(1).cloned().unwrap_or(changes[0] + len as u64),
+                first: changes[0],
+                freq,
+                count: Some(1),
+            }));
+        }
+    } else {
+        // the group, then the nearest frequencies first for the ones
+        // beyond them in the direction of the drift
+        let group = |i: usize| freqs[i].group(drift);
+        let mut order: Vec<usize> = (0..len).collect();
+        order.sort_unstable_by(|&a, &b| {
+            let nearest = match drift > T::ZERO {
+                true => freqs[b].cmp(&freqs[a]),
+                false => freqs[a].cmp(&freqs[b]),
+            };
+            group(a).cmp(&group(b)).then(nearest).then(a.cmp(&b))
+        });
+        for r in 0..len.saturating_sub(1) {
+            let (nearest, next) = (order[r], order[r + 1]);
+            if group(nearest) != group(next) {
+                continue;
+            }
+            // up to the previous frequency of the group, for which the
+            // nearest ones are different; none when it is the same. A count
+            // too large for a u64 is never reached either
+            let count = match r.checked_sub(1).map(|q| order[q]) {
+                Some(previous) if group(previous) == group(nearest) => {
+                    match freqs[nearest].drifts_to(freqs[previous], drift) {
+                        0 => continue,
+                        count => u64::try_from(count).ok(),
+                    }
+                }
+                _ => None,
+            };
+            // left out when repeating after more changes than a u64 counts
+            let second = u64::try_from(freqs[next].drifts_to(freqs[nearest], drift))
+                .ok()
+                .and_then(|passes| passes.checked_mul(len as u64))
+                .and_then(|changes| changes.checked_add(next as u64));
+            if let Some(second) = second {
+                streams.push(Reverse(Stream {
+                    second,
+                    first: nearest as u64,
+                    freq: freqs[nearest],
+                    count,
+                }));
+            }
+        }
+    }
+    Repetitions {
+        len,
+        drift,
+        streams,
+        overflow: first_overflow(&freqs, drift),
+        out_of_range: false,
+    }
+}
+
+/// First frequency reached twice while repeating the list of changes,
+/// none if the frequencies never repeat, or the overflow of the frequency
+/// before it
+pub fn first_repetition<T: Frequency>(nums: &[T]) -> Result<Option<Repetition<T>>, Overflow> {
+    repetitions(nums).next().transpose()
+}
+
+/// Parse the changes and compute the resulting frequency
+pub fn compute_freq<T: Frequency>(input: &str) -> Result<T, ParseError> {
+    get_nums(input).map(|nums: Vec<T>| sum_freq(&nums).expect("overflow checked by get_nums"))
+}
+
+/// Parse the changes and find the first frequency reached twice, the
+/// overflow of the frequency before it being an error
+pub fn compute_repetition<T: Frequency>(input: &str) -> Result<Option<Repetition<T>>, ParseError> {
+    let nums: Vec<T> = get_nums(input)?;
+    first_repetition(&nums).map_err(|overflow| ParseError::new(overflow.to_string()))
+}
+
+/// Random changes from -20 to 20 drifting by less than their count on
+/// every pass: two of the frequencies of the first pass are then the same
+/// modulo the drift, so a frequency is always reached twice
+pub fn generate_changes(rng: &mut Rng, count: usize) -> Vec<i32> {
+    let count = count.max(1);
+    let max_drift = count as i64 - 1;
+    let drift = rng.range(-max_drift..=max_drift) as i32;
+    let mut changes: Vec<i32> = (1..count)
+        .map(|_| rng.range(1..=20) as i32 * if rng.chance(0.5) { 1 } else { -1 })
+        .collect();
+    let sum: i32 = changes.iter().sum();
+    changes.insert(rng.index(count), drift - sum);
+    changes
+}
+
+/// The first frequencies reached twice, printed as the frequencies alone
+/// followed by the overflow cutting them short; the JSON output lists when
+/// they were reached
+#[derive(Debug, Clone, PartialEq)]
+pub struct Repeats {
+    pub repeats: Vec<Repetition<i128>>,
+    /// Overflow of the frequency before the next repetition
+    pub overflow: Option<Overflow>,
+}
+
+impl Repeats {
+    /// The first `k` frequencies of the changes reached twice
+    pub fn first<T: Frequency>(nums: &[T], k: usize) -> Repeats {
+        let mut repeats = Repeats {
+            repeats: Vec::new(),
+            overflow: None,
+        };
+        for repetition in repetitions(nums).take(k) {
+            match repetition {
+                Ok(repetition) => repeats.repeats.push(repetition.widen()),
+                Err(overflow) => repeats.overflow = Some(overflow),
+            }
+        }
+        repeats
+    }
+}
+
+impl Display for Repeats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let freqs: Vec<String> = self.repeats.iter().map(|r| r.freq.to_string()).collect();
+        match (freqs.is_empty(), &self.overflow) {
+            (true, None) => write!(f, "none, the frequencies never repeat"),
+            (true, Some(overflow)) => write!(f, "none, {}", overflow),
+            (false, None) => write!(f, "{}", freqs.join(", ")),
+            (false, Some(overflow)) => write!(f, "{}, then {}", freqs.join(", "), overflow),
+        }
+    }
+}
+
+impl Answer for Repeats {
+    fn fields(&self) -> Vec<(&'static str, Json)> {
+        let occurrence = |prefix: &str, o: &Occurrence| {
+            vec![
+                (format!("{}changes", prefix), Json::from(o.changes)),
+                (format!("{}pass", prefix), Json::from(o.pass)),
+                (
+                    format!("{}line", prefix),
+                    o.index.map_or(Json::Null, |index| Json::from(index + 1)),
+                ),
+            ]
+        };
+        let repeats = self
+            .repeats
+            .iter()
+            .map(|r| {
+                let mut members = vec![("frequency".to_string(), Json::from(r.freq))];
+                members.extend(occurrence("", &r.at));
+                members.extend(occurrence("earlier_", &r.earlier));
+                Json::Object(members)
+            })
+            .collect();
+        let overflow = self.overflow.map_or(Json::Null, |overflow| {
+            Json::Object(occurrence("", &overflow.at))
+        });
+        vec![("repeats", Json::Array(repeats)), ("overflow", overflow)]
+    }
+}
+
+/// The frequency after every change, from the start up to the first
+/// repetition or over the first pass when the frequencies never repeat, as
+/// CSV with the columns `pass,index,delta,frequency,note`; the start has
+/// neither index nor delta. The notes mark the first minimum, the first
+/// maximum, the first repetition and the earlier occurrence of its
+/// frequency. The timeline stops after `passes` passes; the frequency
+/// overflowing `T` before that is an error
+pub fn timeline<T: Frequency>(nums: &[T], passes: u64) -> Result<String, Overflow> {
+    let len = nums.len() as u64;
+    let cut = passes.saturating_mul(len);
+    let (repetition, end) = match first_repetition(nums) {
+        Ok(repetition) => (repetition, repetition.map_or(len, |r| r.at.changes)),
+        // on the way to a repetition past the overflow
+        Err(overflow) if overflow.at.changes > cut => (None, cut),
+        Err(overflow) => return Err(overflow),
+    };
+    let changes = end.min(cut);
+    let freqs: Vec<T> = std::iter::once(T::ZERO)
+        .chain(
+            nums.iter()
+                .cycle()
+                .take(usize::try_from(changes).unwrap_or(usize::MAX))
+                .scan(T::ZERO, |acc, &n| {
+                    *acc = acc.checked_add(n)?;
+                    Some(*acc)
+                }),
+        )
+        .collect();
+    let min = (0..freqs.len()).min_by_key(|&k| freqs[k]);
+    let max = (0..freqs.len()).max_by_key(|&k| (freqs[k], Reverse(k)));
+
+    let mut csv = String::from("pass,index,delta,frequency,note\n");
+    for (k, freq) in freqs.iter().enumerate() {
+        let at = Occurrence::after(k as u64, nums.len());
+        let mut notes = Vec::new();
+        if min == Some(k) {
+            notes.push("min");
+        }
+        if max == Some(k) {
+            notes.push("max");
+        }
+        if let Some(repetition) = &repetition {
+            if repetition.earlier.changes == k as u64 {
+                notes.push("first repeat earlier");
+            }
+            if repetition.at.changes == k as u64 {
+                notes.push("first repeat");
+            }
+        }
+        let (index, delta) = match at.index {
+            Some(index) => (index.to_string(), nums[index].to_string()),
+            None => (String::new(), String::new()),
+        };
+        csv += &format!(
+            "{},{},{},{},{}\n",
+            at.pass,
+            index,
+            delta,
+            freq,
+            notes.join("; ")
+        );
+    }
+    Ok(csv)
+}
+
+/// Integer type the frequencies are computed with, set by `--width`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Width {
+    I32,
+    I64,
+    I128,
+}
+
+impl FromStr for Width {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Width, String> {
+        match s {
+            "32" => Ok(Width::I32),
+            "64" => Ok(Width::I64),
+            "128" => Ok(Width::I128),
+            _ => Err("expected 32, 64 or 128".to_string()),
+        }
+    }
+}
+
+/// The changes parsed with the integer type of the width
+#[derive(Debug, Clone, PartialEq)]
+pub enum Changes {
+    I32(Vec<i32>),
+    I64(Vec<i64>),
+    I128(Vec<i128>),
+}
+
+// one change by line with its sign, like `+3`
+fn write_changes<T: Display>(f: &mut fmt::Formatter, nums: &[T]) -> fmt::Result {
+    nums.iter().try_for_each(|num| writeln!(f, "{:+}", num))
+}
+
+/// The changes printed like the puzzle input, one by line with its sign
+impl Display for Changes {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Changes::I32(nums) => write_changes(f, nums),
+            Changes::I64(nums) => write_changes(f, nums),
+            Changes::I128(nums) => write_changes(f, nums),
+        }
+    }
+}
+
+/// Part 1 is the resulting frequency, part 2 the first one reached twice
+pub struct Day01 {
+    /// Frequencies reached twice listed by part 2
+    pub repeats: usize,
+    pub width: Width,
+    /// Passes at most in the timeline of `--csv`
+    pub passes: u64,
+}
+
+impl Default for Day01 {
+    fn default() -> Day01 {
+        Day01 {
+            repeats: 1,
+            width: Width::I32,
+            passes: 1_000,
+        }
+    }
+}
+
+impl Solution for Day01 {
+    const DAY: u8 = 1;
+    const README: &'static str = include_str!("../README.md");
+
+    type Input = Changes;
+    type Part1 = i128;
+    type Part2 = Repeats;
+
+    const FLAGS: &'static [Flag] = &[
+        Flag {
+            name: "repeats",
+            value: "K",
+            help: "first frequencies reached twice listed by part 2 (1)",
+        },
+        Flag {
+            name: "width",
+            value: "BITS",
+            help: "bits of the frequencies: 32, 64 or 128 (32)",
+        },
+        Flag {
+            name: "passes",
+            value: "N",
+            help: "passes at most in the timeline of --csv (1000)",
+        },
+    ];
+
+    fn set_flag(&mut self, flag: &str, value: &str) -> Result<(), String> {
+        match flag {
+            "repeats" => match flag_value(flag, value)? {
+                0 => return Err("Part 2 must list at least one frequency".to_string()),
+                repeats => self.repeats = repeats,
+            },
+            "width" => self.width = flag_value(flag, value)?,
+            "passes" => self.passes = flag_value(flag, value)?,
+            _ => return Err(format!("Unknown flag --{}", flag)),
+        }
+        Ok(())
+    }
+
+    fn parse(&self, input: &str) -> Result<Changes, ParseError> {
+        match self.width {
+            Width::I32 => get_nums(input).map(Changes::I32),
+            Width::I64 => get_nums(input).map(Changes::I64),
+            Width::I128 => get_nums(input).map(Changes::I128),
+        }
+    }
+
+    fn part1(&self, changes: &Changes) -> i128 {
+        let freq = match changes {
+            Changes::I32(nums) => sum_freq(nums).map(i128::from),
+            Changes::I64(nums) => sum_freq(nums).map(i128::from),
+            Changes::I128(nums) => sum_freq(nums),
+        };
+        freq.expect("overflow checked by get_nums")
+    }
+
+    fn part2(&self, changes: &Changes) -> Repeats {
+        match changes {
+            Changes::I32(nums) => Repeats::first(nums, self.repeats),
+            Changes::I64(nums) => Repeats::first(nums, self.repeats),
+            Changes::I128(nums) => Repeats::first(nums, self.repeats),
+        }
+    }
+
+    fn table(&self, changes: &Changes) -> Result<String, String> {
+        let csv = match changes {
+            Changes::I32(nums) => timeline(nums, self.passes),
+            Changes::I64(nums) => timeline(nums, self.passes),
+            Changes::I128(nums) => timeline(nums, self.passes),
+        };
+        csv.map_err(|overflow| overflow.to_string())
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        Changes::I32(generate_changes(rng, size)).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        compute_freq, compute_repetition, first_repetition, generate_changes, get_nums,
+        repetitions, sum_freq, timeline, Changes, Day01, Occurrence, Overflow, Repeats, Repetition,
+        Width,
+    };
+    use common::{Answer, Rng, Solution};
+    use std::collections::HashMap;
+
+    // the first `k` frequencies reached twice, applying the changes one at
+    // a time for up to `passes` passes
+    fn simulate(nums: &[i32], passes: usize, k: usize) -> Vec<Repetition> {
+        let mut seen: HashMap<i32, (u64, usize)> = HashMap::new();
+        seen.insert(0, (0, 1));
+        let mut repeats = Vec::new();
+        let mut freq = 0;
+        for (n, change) in nums.iter().cycle().take(nums.len() * passes).enumerate() {
+            freq += change;
+            let changes = n as u64 + 1;
+            let (first, count) = seen.entry(freq).or_insert((changes, 0));
+            *count += 1;
+            if *count == 2 {
+                repeats.push(Repetition {
+                    freq,
+                    at: Occurrence::after(changes, nums.len()),
+                    earlier: Occurrence::after(*first, nums.len()),
+                });
+                if repeats.len() == k {
+                    break;
+                }
+            }
+        }
+        repeats
+    }
+
+    #[test]
+    fn test_compute_freq() {
+        let tests: Vec<(String, i32)> = vec![
+            ("+1\n-2\n+3\n+1".to_string(), 3),
+            ("+1\n+1\n+1".to_string(), 3),
+            ("+1\n+1\n-2".to_string(), 0),
+            ("-1\n-2\n-3".to_string(), -6),
+        ];
+
+        tests
+            .iter()
+            .for_each(|(input, result)| assert_eq!(compute_freq::<i32>(input).unwrap(), *result));
+    }
+
+    #[test]
+    fn test_compute_repetition() {
+        let tests: Vec<(String, i32)> = vec![
+            ("+1\n-2\n+3\n+1\n".to_string(), 2),
+            ("+1\n-1\n".to_string(), 0),
+            ("+3\n+3\n+4\n-2\n-4\n".to_string(), 10),
+            ("-6\n+3\n+8\n+5\n-6\n".to_string(), 5),
+            ("+7\n+7\n-2\n-7\n-4\n".to_string(), 14),
+        ];
+
+        tests.iter().for_each(|(input, result)| {
+            let repetition = compute_repetition::<i32>(input).unwrap().unwrap();
+            assert_eq!(repetition.freq, *result)
+        });
+    }
+
+    #[test]
+    fn test_first_repetition() {
+        let freq = |nums: &[i32]| first_repetition(nums).unwrap().map(|r| r.freq);
+        // never repeating, drifting up or down
+        assert_eq!(freq(&[1, 1]), None);
+        assert_eq!(freq(&[-3, 1]), None);
+        assert_eq!(freq(&[5]), None);
+        assert_eq!(freq(&[]), None);
+        // no drift, back to 0 at the end of the first pass
+        assert_eq!(freq(&[0]), Some(0));
+        assert_eq!(freq(&[2, 3, -5]), Some(0));
+        // negative drift, back to 0 on the fourth pass
+        assert_eq!(freq(&[-7, 10, -4]), Some(0));
+        // a long way away, 1000000 passes
+        assert_eq!(freq(&[1_000_000, -999_999]), Some(1_000_000));
+
+        // the example repeating 2 on the second pass
+        let repetition = first_repetition(&[1, -2, 3, 1]).unwrap().unwrap();
+        assert_eq!(
+            repetition.at,
+            Occurrence {
+                changes: 6,
+                pass: 1,
+                index: Some(1),
+            }
+        );
+        assert_eq!(repetition.earlier, Occurrence::after(3, 4));
+        assert_eq!(
+            repetition.to_string(),
+            "2 after 6 changes (pass 1, line 2), first reached after 3 changes (pass 0, line 3)"
+        );
+        assert_eq!(
+            first_repetition(&[1, -1])
+                .unwrap()
+                .unwrap()
+                .earlier
+                .to_string(),
+            "at the start"
+        );
+    }
+
+    #[test]
+    fn test_repetitions() {
+        let freqs = |nums: &[i32]| -> Vec<i32> {
+            repetitions(nums).take(6).map(|r| r.unwrap().freq).collect()
+        };
+        assert_eq!(freqs(&[1, -2, 3, 1]), [2, 5, 8, 11, 14, 17]);
+        assert_eq!(freqs(&[2, 3, -5]), [0, 2, 5]);
+        assert_eq!(freqs(&[1, 1]), []);
+
+        let mut rng = Rng::new(0);
+        for _ in 0..1_000 {
+            let nums: Vec<i32> = (0..rng.range(1..=12))
+                .map(|_| rng.range(-10..=10) as i32)
+                .collect();
+            // the frequencies of a pass are less than 240 apart: when there
+            // is a repetition, the first one happens within 241 passes and
+            // is followed by a repetition on every pass
+            let repeats: Vec<Repetition> = repetitions(&nums)
+                .take(5)
+                .collect::<Result<_, _>>()
+                .unwrap();
+            assert_eq!(repeats, simulate(&nums, 250, 5), "{:?}", nums);
+        }
+    }
+
+    #[test]
+    fn test_overflow() {
+        assert_eq!(sum_freq(&[i32::MAX, -1, 1]), Ok(i32::MAX));
+        assert_eq!(sum_freq(&[i32::MAX, 1, -5]), Err(1));
+        assert_eq!(sum_freq(&[i64::MIN, -1]), Err(1));
+
+        let input = "+1\n+2147483646\n+1\n-5\n";
+        let err = get_nums::<i32>(input).unwrap_err();
+        assert_eq!(err.line(), Some(3));
+        assert_eq!(err.message(), "the frequency overflows i32 (entry 3)");
+        assert_eq!(compute_freq::<i64>(input).unwrap(), 2_147_483_643);
+
+        let err = get_nums::<i64>("+99999999999999999999\n").unwrap_err();
+        assert_eq!(err.line(), Some(1));
+        assert!(err.message().contains("invalid number"));
+        assert!(get_nums::<i128>("+99999999999999999999\n").is_ok());
+
+        // the frequencies repeating after an overflow are never reached, the
+        // frequency after 5 changes being out of range first
+        let nums = [i32::MAX - 1, 2 - i32::MAX];
+        let overflow = Overflow {
+            width: "i32",
+            at: Occurrence::after(5, 2),
+        };
+        assert_eq!(repetitions(&nums).collect::<Vec<_>>(), [Err(overflow)]);
+        assert_eq!(first_repetition(&nums), Err(overflow));
+        assert_eq!(
+            overflow.to_string(),
+            "the frequency overflows i32 after 5 changes (pass 2, line 1)"
+        );
+        let nums = [i64::from(i32::MAX) - 1, 2 - i64::from(i32::MAX)];
+        let freqs: Vec<i64> = repetitions(&nums)
+            .take(3)
+            .map(|r| r.unwrap().freq)
+            .collect();
+        assert_eq!(freqs, [2_147_483_646, 2_147_483_647, 2_147_483_648]);
+        // the repetitions before the overflow come first
+        let nums = [1, -1, 1_000_000_000];
+        let repeats: Vec<_> = repetitions(&nums).map(|r| r.map(|r| r.freq)).collect();
+        assert_eq!(
+            repeats,
+            [
+                Ok(0),
+                Ok(1_000_000_000),
+                Ok(2_000_000_000),
+                Err(Overflow {
+                    width: "i32",
+                    at: Occurrence::after(9, 3),
+                })
+            ]
+        );
+        let repeats = Repeats::first(&nums, 5);
+        assert_eq!(
+            repeats.to_string(),
+            "0, 1000000000, 2000000000, then the frequency overflows i32 after 9 changes \
+             (pass 2, line 3)"
+        );
+        assert_eq!(
+            repeats.fields()[1].1.to_string(),
+            "{\"changes\":9,\"pass\":2,\"line\":3}"
+        );
+        assert_eq!(
+            Repeats::first(&[i32::MAX - 1, 2 - i32::MAX], 1).to_string(),
+            "none, the frequency overflows i32 after 5 changes (pass 2, line 1)"
+        );
+        // nor the ones after more than 2^64 changes
+        assert_eq!(first_repetition(&[i128::MAX, i128::MIN]), Ok(None));
+        assert_eq!(
+            first_repetition(&[i128::MAX, -i128::MAX])
+                .unwrap()
+                .map(|r| r.at.changes),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn test_width() {
+        let mut day = Day01::default();
+        let input = "+2147483647\n+1\n-1\n";
+        assert!(day.parse(input).is_err());
+
+        common::set_flags(&mut day, &[("width".to_string(), "64".to_string())]).unwrap();
+        assert_eq!(day.width, Width::I64);
+        let nums = day.parse(input).unwrap();
+        assert_eq!(day.part1(&nums), 2_147_483_647);
+        assert_eq!(day.part2(&nums).to_string(), "2147483647");
+
+        day.width = Width::I128;
+        let nums = day
+            .parse("+170141183460469231731687303715884105727\n-1\n")
+            .unwrap();
+        assert_eq!(day.part1(&nums), i128::MAX - 1);
+        assert!(common::set_flags(&mut day, &[("width".to_string(), "16".to_string())]).is_err());
+    }
+
+    #[test]
+    fn test_get_nums_errors() {
+        let err = get_nums::<i32>("+1\n-2\n+x3\n").unwrap_err();
+        assert_eq!((err.line(), err.column()), (Some(3), Some(1)));
+        assert_eq!(
+            err.message(),
+            "invalid change `+x3` (entry 3): expected a number"
+        );
+
+        let err = get_nums::<i32>("+1, -2 +x, +1").unwrap_err();
+        assert_eq!((err.line(), err.column()), (Some(1), Some(8)));
+        assert!(err.message().contains("(entry 3)"));
+
+        let err = get_nums::<i32>("\n+1,é,+2\n").unwrap_err();
+        assert_eq!((err.line(), err.column()), (Some(2), Some(4)));
+
+        let err = get_nums::<i32>("+1 +2x").unwrap_err();
+        assert_eq!(
+            err.message(),
+            "invalid change `+2x` (entry 2): unexpected text at the end of the line"
+        );
+    }
+
+    #[test]
+    fn test_separators() {
+        let nums = vec![1, -2, 3, 1];
+        assert_eq!(get_nums::<i32>("+1, -2, +3, +1").unwrap(), nums);
+        assert_eq!(get_nums::<i32>("+1 -2\t+3\r\n+1\r\n").unwrap(), nums);
+        assert_eq!(get_nums::<i32>("+1,-2\n\n  +3 ,, +1,\n\n").unwrap(), nums);
+        assert_eq!(get_nums::<i32>(" ,\n").unwrap(), []);
+    }
+
+    #[test]
+    fn test_timeline() {
+        assert_eq!(
+            timeline(&[1, -2, 3, 1], 1_000).unwrap(),
+            "pass,index,delta,frequency,note\n\
+             0,,,0,\n\
+             0,0,1,1,\n\
+             0,1,-2,-1,min\n\
+             0,2,3,2,first repeat earlier\n\
+             0,3,1,3,\n\
+             1,0,1,4,max\n\
+             1,1,-2,2,first repeat\n"
+        );
+        // a single pass without a repetition
+        assert_eq!(
+            timeline(&[-1, -1], 1_000).unwrap(),
+            "pass,index,delta,frequency,note\n\
+             0,,,0,max\n\
+             0,0,-1,-1,\n\
+             0,1,-1,-2,min\n"
+        );
+        // cut after the given passes, an error when the frequency overflows
+        // before
+        assert_eq!(timeline(&[1, -2, 3, 1], 1).unwrap().lines().count(), 6);
+        assert_eq!(timeline(&[1, -1], 0).unwrap().lines().count(), 2);
+        let nums = [2_000_000_000, -1_000_000_000];
+        assert_eq!(
+            timeline(&nums, 1_000),
+            Err(Overflow {
+                width: "i32",
+                at: Occurrence::after(3, 2),
+            })
+        );
+        assert_eq!(
+            timeline(&nums, 1).unwrap(),
+            "pass,index,delta,frequency,note\n\
+             0,,,0,min\n\
+             0,0,2000000000,2000000000,max\n\
+             0,1,-1000000000,1000000000,\n"
+        );
+
+        let mut day = Day01::default();
+        common::set_flags(&mut day, &[("passes".to_string(), "1".to_string())]).unwrap();
+        let csv = day.table(&day.parse("+1, -2, +3, +1").unwrap()).unwrap();
+        assert_eq!(csv.lines().count(), 6);
+    }
+
+    #[test]
+    fn test_solution() {
+        let day = Day01::default();
+        let nums = day.parse("+1\n-2\n+3\n+1").unwrap();
+        assert_eq!(nums, Changes::I32(vec![1, -2, 3, 1]));
+        assert_eq!(day.part1(&nums), 3);
+        assert_eq!(day.part2(&nums).to_string(), "2");
+        assert_eq!(
+            day.part2(&Changes::I32(vec![1, 1])).to_string(),
+            "none, the frequencies never repeat"
+        );
+
+        let mut day = Day01::default();
+        common::set_flags(&mut day, &[("repeats".to_string(), "3".to_string())]).unwrap();
+        assert_eq!(day.part2(&nums).to_string(), "2, 5, 8");
+        assert!(common::set_flags(&mut day, &[("repeats".to_string(), "0".to_string())]).is_err());
+        assert_eq!(
+            day.set_flag("pass", "3"),
+            Err("Unknown flag --pass".to_string())
+        );
+        assert_eq!(day.passes, 1_000);
+
+        let repeats = Repeats::first(&[1, -1], 1);
+        assert_eq!(
+            repeats.fields()[0].1.to_string(),
+            "[{\"frequency\":0,\"changes\":2,\"pass\":0,\"line\":2,\
+             \"earlier_changes\":0,\"earlier_pass\":0,\"earlier_line\":null}]"
+        );
+    }
+
+    #[test]
+    fn test_generate() {
+        let day = Day01::default();
+        for seed in 0..20 {
+            let mut rng = Rng::new(seed);
+            let changes = generate_changes(&mut rng, seed as usize * 10);
+            assert_eq!(changes.len(), (seed as usize * 10).max(1));
+            assert!(changes.iter().sum::<i32>().unsigned_abs() < changes.len() as u32);
+
+            let input = day.generate(&mut rng, 50);
+            let nums = day.parse(&input).unwrap();
+            assert!(matches!(&nums, Changes::I32(nums) if nums.len() == 50));
+            assert_eq!(day.part2(&nums).repeats.len(), 1);
+        }
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let mut rng = Rng::new(0);
+        for width in &[Width::I32, Width::I64, Width::I128] {
+            let day = Day01 {
+                width: *width,
+                ..Day01::default()
+            };
+            for _ in 0..200 {
+                let count = rng.range(0..=50) as usize;
+                // small enough for the frequencies not to overflow
+                let shift = if *width == Width::I32 { 40 } else { 8 };
+                let mut number = || (rng.next_u64() >> rng.range(shift..=63)) as i64 - 1_000;
+                let changes = match width {
+                    Width::I32 => Changes::I32((0..count).map(|_| number() as i32).collect()),
+                    Width::I64 => Changes::I64((0..count).map(|_| number()).collect()),
+                    Width::I128 => Changes::I128((0..count).map(|_| number().into()).collect()),
+                };
+                assert_eq!(day.parse(&changes.to_string()), Ok(changes));
+            }
+        }
+        assert_eq!(Changes::I32(vec![1, -2, 0]).to_string(), "+1\n-2\n+0\n");
+        assert_eq!(Changes::I64(vec![]).to_string(), "");
+    }
+}
@@ -0,0 +1,241 @@
+//! Day 2: Inventory Management System
+
+use common::{parse_lines, ParseError, Rng, Solution};
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+/// Distinct numbers of times the letters appear in a box ID
+pub fn code_occurrences(code: &str) -> HashSet<u32> {
+    code.chars()
+        .fold(HashMap::new(), |mut lc, l| {
+            *lc.entry(l).or_insert(0) += 1;
+            lc
+        })
+        .values()
+        .cloned()
+        .collect()
+}
+
+/// For every number of occurrences, how many box IDs have a letter
+/// appearing exactly that many times
+pub fn codes_occurrences_count(codes: &str) -> HashMap<u32, u32> {
+    codes
+        .lines()
+        .map(code_occurrences)
+        .fold(HashMap::new(), |oc, hs: HashSet<u32>| {
+            hs.into_iter().fold(oc, |mut oc, o| {
+                *oc.entry(o).or_insert(0) += 1;
+                oc
+            })
+        })
+}
+
+/// Number of IDs with a letter appearing twice times the number of IDs
+/// with a letter appearing three times
+pub fn checksum(codes: &str) -> u32 {
+    let oc = codes_occurrences_count(codes);
+    oc.get(&2).unwrap_or(&0) * oc.get(&3).unwrap_or(&0)
+}
+
+/// Letters in the same position in both IDs
+pub fn common_chars(a: &str, b: &str) -> String {
+    a.chars()
+        .zip(b.chars())
+        .filter(|(ac, bc)| ac == bc)
+        .map(|(a, _)| a)
+        .collect()
+}
+
+/// Common letters of the two IDs differing by exactly one character
+pub fn find_codes_common_chars(codes: &str) -> String {
+    use itertools::Itertools;
+
+    codes
+        .lines()
+        .cartesian_product(codes.lines().skip(1))
+        .map(|(a, b)| (a, common_chars(a, b)))
+        .filter(|(a, b)| a.len() - 1 == b.len())
+        .map(|(_, b)| b)
+        .next()
+        .unwrap()
+        .to_string()
+}
+
+/// Check that a box ID is made of lowercase letters
+pub fn check_code(code: &str) -> Result<(), ParseError> {
+    match code.find(|c: char| !c.is_ascii_lowercase()) {
+        Some(pos) => Err(ParseError::at(pos, 1, "expected a lowercase letter")),
+        None if code.is_empty() => Err(ParseError::at(0, 1, "expected a box ID")),
+        None => Ok(()),
+    }
+}
+
+/// Random box IDs of 26 letters, two of them differing by the letter at
+/// the returned position
+pub fn generate_codes(rng: &mut Rng, count: usize) -> (Vec<String>, usize) {
+    let mut letter = || (b'a' + rng.below(26) as u8) as char;
+    let mut codes: Vec<String> = (0..count.max(2) - 1)
+        .map(|_| (0..26).map(|_| letter()).collect())
+        .collect();
+
+    let base = rng.index(codes.len());
+    let pos = rng.index(26);
+    let mut correct: Vec<char> = codes[base].chars().collect();
+    let old = correct[pos];
+    correct[pos] = (b'a' + (old as u8 - b'a' + rng.range(1..=25) as u8) % 26) as char;
+    codes.insert(rng.index(codes.len() + 1), correct.into_iter().collect());
+    (codes, pos)
+}
+
+/// Part 1 is the checksum of the box IDs, part 2 the letters in common
+/// between the two correct IDs
+#[derive(Default)]
+pub struct Day02;
+
+impl Solution for Day02 {
+    const DAY: u8 = 2;
+    const README: &'static str = include_str!("../README.md");
+
+    type Input = String;
+    type Part1 = u32;
+    type Part2 = String;
+
+    fn parse(&self, input: &str) -> Result<String, ParseError> {
+        parse_lines(input, check_code)?;
+        Ok(input.to_string())
+    }
+
+    fn part1(&self, codes: &String) -> u32 {
+        checksum(codes)
+    }
+
+    fn part2(&self, codes: &String) -> String {
+        find_codes_common_chars(codes)
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        let (codes, _) = generate_codes(rng, size);
+        codes.iter().map(|code| format!("{}\n", code)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::{Rng, Solution};
+    use std::collections::HashSet;
+    use std::iter::FromIterator;
+
+    use super::{
+        check_code, checksum, code_occurrences, codes_occurrences_count, common_chars,
+        find_codes_common_chars, generate_codes, Day02,
+    };
+
+    #[test]
+    fn test_code_occurrences() {
+        let tests: Vec<(&str, Vec<u32>)> = vec![
+            ("abcdef", [1].to_vec()),
+            ("bababc", [1, 2, 3].to_vec()),
+            ("abbcde", [1, 2].to_vec()),
+            ("abcccd", [1, 3].to_vec()),
+            ("aabcdd", [1, 2].to_vec()),
+            ("abcdee", [1, 2].to_vec()),
+            ("ababab", [3].to_vec()),
+        ];
+
+        tests.iter().for_each(|(str, os)| {
+            assert_eq!(
+                code_occurrences(str),
+                HashSet::from_iter(os.iter().cloned())
+            );
+        });
+    }
+
+    static CODES: &str = "abcdef\n\
+                          bababc\n\
+                          abbcde\n\
+                          abcccd\n\
+                          aabcdd\n\
+                          abcdee\n\
+                          ababab";
+
+    #[test]
+    fn test_codes_occurences_count() {
+        let oc = codes_occurrences_count(CODES);
+
+        assert_eq!(*oc.get(&2).unwrap(), 4);
+        assert_eq!(*oc.get(&3).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_checksum() {
+        assert_eq!(checksum(CODES), 12);
+    }
+
+    #[test]
+    fn test_common_chars() {
+        let tests: Vec<(&str, &str, &str)> = vec![
+            ("asdf", "asdf", "asdf"),
+            ("asdf", "asdg", "asd"),
+            ("asdf", "asgf", "asf"),
+            ("asdf", "agdf", "adf"),
+            ("asdf", "gsdf", "sdf"),
+            ("asdf", "zzzz", ""),
+        ];
+
+        tests.iter().for_each(|(a, b, t)| {
+            assert_eq!(common_chars(a, b), t.to_string());
+        })
+    }
+
+    #[test]
+    fn test_find_codes_common_chars() {
+        let codes: &str = "abcde\n\
+                           fghij\n\
+                           klmno\n\
+                           pqrst\n\
+                           fguij\n\
+                           axcye\n\
+                           wvxyz";
+
+        assert_eq!(find_codes_common_chars(codes), "fgij".to_string());
+    }
+
+    #[test]
+    fn test_check_code() {
+        assert_eq!(check_code("abcdef"), Ok(()));
+        assert_eq!(
+            check_code("abCdef").unwrap_err().message(),
+            "expected a lowercase letter"
+        );
+        assert!(check_code("").is_err());
+
+        let err = Day02.parse("abcdef\nbababc\nabb cde").unwrap_err();
+        assert_eq!((err.line(), err.column()), (Some(3), Some(4)));
+    }
+
+    #[test]
+    fn test_solution() {
+        let codes = Day02.parse(CODES).unwrap();
+        assert_eq!(Day02.part1(&codes), 12);
+
+        let codes = Day02
+            .parse("abcde\nfghij\nklmno\npqrst\nfguij\naxcye\nwvxyz")
+            .unwrap();
+        assert_eq!(Day02.part2(&codes), "fgij".to_string());
+    }
+
+    #[test]
+    fn test_generate() {
+        for seed in 0..10 {
+            let mut rng = Rng::new(seed);
+            let (codes, pos) = generate_codes(&mut rng, 100);
+            assert_eq!(codes.len(), 100);
+            let input: String = codes.iter().map(|code| format!("{}\n", code)).collect();
+            let codes = Day02.parse(&input).unwrap();
+            assert_eq!(Day02.part2(&codes).len(), 25);
+            assert!(pos < 26);
+
+            assert!(Day02.parse(&Day02.generate(&mut rng, 0)).is_ok());
+        }
+    }
+}
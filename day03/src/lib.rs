@@ -0,0 +1,560 @@
+//! Day 3: No Matter How You Slice It
+
+use common::{
+    parse_lines, Answer, BoundingBox, Grid, Image, ParseError, Point, Rgb, Rng, Scanner, Solution,
+};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt::{self, Display};
+
+#[derive(Debug, PartialEq)]
+/// Rectangle of fabric claimed by an elf, `#id @ x,y: wxh`
+pub struct Claim {
+    pub id: u32,
+    /// inches from the left edge
+    pub x: u32,
+    /// inches from the top edge
+    pub y: u32,
+    pub w: u32,
+    pub h: u32,
+}
+
+impl Claim {
+    pub fn new(id: u32, x: u32, y: u32, w: u32, h: u32) -> Claim {
+        Claim { id, x, y, w, h }
+    }
+
+    /// Whether the claim ends within the coordinates of a [`Point`], which
+    /// [`parse_claim`] checks
+    pub fn fits(&self) -> bool {
+        let fits = |start: u32, size: u32| {
+            start
+                .checked_add(size)
+                .is_some_and(|end| i32::try_from(end).is_ok())
+        };
+        fits(self.x, self.w) && fits(self.y, self.h)
+    }
+
+    /// Square inches covered by the claim, none if it is empty; panics if
+    /// the claim does not fit
+    pub fn bounds(&self) -> Option<BoundingBox> {
+        assert!(self.fits(), "Claim {} out of range", self);
+        if self.w == 0 || self.h == 0 {
+            return None;
+        }
+        let min = Point::new(self.x as i32, self.y as i32);
+        Some(BoundingBox::with_size(
+            min,
+            self.w as usize,
+            self.h as usize,
+        ))
+    }
+}
+
+/// Printed in the format of the puzzle, `#123 @ 3,2: 5x4`
+impl Display for Claim {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "#{} @ {},{}: {}x{}",
+            self.id, self.x, self.y, self.w, self.h
+        )
+    }
+}
+
+/// Largest claim in square inches, every square inch of a claim is visited
+/// to register it
+pub const MAX_CLAIM_AREA: u64 = 1 << 22;
+
+/// Parse a claim like `#123 @ 3,2: 5x4`, the sides of the claim must end
+/// within the coordinates of a [`Point`] and it must cover at most
+/// [`MAX_CLAIM_AREA`] square inches
+pub fn parse_claim(line: &str) -> Result<Claim, ParseError> {
+    let mut s = Scanner::new(line);
+    s.literal("#")?;
+    let id = s.number()?;
+    s.literal(" @ ")?;
+    let start = s.pos();
+    let x = s.number()?;
+    s.literal(",")?;
+    let y = s.number()?;
+    s.literal(": ")?;
+    let w = s.number()?;
+    s.literal("x")?;
+    let h = s.number()?;
+    s.end()?;
+
+    let claim = Claim::new(id, x, y, w, h);
+    if !claim.fits() {
+        return Err(ParseError::at(
+            start,
+            line.len() - start,
+            format!("claim reaching past {}", i32::MAX),
+        ));
+    }
+    if u64::from(w) * u64::from(h) > MAX_CLAIM_AREA {
+        return Err(ParseError::at(
+            start,
+            line.len() - start,
+            format!("claim larger than {} square inches", MAX_CLAIM_AREA),
+        ));
+    }
+    Ok(claim)
+}
+
+/// Parse one claim per line
+pub fn parse_claims(claimsstr: &str) -> Result<Vec<Claim>, ParseError> {
+    parse_lines(claimsstr, parse_claim)
+}
+
+/// Largest fabric registered on a dense grid, the claims spread further
+/// apart are registered in a map of the square inches claimed
+pub const MAX_GRID_AREA: u64 = 1 << 24;
+
+/// Number of claims on every square inch of the fabric
+#[derive(Debug, Clone, PartialEq)]
+pub enum Register {
+    /// Smallest piece of fabric holding all the claims
+    Dense(Grid<u32>),
+    /// Only the square inches claimed
+    Sparse(HashMap<Point, u32>),
+}
+
+impl Register {
+    /// Number of claims on a square inch
+    pub fn get(&self, p: Point) -> u32 {
+        match self {
+            Register::Dense(reg) => reg.get(p).copied().unwrap_or(0),
+            Register::Sparse(reg) => reg.get(&p).copied().unwrap_or(0),
+        }
+    }
+}
+
+/// Add a claim to the register counting the claims on every square inch,
+/// the register must cover the claim
+pub fn register_claim(mut reg: Grid<u32>, claim: &Claim) -> Grid<u32> {
+    if let Some(bounds) = claim.bounds() {
+        bounds.points().for_each(|p| reg[p] += 1);
+    }
+    reg
+}
+
+/// Number of claims on every square inch, on a grid for the smallest piece
+/// of fabric holding all the claims unless it is larger than
+/// [`MAX_GRID_AREA`]
+pub fn register_claims(claims: &[Claim]) -> Register {
+    let bounds = BoundingBox::from_points(
+        claims
+            .iter()
+            .filter_map(Claim::bounds)
+            .flat_map(|bounds| vec![bounds.min, bounds.max]),
+    )
+    .unwrap_or_else(|| BoundingBox::new(Point::new(0, 0), Point::new(0, 0)));
+    if bounds.width() as u64 * bounds.height() as u64 <= MAX_GRID_AREA {
+        return Register::Dense(claims.iter().fold(Grid::new(bounds, 0), register_claim));
+    }
+
+    let mut reg = HashMap::new();
+    for bounds in claims.iter().filter_map(Claim::bounds) {
+        bounds
+            .points()
+            .for_each(|p| *reg.entry(p).or_insert(0) += 1);
+    }
+    Register::Sparse(reg)
+}
+
+/// Square inches within two or more claims
+pub fn count_overlapping_claims(reg: &Register) -> u32 {
+    let overlapping = |n: &u32| *n > 1;
+    match reg {
+        Register::Dense(reg) => reg.values().iter().filter(|n| overlapping(n)).count() as u32,
+        Register::Sparse(reg) => reg.values().filter(|n| overlapping(n)).count() as u32,
+    }
+}
+
+/// ID of the only claim that doesn't overlap any other, none when they
+/// all overlap another one
+pub fn find_non_overlapping_claim(claims: &[Claim], reg: &Register) -> Option<u32> {
+    claims
+        .iter()
+        .find(|claim| {
+            claim
+                .bounds()
+                .is_none_or(|bounds| bounds.points().all(|p| reg.get(p) == 1))
+        })
+        .map(|claim| claim.id)
+}
+
+/// ID of the claim overlapping no other, if any; printed `no such claim`
+/// otherwise
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Intact(pub Option<u32>);
+
+impl Display for Intact {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Some(id) => write!(f, "{}", id),
+            None => write!(f, "no such claim"),
+        }
+    }
+}
+
+impl Answer for Intact {}
+
+/// Random claims numbered from 1, all overlapping another one but the
+/// returned claim
+pub fn generate_claims(rng: &mut Rng, count: usize) -> (Vec<Claim>, u32) {
+    let overlap = |a: &Claim, b: &Claim| {
+        a.x < b.x + b.w && b.x < a.x + a.w && a.y < b.y + b.h && b.y < a.y + a.h
+    };
+
+    // the intact claim is marked with the ID 1 until the claims are shuffled
+    let mut claims: Vec<Claim> = Vec::new();
+    while claims.len() < count.max(3) {
+        // the second claim starts on the first one, the next ones on any
+        // claim before them, so that they all overlap another one
+        let anchor = match claims.len() {
+            0 | 1 => None,
+            2 => Some(&claims[1]),
+            n => Some(&claims[1 + rng.index(n - 1)]),
+        };
+        let (x, y) = match anchor {
+            Some(a) => (
+                a.x + rng.below(a.w as u64) as u32,
+                a.y + rng.below(a.h as u64) as u32,
+            ),
+            None => (rng.range(0..=970) as u32, rng.range(0..=970) as u32),
+        };
+        let (w, h) = (rng.range(1..=29) as u32, rng.range(1..=29) as u32);
+        let claim = Claim::new(claims.is_empty() as u32, x, y, w, h);
+        if claims.first().is_none_or(|intact| !overlap(&claim, intact)) {
+            claims.push(claim);
+        }
+    }
+
+    rng.shuffle(&mut claims);
+    let mut intact_id = 0;
+    for (n, claim) in claims.iter_mut().enumerate() {
+        if claim.id == 1 {
+            intact_id = n as u32 + 1;
+        }
+        claim.id = n as u32 + 1;
+    }
+    (claims, intact_id)
+}
+
+/// Draw the fabric: the square inches claimed once in blue, the ones
+/// claimed more than once in red and the claim not overlapping any other
+/// in green; none when the claims are spread too far apart to be drawn
+pub fn render_claims(claims: &[Claim]) -> Option<Image> {
+    let reg = match register_claims(claims) {
+        Register::Dense(reg) => reg,
+        Register::Sparse(_) => return None,
+    };
+    let intact = claims.iter().find_map(|claim| {
+        claim
+            .bounds()
+            .filter(|bounds| bounds.points().all(|p| reg[p] == 1))
+    });
+    Some(Image::from_grid(&reg, |p, &n| match n {
+        0 => Rgb(30, 30, 40),
+        1 if intact.is_some_and(|bounds| bounds.contains(p)) => Rgb(80, 200, 120),
+        1 => Rgb(70, 130, 180),
+        _ => Rgb(220, 60, 60),
+    }))
+}
+
+/// Part 1 counts the square inches claimed more than once, part 2 finds
+/// the claim not overlapping any other
+#[derive(Default)]
+pub struct Day03;
+
+impl Solution for Day03 {
+    const DAY: u8 = 3;
+    const README: &'static str = include_str!("../README.md");
+
+    type Input = Vec<Claim>;
+    type Part1 = u32;
+    type Part2 = Intact;
+
+    fn parse(&self, input: &str) -> Result<Vec<Claim>, ParseError> {
+        parse_claims(input)
+    }
+
+    fn part1(&self, claims: &Vec<Claim>) -> u32 {
+        count_overlapping_claims(&register_claims(claims))
+    }
+
+    fn part2(&self, claims: &Vec<Claim>) -> Intact {
+        Intact(find_non_overlapping_claim(claims, &register_claims(claims)))
+    }
+
+    fn render(&self, claims: &Vec<Claim>) -> Option<Image> {
+        render_claims(claims)
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        let (claims, _) = generate_claims(rng, size);
+        claims.iter().map(|claim| format!("{}\n", claim)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        count_overlapping_claims, find_non_overlapping_claim, generate_claims, parse_claim,
+        parse_claims, register_claim, register_claims, render_claims, Claim, Day03, Intact,
+        Register, MAX_CLAIM_AREA,
+    };
+    use common::{BoundingBox, Grid, Point, Rgb, Rng, Solution};
+
+    #[test]
+    fn test_parse_claim() {
+        let tests = [
+            ("#1 @ 1,1: 1x1", Claim::new(1, 1, 1, 1, 1)),
+            ("#12 @ 12,12: 12x12", Claim::new(12, 12, 12, 12, 12)),
+            (
+                "#123 @ 123,123: 123x123",
+                Claim::new(123, 123, 123, 123, 123),
+            ),
+            (
+                "#1234 @ 1234,1234: 1234x1234",
+                Claim::new(1234, 1234, 1234, 1234, 1234),
+            ),
+        ];
+
+        tests
+            .iter()
+            .for_each(|(s, c)| assert_eq!(parse_claim(s).unwrap(), *c));
+    }
+
+    #[test]
+    fn test_parse_claims_errors() {
+        let err = parse_claims("#1 @ 1,3: 4x4\n#2 @ 3;1: 4x4").unwrap_err();
+        assert_eq!((err.line(), err.column()), (Some(2), Some(7)));
+        assert_eq!(err.message(), "expected `,`");
+
+        let err = parse_claims("#1 @ 1,3: 4x").unwrap_err();
+        assert_eq!(
+            (err.column(), err.message()),
+            (Some(13), "expected a number")
+        );
+
+        let err = parse_claims("#1 @ 2147483647,0: 2x2").unwrap_err();
+        assert_eq!(
+            (err.column(), err.message()),
+            (Some(6), "claim reaching past 2147483647")
+        );
+        assert!(parse_claim("#1 @ 0,3000000000: 1x1").is_err());
+        assert!(parse_claim("#1 @ 0,2147483646: 1x1").is_ok());
+
+        let err = parse_claims("#1 @ 0,0: 100000x100000").unwrap_err();
+        assert_eq!(
+            (err.column(), err.message()),
+            (Some(6), "claim larger than 4194304 square inches")
+        );
+        assert!(parse_claim("#1 @ 0,0: 2048x2048").is_ok());
+    }
+
+    #[test]
+    fn test_spread_claims() {
+        // the fabric holding both would take gigabytes as a grid
+        let claims = parse_claims(
+            "#1 @ 2000000000,0: 2x2\n\
+             #2 @ 0,0: 2x2\n\
+             #3 @ 2000000001,1: 2x2",
+        )
+        .unwrap();
+        let reg = register_claims(&claims);
+        assert!(matches!(reg, Register::Sparse(_)));
+        assert_eq!(reg.get(Point::new(2_000_000_001, 1)), 2);
+        assert_eq!(count_overlapping_claims(&reg), 1);
+        assert_eq!(find_non_overlapping_claim(&claims, &reg), Some(2));
+        assert_eq!(render_claims(&claims), None);
+    }
+
+    // register of the claims on the fabric of the example, `#1 @ 1,3: 4x4`
+    // `#2 @ 3,1: 4x4` and `#3 @ 5,5: 2x2`
+    fn register(claims: &[Claim]) -> Grid<u32> {
+        let bounds = BoundingBox::new(Point::new(0, 0), Point::new(7, 7));
+        claims.iter().fold(Grid::new(bounds, 0), register_claim)
+    }
+
+    // claimed square inches of the register
+    fn claimed(reg: &Grid<u32>) -> Vec<(Point, u32)> {
+        reg.iter()
+            .filter(|(_, n)| **n > 0)
+            .map(|(p, n)| (p, *n))
+            .collect()
+    }
+
+    #[test]
+    fn test_register_claim_rect() {
+        let reg = register(&[Claim::new(1, 1, 1, 1, 3)]);
+        let result = vec![
+            (Point::new(1, 1), 1),
+            (Point::new(1, 2), 1),
+            (Point::new(1, 3), 1),
+        ];
+        assert_eq!(claimed(&reg), result);
+
+        let reg = register(&[Claim::new(1, 1, 1, 3, 1)]);
+        let result = vec![
+            (Point::new(1, 1), 1),
+            (Point::new(2, 1), 1),
+            (Point::new(3, 1), 1),
+        ];
+        assert_eq!(claimed(&reg), result);
+
+        assert_eq!(claimed(&register(&[Claim::new(1, 1, 1, 0, 3)])), vec![]);
+    }
+
+    #[test]
+    fn test_register_claim_same_claim() {
+        let reg = register(&[Claim::new(1, 1, 1, 1, 3), Claim::new(1, 1, 1, 1, 3)]);
+        let result = vec![
+            (Point::new(1, 1), 2),
+            (Point::new(1, 2), 2),
+            (Point::new(1, 3), 2),
+        ];
+        assert_eq!(claimed(&reg), result);
+    }
+
+    #[test]
+    fn test_register_claim_overlapping_claim() {
+        let reg = register(&[Claim::new(1, 1, 1, 1, 3), Claim::new(1, 1, 1, 3, 1)]);
+        let result = vec![
+            (Point::new(1, 1), 2),
+            (Point::new(2, 1), 1),
+            (Point::new(3, 1), 1),
+            (Point::new(1, 2), 1),
+            (Point::new(1, 3), 1),
+        ];
+        assert_eq!(claimed(&reg), result);
+    }
+
+    /*
+    ........
+    ...2222.
+    ...2222.
+    .11XX22.
+    .11XX22.
+    .111133.
+    .111133.
+    ........
+    */
+    #[test]
+    fn test_register_claims_example() {
+        let claims = "#1 @ 1,3: 4x4\n\
+                      #2 @ 3,1: 4x4\n\
+                      #3 @ 5,5: 2x2";
+
+        let result = match register_claims(&parse_claims(claims).unwrap()) {
+            Register::Dense(result) => result,
+            reg => panic!("{:?}", reg),
+        };
+        assert_eq!(
+            result.bounds(),
+            BoundingBox::new(Point::new(1, 1), Point::new(6, 6))
+        );
+
+        // square inches claimed once and twice
+        let once = [
+            Claim::new(1, 1, 3, 2, 4),
+            Claim::new(1, 1, 5, 6, 2),
+            Claim::new(1, 3, 1, 4, 2),
+            Claim::new(1, 5, 1, 2, 6),
+            Claim::new(1, 5, 5, 2, 2),
+        ];
+        for claim in once.chunks(1) {
+            for (p, claims_n) in claimed(&register(claim)) {
+                assert_eq!(result[p], claims_n, "{}", p);
+            }
+        }
+        let twice = register(&[Claim::new(1, 3, 3, 2, 2), Claim::new(1, 3, 3, 2, 2)]);
+        for (p, claims_n) in claimed(&twice) {
+            assert_eq!(result[p], claims_n, "{}", p);
+        }
+    }
+
+    #[test]
+    fn test_count_overlapping_claims() {
+        let claims = "#1 @ 1,3: 4x4\n\
+                      #2 @ 3,1: 4x4\n\
+                      #3 @ 5,5: 2x2";
+
+        let result = register_claims(&parse_claims(claims).unwrap());
+
+        assert_eq!(count_overlapping_claims(&result), 4);
+    }
+
+    #[test]
+    fn test_find_non_overlapping_claim() {
+        let input = "#1 @ 1,3: 4x4\n\
+                     #2 @ 3,1: 4x4\n\
+                     #3 @ 5,5: 2x2";
+        let claims = parse_claims(input).unwrap();
+        let reg = register_claims(&claims);
+
+        assert_eq!(find_non_overlapping_claim(&claims, &reg), Some(3));
+
+        let claims = parse_claims("#1 @ 1,1: 2x2\n#2 @ 2,2: 2x2").unwrap();
+        let reg = register_claims(&claims);
+        assert_eq!(find_non_overlapping_claim(&claims, &reg), None);
+        assert_eq!(Day03.part2(&claims).to_string(), "no such claim");
+    }
+
+    #[test]
+    fn test_solution() {
+        let input = "#1 @ 1,3: 4x4\n\
+                     #2 @ 3,1: 4x4\n\
+                     #3 @ 5,5: 2x2";
+        let claims = Day03.parse(input).unwrap();
+
+        assert_eq!(Day03.part1(&claims), 4);
+        assert_eq!(Day03.part2(&claims), Intact(Some(3)));
+    }
+
+    #[test]
+    fn test_render_claims() {
+        let claims = parse_claims("#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\n#3 @ 5,5: 2x2").unwrap();
+        let image = render_claims(&claims).unwrap();
+
+        // the fabric from 1,1 to 6,6
+        assert_eq!((image.width(), image.height()), (6, 6));
+        assert_eq!(image.get(0, 0), Some(Rgb(30, 30, 40)));
+        assert_eq!(image.get(0, 2), Some(Rgb(70, 130, 180)));
+        assert_eq!(image.get(2, 2), Some(Rgb(220, 60, 60)));
+        assert_eq!(image.get(5, 5), Some(Rgb(80, 200, 120)));
+    }
+
+    #[test]
+    fn test_generate() {
+        for seed in 0..10 {
+            let mut rng = Rng::new(seed);
+            let (claims, intact) = generate_claims(&mut rng, 200);
+            assert_eq!(claims.len(), 200);
+            let reg = register_claims(&claims);
+            assert_eq!(find_non_overlapping_claim(&claims, &reg), Some(intact));
+
+            let claims = Day03.parse(&Day03.generate(&mut rng, 1)).unwrap();
+            assert_eq!(claims.len(), 3);
+            assert!(Day03.part2(&claims).0.is_some_and(|id| id > 0));
+        }
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let mut rng = Rng::new(0);
+        for _ in 0..1_000 {
+            let mut number = || rng.next_u64() as u32 >> rng.below(32);
+            let claim = Claim::new(number(), number(), number(), number(), number());
+            let area = u64::from(claim.w) * u64::from(claim.h);
+            match claim.fits() && area <= MAX_CLAIM_AREA {
+                true => assert_eq!(parse_claim(&claim.to_string()), Ok(claim)),
+                false => assert!(parse_claim(&claim.to_string()).is_err()),
+            }
+        }
+        assert_eq!(Claim::new(123, 3, 2, 5, 4).to_string(), "#123 @ 3,2: 5x4");
+    }
+}
@@ -0,0 +1,623 @@
+//! Day 4: Repose Record
+
+use common::{ParseError, Rng, Scanner, Solution};
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+
+/// A line of the guards' records, asleep and awake carry the minute
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Event {
+    Start(u32), // start of a shift
+    Asleep(u8),
+    Awake(u8),
+}
+
+/// Printed as the text of a record after its timestamp, like
+/// `Guard #10 begins shift`
+impl Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Event::Start(id) => write!(f, "Guard #{} begins shift", id),
+            Event::Asleep(_) => write!(f, "falls asleep"),
+            Event::Awake(_) => write!(f, "wakes up"),
+        }
+    }
+}
+
+/// How many times a guard was asleep on each minute of the midnight hour
+pub struct Guard {
+    pub id: u32,
+    pub minutes_slept: [u32; 60],
+}
+
+impl Guard {
+    pub fn new(id: u32) -> Guard {
+        Guard {
+            id,
+            minutes_slept: [0; 60],
+        }
+    }
+}
+
+// minute of a timestamp like `[1518-11-01 00:05]`, only the minute matters
+// as the guards sleep during the midnight hour
+fn parse_minute(s: &mut Scanner) -> Result<u8, ParseError> {
+    s.literal("[")?;
+    s.number::<u32>()?;
+    s.literal("-")?;
+    s.number::<u8>()?;
+    s.literal("-")?;
+    s.number::<u8>()?;
+    s.literal(" ")?;
+    s.number::<u8>()?;
+    s.literal(":")?;
+    let pos = s.pos();
+    let minute = s.number::<u8>()?;
+    if minute >= 60 {
+        return Err(ParseError::at(pos, 2, "expected a minute below 60"));
+    }
+    s.literal("] ")?;
+    Ok(minute)
+}
+
+/// Parse a record like `[1518-11-01 00:05] falls asleep`
+pub fn parse_event(line: &str) -> Result<Event, ParseError> {
+    let mut s = Scanner::new(line);
+    let minute = parse_minute(&mut s)?;
+
+    let event = if s.rest().starts_with("Guard") {
+        s.literal("Guard #")?;
+        let id = s.number()?;
+        s.literal(" begins shift")?;
+        Event::Start(id)
+    } else if s.rest().starts_with("falls") {
+        s.literal("falls asleep")?;
+        Event::Asleep(minute)
+    } else if s.rest().starts_with("wakes") {
+        s.literal("wakes up")?;
+        Event::Awake(minute)
+    } else {
+        return Err(ParseError::at(
+            s.pos(),
+            s.rest().len(),
+            "expected `Guard #`, `falls asleep` or `wakes up`",
+        ));
+    };
+    s.end()?;
+
+    Ok(event)
+}
+
+/// Parse the records, returning the events _in chronological order_; the
+/// shifts must be replayable, with a guard on shift falling asleep before
+/// waking up
+pub fn parse_events(input: &str) -> Result<Vec<Event>, ParseError> {
+    let mut lines: Vec<(usize, &str)> = input.lines().enumerate().collect();
+    lines.sort_unstable_by_key(|&(_, line)| line);
+
+    let mut events = Vec::with_capacity(lines.len());
+    // the guard on shift and whether they are asleep
+    let mut on_shift: Option<(u32, bool)> = None;
+    for (idx, line) in lines {
+        let event = parse_event(line).map_err(|err| err.on_line(idx, line))?;
+        let next = match (event, on_shift) {
+            (Event::Start(_), Some((id, true))) => Err(format!(
+                "guard #{} still asleep at the start of the shift",
+                id
+            )),
+            (Event::Start(id), _) => Ok((id, false)),
+            (Event::Asleep(_), None) => {
+                Err("falls asleep before any guard begins a shift".to_string())
+            }
+            (Event::Asleep(_), Some((id, true))) => Err(format!("guard #{} already asleep", id)),
+            (Event::Asleep(_), Some((id, false))) => Ok((id, true)),
+            (Event::Awake(_), Some((id, true))) => Ok((id, false)),
+            (Event::Awake(_), _) => Err("wakes up without falling asleep".to_string()),
+        };
+        on_shift = Some(next.map_err(|message| {
+            // on the text of the record, after the timestamp
+            let column = line.find("] ").map_or(0, |pos| pos + 2);
+            ParseError::at(column, line.len() - column, message).on_line(idx, line)
+        })?);
+        events.push(event);
+    }
+    if on_shift.is_none() {
+        return Err(ParseError::new("no guard begins a shift"));
+    }
+    Ok(events)
+}
+
+/// Replay the shifts collecting the sleeping minutes of every guard
+pub fn get_guards_stats(events: impl IntoIterator<Item = Event>) -> HashMap<u32, Guard> {
+    #[derive(Debug)]
+    enum State {
+        Init,
+        CurrentGuard(u32),
+        Asleep(u32, u8), // guard_id, minute
+    }
+
+    events
+        .into_iter()
+        .fold(
+            (HashMap::new(), State::Init),
+            |(mut guards, state), e| match e {
+                Event::Start(id) => {
+                    guards.entry(id).or_insert(Guard::new(id));
+                    let next = match state {
+                        State::Init | State::CurrentGuard(_) => State::CurrentGuard(id),
+                        _ => panic!("Invalid state {:?} with event {:?}", state, e),
+                    };
+                    (guards, next)
+                }
+                Event::Asleep(minute) => {
+                    let next = match state {
+                        State::CurrentGuard(id) => State::Asleep(id, minute),
+                        _ => panic!("Invalid state {:?} with event {:?}", state, e),
+                    };
+                    (guards, next)
+                }
+                Event::Awake(end_minute) => {
+                    let next = match state {
+                        State::Asleep(id, begin_minute) => {
+                            let guard = guards
+                                .get_mut(&id)
+                                .unwrap_or_else(|| panic!("Cannot found Gaurd #{}", id));
+                            (begin_minute..end_minute).for_each(|minute| {
+                                guard.minutes_slept[minute as usize] += 1;
+                            });
+                            State::CurrentGuard(id)
+                        }
+                        _ => panic!("Invalid state {:?} with event {:?}", state, e),
+                    };
+                    (guards, next)
+                }
+            },
+        )
+        .0
+}
+
+/// Guard with the most minutes asleep
+pub fn find_sleepiest_guard(guards: &HashMap<u32, Guard>) -> &Guard {
+    guards
+        .values()
+        .max_by_key(|guard| guard.minutes_slept.iter().sum::<u32>())
+        .expect("Cannot find a guard")
+}
+
+/// Minute the guard is asleep the most and how many times, the earliest
+/// one on a tie: minute 0 for a guard who never sleeps
+pub fn find_favourite_minute(guard: &Guard) -> (u32, u32) {
+    let (minute, times) = guard
+        .minutes_slept
+        .iter()
+        .enumerate()
+        .rev()
+        .max_by_key(|&(_, times)| times)
+        .expect("Cannot find favourite minute");
+
+    (minute as u32, *times)
+}
+
+/// Guard ID and minute of the guard most frequently asleep on the
+/// same minute
+pub fn find_frequent_minute(guards: &HashMap<u32, Guard>) -> (u32, u32) {
+    let (id, (minute, _)) = guards
+        .values()
+        .map(|guard| (guard.id, find_favourite_minute(guard)))
+        .max_by_key(|&(_, (_, times))| times)
+        .expect("Cannot find frequent minute");
+
+    (id, minute)
+}
+
+// timestamp of a minute of the `day`-th day from 1518-01-01, leap years
+// left aside
+fn timestamp(day: usize, hour: u32, minute: u32) -> String {
+    const MONTHS: [usize; 12] = [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+    let (year, mut day) = (1518 + day / 365, day % 365);
+    let mut month = 0;
+    while day >= MONTHS[month] {
+        day -= MONTHS[month];
+        month += 1;
+    }
+    format!(
+        "[{}-{:02}-{:02} {:02}:{:02}]",
+        year,
+        month + 1,
+        day + 1,
+        hour,
+        minute
+    )
+}
+
+/// Random records of about `count` lines, shuffled like the puzzle input
+pub fn generate_records(rng: &mut Rng, count: usize) -> Vec<String> {
+    let mut ids: Vec<u32> = Vec::new();
+    while ids.len() < (count / 10).max(1) {
+        let id = rng.range(10..=3500) as u32;
+        if !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
+
+    let mut records = Vec::new();
+    let mut day = 0;
+    while records.len() < count.max(1) {
+        day += 1;
+        // the shift begins a bit before or after midnight
+        let begin = rng.range(-20..=5);
+        let start = match begin < 0 {
+            true => timestamp(day - 1, 23, (60 + begin) as u32),
+            false => timestamp(day, 0, begin as u32),
+        };
+        let event = Event::Start(*rng.pick(&ids));
+        records.push(format!("{} {}", start, event));
+
+        // distinct minutes, so that the records sort in order
+        let first = begin.max(0) + 1;
+        let mut minutes: Vec<i64> = Vec::new();
+        for _ in 0..rng.range(0..=3) * 2 {
+            let minute = rng.range(first..=59);
+            if !minutes.contains(&minute) {
+                minutes.push(minute);
+            }
+        }
+        minutes.truncate(minutes.len() / 2 * 2);
+        minutes.sort_unstable();
+        for nap in minutes.chunks(2) {
+            let (asleep, awake) = (nap[0] as u32, nap[1] as u32);
+            let asleep = format!(
+                "{} {}",
+                timestamp(day, 0, asleep),
+                Event::Asleep(asleep as u8)
+            );
+            let awake = format!("{} {}", timestamp(day, 0, awake), Event::Awake(awake as u8));
+            records.extend([asleep, awake]);
+        }
+    }
+    rng.shuffle(&mut records);
+    records
+}
+
+/// Both parts multiply a guard ID by a minute: part 1 uses the sleepiest
+/// guard, part 2 the guard most frequently asleep on the same minute
+#[derive(Default)]
+pub struct Day04;
+
+impl Solution for Day04 {
+    const DAY: u8 = 4;
+    const README: &'static str = include_str!("../README.md");
+
+    type Input = Vec<Event>;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(&self, input: &str) -> Result<Vec<Event>, ParseError> {
+        parse_events(input)
+    }
+
+    fn part1(&self, events: &Vec<Event>) -> u64 {
+        let guards = get_guards_stats(events.iter().cloned());
+        let guard = find_sleepiest_guard(&guards);
+        let (minute, _) = find_favourite_minute(guard);
+        u64::from(guard.id) * u64::from(minute)
+    }
+
+    fn part2(&self, events: &Vec<Event>) -> u64 {
+        let guards = get_guards_stats(events.iter().cloned());
+        let (guard_id, minute) = find_frequent_minute(&guards);
+        u64::from(guard_id) * u64::from(minute)
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        generate_records(rng, size)
+            .iter()
+            .map(|record| format!("{}\n", record))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        find_favourite_minute, find_frequent_minute, find_sleepiest_guard, generate_records,
+        get_guards_stats, parse_event, parse_events, timestamp, Day04, Event,
+    };
+    use common::{Rng, Solution};
+
+    #[test]
+    fn test_parse_event() {
+        [
+            (
+                "[1518-11-01 00:00] Guard #10 begins shift",
+                Event::Start(10),
+            ),
+            ("[1518-11-01 00:05] falls asleep", Event::Asleep(5)),
+            ("[1518-11-01 00:25] wakes up", Event::Awake(25)),
+        ]
+        .iter()
+        .for_each(|(line, expect)| assert_eq!(parse_event(line).unwrap(), *expect));
+    }
+
+    #[test]
+    fn test_parse_event_errors() {
+        [
+            (
+                "[1518-11-01 00:00] Guard 10 begins shift",
+                26,
+                "expected `Guard #`",
+            ),
+            (
+                "[1518-11-01 00:60] falls asleep",
+                16,
+                "expected a minute below 60",
+            ),
+            (
+                "[1518-11-01 00:05] dozes off",
+                20,
+                "expected `Guard #`, `falls asleep` or `wakes up`",
+            ),
+            ("1518-11-01 00:05 falls asleep", 1, "expected `[`"),
+        ]
+        .iter()
+        .for_each(|&(line, column, message)| {
+            let err = parse_event(line).unwrap_err().on_line(0, line);
+            assert_eq!((err.column(), err.message()), (Some(column), message));
+        });
+    }
+
+    #[test]
+    fn test_parse_events() {
+        let input = "[1518-11-01 00:25] wakes up\n\
+                     [1518-11-01 00:00] Guard #10 begins shift\n\
+                     [1518-11-01 00:05] falls asleep";
+        let expect = vec![Event::Start(10), Event::Asleep(5), Event::Awake(25)];
+
+        assert_eq!(parse_events(input).unwrap(), expect);
+
+        // errors point at the line in the records, not in the sorted events
+        let err =
+            parse_events("[1518-11-01 00:25] wakes up\n[1518-11-01 00:05] falls").unwrap_err();
+        assert_eq!(err.line(), Some(2));
+    }
+
+    #[test]
+    fn test_parse_events_shifts() {
+        let err = parse_events(
+            "[1518-11-01 00:05] falls asleep\n\
+             [1518-11-01 00:00] Guard #10 begins shift\n\
+             [1518-11-01 00:01] falls asleep",
+        )
+        .unwrap_err();
+        assert_eq!((err.line(), err.column()), (Some(1), Some(20)));
+        assert_eq!(err.message(), "guard #10 already asleep");
+
+        let err = parse_events("[1518-11-01 00:05] falls asleep\n").unwrap_err();
+        assert_eq!((err.line(), err.column()), (Some(1), Some(20)));
+        assert_eq!(
+            err.message(),
+            "falls asleep before any guard begins a shift"
+        );
+
+        let err = parse_events(
+            "[1518-11-01 00:00] Guard #10 begins shift\n\
+             [1518-11-01 00:05] wakes up",
+        )
+        .unwrap_err();
+        assert_eq!(err.message(), "wakes up without falling asleep");
+
+        let err = parse_events(
+            "[1518-11-01 00:00] Guard #10 begins shift\n\
+             [1518-11-01 00:05] falls asleep\n\
+             [1518-11-02 00:00] Guard #99 begins shift",
+        )
+        .unwrap_err();
+        assert_eq!(
+            (err.line(), err.message()),
+            (Some(3), "guard #10 still asleep at the start of the shift")
+        );
+
+        let err = parse_events("").unwrap_err();
+        assert_eq!(
+            (err.line(), err.message()),
+            (None, "no guard begins a shift")
+        );
+    }
+
+    fn check_minutes(minutes_slept: &[u32; 60], minutes: impl Iterator<Item = usize>, eq: u32) {
+        minutes.for_each(|minute| {
+            assert_eq!(minutes_slept[minute], eq);
+        });
+    }
+
+    #[test]
+    fn test_get_guards_stats_one_shift() {
+        let input = "[1518-11-01 00:00] Guard #10 begins shift\n\
+                     [1518-11-01 00:05] falls asleep\n\
+                     [1518-11-01 00:25] wakes up";
+        let guards = get_guards_stats(parse_events(input).unwrap());
+        let id = 10;
+        assert!(guards.contains_key(&id));
+
+        let guard = guards.get(&id).unwrap();
+        assert_eq!(guard.id, id);
+        check_minutes(&guard.minutes_slept, 5..25, 1);
+        check_minutes(&guard.minutes_slept, (0..5).chain(25..60), 0);
+    }
+
+    fn test_get_guards_stats_two_shifts_common(input: &str) {
+        let guards = get_guards_stats(parse_events(input).unwrap());
+        let id = 10;
+        assert!(guards.contains_key(&id));
+
+        let guard = guards.get(&id).unwrap();
+        assert_eq!(guard.id, id);
+        check_minutes(&guard.minutes_slept, (5..25).chain(40..50), 1);
+        check_minutes(&guard.minutes_slept, (0..5).chain(25..40).chain(50..60), 0);
+    }
+
+    #[test]
+    fn test_get_guards_stats_two_shifts_one_night() {
+        test_get_guards_stats_two_shifts_common(
+            "[1518-11-01 00:00] Guard #10 begins shift\n\
+             [1518-11-01 00:05] falls asleep\n\
+             [1518-11-01 00:25] wakes up\n\
+             [1518-11-01 00:40] falls asleep\n\
+             [1518-11-01 00:50] wakes up",
+        );
+    }
+
+    #[test]
+    fn test_get_guards_stats_two_shifts_two_night() {
+        test_get_guards_stats_two_shifts_common(
+            "[1518-11-01 00:00] Guard #10 begins shift\n\
+             [1518-11-01 00:05] falls asleep\n\
+             [1518-11-01 00:25] wakes up\n\
+             [1518-11-02 00:00] Guard #10 begins shift\n\
+             [1518-11-02 00:40] falls asleep\n\
+             [1518-11-02 00:50] wakes up",
+        );
+    }
+
+    #[test]
+    fn test_get_guards_stats_two_shifts_two_night_early_begin() {
+        test_get_guards_stats_two_shifts_common(
+            "[1518-11-01 00:00] Guard #10 begins shift\n\
+             [1518-11-01 00:05] falls asleep\n\
+             [1518-11-01 00:25] wakes up\n\
+             [1518-11-01 00:00] Guard #10 begins shift\n\
+             [1518-11-02 00:40] falls asleep\n\
+             [1518-11-02 00:50] wakes up",
+        );
+    }
+
+    const EXAMPLE_INPUT: &str = "[1518-11-01 00:00] Guard #10 begins shift\n\
+                                 [1518-11-01 00:05] falls asleep\n\
+                                 [1518-11-01 00:25] wakes up\n\
+                                 [1518-11-01 00:30] falls asleep\n\
+                                 [1518-11-01 00:55] wakes up\n\
+                                 [1518-11-01 23:58] Guard #99 begins shift\n\
+                                 [1518-11-02 00:40] falls asleep\n\
+                                 [1518-11-02 00:50] wakes up\n\
+                                 [1518-11-03 00:05] Guard #10 begins shift\n\
+                                 [1518-11-03 00:24] falls asleep\n\
+                                 [1518-11-03 00:29] wakes up\n\
+                                 [1518-11-04 00:02] Guard #99 begins shift\n\
+                                 [1518-11-04 00:36] falls asleep\n\
+                                 [1518-11-04 00:46] wakes up\n\
+                                 [1518-11-05 00:03] Guard #99 begins shift\n\
+                                 [1518-11-05 00:45] falls asleep\n\
+                                 [1518-11-05 00:55] wakes up";
+
+    #[test]
+    fn test_get_guards_stats_two_guards() {
+        let guards = get_guards_stats(parse_events(EXAMPLE_INPUT).unwrap());
+        let (a, b) = (10, 99);
+        assert!(guards.contains_key(&a));
+        assert!(guards.contains_key(&b));
+
+        {
+            let guard = guards.get(&a).unwrap();
+            assert_eq!(guard.id, a);
+            check_minutes(&guard.minutes_slept, (5..24).chain(25..29).chain(30..55), 1);
+            assert_eq!(guard.minutes_slept[24], 2);
+            check_minutes(&guard.minutes_slept, (0..5).chain(29..30).chain(55..60), 0);
+        }
+
+        {
+            let guard = guards.get(&b).unwrap();
+            assert_eq!(guard.id, b);
+            check_minutes(&guard.minutes_slept, (36..40).chain(50..55), 1);
+            check_minutes(&guard.minutes_slept, (40..45).chain(46..50), 2);
+            assert_eq!(guard.minutes_slept[45], 3);
+            check_minutes(&guard.minutes_slept, (0..36).chain(55..60), 0);
+        }
+    }
+
+    #[test]
+    fn test_find_sleepiest_gaurd() {
+        let guards = get_guards_stats(parse_events(EXAMPLE_INPUT).unwrap());
+        let guard = find_sleepiest_guard(&guards);
+
+        assert_eq!(guard.id, 10);
+    }
+
+    #[test]
+    fn test_find_favourite_minute() {
+        let guards = get_guards_stats(parse_events(EXAMPLE_INPUT).unwrap());
+        let guard = find_sleepiest_guard(&guards);
+
+        assert_eq!(find_favourite_minute(guard).0, 24);
+    }
+
+    #[test]
+    fn test_find_frequent_minute() {
+        let guards = get_guards_stats(parse_events(EXAMPLE_INPUT).unwrap());
+
+        assert_eq!(find_frequent_minute(&guards), (99, 45));
+    }
+
+    #[test]
+    fn test_solution() {
+        let events = Day04.parse(EXAMPLE_INPUT).unwrap();
+
+        assert_eq!(Day04.part1(&events), 240);
+        assert_eq!(Day04.part2(&events), 4455);
+
+        let events = Day04
+            .parse(
+                "[1518-11-01 00:00] Guard #4000000000 begins shift\n\
+                 [1518-11-01 00:05] falls asleep\n\
+                 [1518-11-01 00:25] wakes up",
+            )
+            .unwrap();
+        assert_eq!(Day04.part1(&events), 4_000_000_000 * 5);
+        assert_eq!(Day04.part2(&events), 4_000_000_000 * 5);
+
+        // a guard who never sleeps
+        let events = Day04
+            .parse("[1518-11-01 00:00] Guard #10 begins shift")
+            .unwrap();
+        assert_eq!(Day04.part1(&events), 0);
+        assert_eq!(Day04.part2(&events), 0);
+    }
+
+    #[test]
+    fn test_generate() {
+        assert_eq!(timestamp(0, 23, 58), "[1518-01-01 23:58]");
+        assert_eq!(timestamp(59, 0, 5), "[1518-03-01 00:05]");
+        assert_eq!(timestamp(365 + 364, 0, 0), "[1519-12-31 00:00]");
+
+        for seed in 0..10 {
+            let mut rng = Rng::new(seed);
+            let records = generate_records(&mut rng, 300);
+            assert!(records.len() >= 300);
+            let events = parse_events(&records.join("\n")).unwrap();
+            assert!(matches!(events[0], Event::Start(_)));
+            // the replay panics on records out of order
+            let guards = get_guards_stats(events);
+            assert!(guards.len() <= 30);
+
+            let events = Day04.parse(&Day04.generate(&mut rng, 1)).unwrap();
+            Day04.part1(&events);
+            Day04.part2(&events);
+        }
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let mut rng = Rng::new(0);
+        for _ in 0..1_000 {
+            let minute = rng.range(0..=59) as u8;
+            let event = match rng.below(3) {
+                0 => Event::Start(rng.next_u64() as u32 >> rng.below(32)),
+                1 => Event::Asleep(minute),
+                _ => Event::Awake(minute),
+            };
+            let day = rng.index(3_000);
+            let record = format!("{} {}", timestamp(day, 0, minute as u32), event);
+            assert_eq!(parse_event(&record), Ok(event));
+        }
+        assert_eq!(Event::Start(10).to_string(), "Guard #10 begins shift");
+    }
+}
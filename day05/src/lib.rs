@@ -0,0 +1,184 @@
+//! Day 5: Alchemical Reduction
+
+use common::{single_line, ParseError, Rng, Solution};
+
+pub mod oracle;
+
+/// Units react when they are the same type with opposite polarity
+pub fn unit_react(a: char, b: char) -> bool {
+    a != b && a.eq_ignore_ascii_case(&b)
+}
+
+/// Units left after all the reactions of the polymer
+pub fn polymer_react(cs: impl Iterator<Item = char>) -> Vec<char> {
+    cs.fold(Vec::new(), |mut us, u| {
+        match us.last() {
+            Some(top) if unit_react(*top, u) => {
+                us.pop();
+            }
+            _ => {
+                us.push(u);
+            }
+        };
+
+        us
+    })
+}
+
+/// Units left after removing all the units of a type, regardless of
+/// polarity, and reacting the polymer
+pub fn polymer_clean_react(cs: impl Iterator<Item = char>, todelete: char) -> Vec<char> {
+    polymer_react(cs.filter(|c| !c.eq_ignore_ascii_case(&todelete)))
+}
+
+/// Random polymer of `count` units, about half of them reacting away with
+/// the units nested between them like brackets
+pub fn generate_polymer(rng: &mut Rng, count: usize) -> Vec<char> {
+    let mut polymer = Vec::with_capacity(count);
+    let mut open = Vec::new();
+    while polymer.len() < count.max(1) {
+        let closing = match open.last() {
+            Some(&unit) if rng.chance(0.5) => Some(unit),
+            _ => None,
+        };
+        match closing {
+            Some(unit) => {
+                open.pop();
+                polymer.push(flip(unit));
+            }
+            None => {
+                let unit = (b'a' + rng.below(26) as u8) as char;
+                let unit = match rng.chance(0.5) {
+                    true => unit.to_ascii_uppercase(),
+                    false => unit,
+                };
+                open.push(unit);
+                polymer.push(unit);
+            }
+        }
+    }
+    polymer
+}
+
+// the unit of the same type with the opposite polarity
+fn flip(unit: char) -> char {
+    match unit.is_ascii_lowercase() {
+        true => unit.to_ascii_uppercase(),
+        false => unit.to_ascii_lowercase(),
+    }
+}
+
+/// Part 1 is the length of the reacted polymer, part 2 the shortest length
+/// after removing one unit type
+#[derive(Default)]
+pub struct Day05;
+
+impl Solution for Day05 {
+    const DAY: u8 = 5;
+    const README: &'static str = include_str!("../README.md");
+
+    type Input = Vec<char>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(&self, input: &str) -> Result<Vec<char>, ParseError> {
+        let line = single_line(input)?;
+        match line.find(|c: char| !c.is_ascii_alphabetic()) {
+            Some(pos) => Err(ParseError::at(pos, 1, "expected a unit letter").on_line(0, line)),
+            None => Ok(line.chars().collect()),
+        }
+    }
+
+    fn part1(&self, polymer: &Vec<char>) -> usize {
+        polymer_react(polymer.iter().cloned()).len()
+    }
+
+    fn part2(&self, polymer: &Vec<char>) -> usize {
+        (b'a'..=b'z')
+            .map(char::from)
+            .map(|todelete| polymer_clean_react(polymer.iter().cloned(), todelete).len())
+            .min()
+            .unwrap()
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        let mut polymer: String = generate_polymer(rng, size).into_iter().collect();
+        polymer.push('\n');
+        polymer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{generate_polymer, polymer_clean_react, polymer_react, unit_react, Day05};
+    use common::{Rng, Solution};
+
+    #[test]
+    fn test_do_react() {
+        assert!(unit_react('a', 'A'));
+        assert!(unit_react('A', 'a'));
+    }
+
+    #[test]
+    fn test_do_not_react() {
+        assert!(!unit_react('a', 'a'));
+        assert!(!unit_react('A', 'A'));
+        assert!(!unit_react('a', 'b'));
+        assert!(!unit_react('A', 'b'));
+        assert!(!unit_react('A', 'B'));
+        assert!(!unit_react('a', 'B'));
+    }
+
+    #[test]
+    fn test_polymer_react() {
+        let pr = |s: &str| polymer_react(s.chars()).into_iter().collect::<String>();
+
+        assert_eq!(pr("aA"), "");
+        assert_eq!(pr("abBA"), "");
+        assert_eq!(pr("abAB"), "abAB");
+        assert_eq!(pr("aabAAB"), "aabAAB");
+        assert_eq!(pr("dabAcCaCBAcCcaDA"), "dabCBAcaDA");
+    }
+
+    #[test]
+    fn test_polymer_clean_react() {
+        let pr = |s: &str, td: char| {
+            polymer_clean_react(s.chars(), td)
+                .into_iter()
+                .collect::<String>()
+        };
+
+        assert_eq!(pr("dbcCCBcCcDaA", 'a'), "dbCBcD");
+        assert_eq!(pr("daAcCaCAcCcaDA", 'b'), "daCAcaDA");
+        assert_eq!(pr("dabAaBAaDA", 'c'), "daDA");
+        assert_eq!(pr("abAcCaCBAcCcaA", 'd'), "abCBAc");
+    }
+
+    #[test]
+    fn test_solution() {
+        let polymer = Day05.parse("dabAcCaCBAcCcaDA\n").unwrap();
+
+        assert_eq!(Day05.part1(&polymer), 10);
+        assert_eq!(Day05.part2(&polymer), 4);
+
+        let err = Day05.parse("dabAc CaCBA\n").unwrap_err();
+        assert_eq!((err.line(), err.column()), (Some(1), Some(6)));
+        assert!(Day05.parse("").is_err());
+    }
+
+    #[test]
+    fn test_generate() {
+        for seed in 0..10 {
+            let mut rng = Rng::new(seed);
+            let polymer = generate_polymer(&mut rng, 1000);
+            assert_eq!(polymer.len(), 1000);
+            // the pairs that react are gone, the units nested deeper first
+            let left = polymer_react(polymer.iter().cloned());
+            assert!(left.len() < 1000);
+            assert!(left.windows(2).all(|pair| !unit_react(pair[0], pair[1])));
+
+            let polymer = Day05.parse(&Day05.generate(&mut rng, 0)).unwrap();
+            assert_eq!(Day05.part1(&polymer), 1);
+        }
+    }
+}
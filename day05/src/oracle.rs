@@ -0,0 +1,84 @@
+//! Naive reactions of the polymers, the oracle of the differential tests
+//! of the stack reduction of [`polymer_react`](crate::polymer_react)
+
+/// Units left after all the reactions, the polymer being scanned again
+/// from the start after every reaction
+pub fn polymer_react(polymer: &[char]) -> Vec<char> {
+    let react = |a: char, b: char| a != b && a.eq_ignore_ascii_case(&b);
+    let mut units = polymer.to_vec();
+    while let Some(i) = (1..units.len()).find(|&i| react(units[i - 1], units[i])) {
+        units.drain(i - 1..=i);
+    }
+    units
+}
+
+/// Shortest length of the reacted polymer once all the units of a type
+/// are removed
+pub fn shortest_clean_react(polymer: &[char]) -> usize {
+    (b'a'..=b'z')
+        .map(|unit| {
+            let cleaned: Vec<char> = polymer
+                .iter()
+                .filter(|c| c.to_ascii_lowercase() != unit as char)
+                .cloned()
+                .collect();
+            polymer_react(&cleaned).len()
+        })
+        .min()
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{polymer_react, shortest_clean_react};
+    use crate::{generate_polymer, Day05};
+    use common::{differential, shrink_vec, Rng, Solution};
+
+    fn shrink(polymer: &str) -> Vec<String> {
+        let units: Vec<char> = polymer.chars().collect();
+        shrink_vec(&units)
+            .into_iter()
+            .map(|units| units.into_iter().collect())
+            .collect()
+    }
+
+    // generated polymers, or random ones of a few unit types reacting
+    // in more ways
+    fn generate(rng: &mut Rng) -> String {
+        let count = rng.range(1..=60) as usize;
+        match rng.chance(0.5) {
+            true => generate_polymer(rng, count).into_iter().collect(),
+            false => (0..count)
+                .map(|_| *rng.pick(&['a', 'A', 'b', 'B']))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_polymer_react() {
+        assert_eq!(polymer_react(&['a', 'b', 'B', 'A', 'c']), vec!['c']);
+
+        let react = |polymer: &String| -> String {
+            crate::polymer_react(polymer.chars()).into_iter().collect()
+        };
+        let oracle = |polymer: &String| -> String {
+            let units: Vec<char> = polymer.chars().collect();
+            polymer_react(&units).into_iter().collect()
+        };
+        if let Err(mismatch) = differential(500, generate, |p: &String| shrink(p), oracle, react) {
+            panic!("{}", mismatch);
+        }
+    }
+
+    #[test]
+    fn test_shortest_clean_react() {
+        let part2 = |polymer: &String| Day05.part2(&polymer.chars().collect());
+        let oracle = |polymer: &String| {
+            let units: Vec<char> = polymer.chars().collect();
+            shortest_clean_react(&units)
+        };
+        if let Err(mismatch) = differential(200, generate, |p: &String| shrink(p), oracle, part2) {
+            panic!("{}", mismatch);
+        }
+    }
+}
@@ -0,0 +1,450 @@
+//! Day 6: Chronal Coordinates
+
+use common::{
+    flag_value, parse_lines, BoundingBox, Flag, Grid, Image, ParseError, Point, Rgb, Rng, Scanner,
+    Solution,
+};
+use std::convert::TryFrom;
+use std::fmt::{self, Display};
+
+/// A point printed as a line of the puzzle, `1, 6`, as the display of
+/// [`Point`] is the one of the answers
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Coordinate(pub Point);
+
+impl Display for Coordinate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}, {}", self.0.x, self.0.y)
+    }
+}
+
+/// Parse a coordinate like `1, 6`
+pub fn parse_coord(line: &str) -> Result<Point, ParseError> {
+    let mut s = Scanner::new(line);
+    s.skip_whitespace();
+    let x = s.number()?;
+    s.literal(",")?;
+    s.skip_whitespace();
+    let y = s.number()?;
+    s.skip_whitespace();
+    s.end()?;
+    Ok(Point::new(x, y))
+}
+
+/// Parse one coordinate per line
+pub fn parse_coords(input: &str) -> Result<Vec<Point>, ParseError> {
+    parse_lines(input, parse_coord)
+}
+
+/// Number of coordinates closer to the source with the given index than
+/// to any other source, zero if the area reaches the border and so it is
+/// infinite
+pub fn get_source_area(
+    sources: &[Point],
+    source_idx: usize,
+    get_distance: impl Fn(Point, Point) -> u32,
+    is_border: impl Fn(Point) -> bool,
+    coords: impl Iterator<Item = Point>,
+) -> u32 {
+    let source = *sources.get(source_idx).expect("Cannot find source");
+    let mut area = 0;
+    for coord in coords {
+        let source_dist = get_distance(source, coord);
+        // if all other sources are further
+        if sources
+            .iter()
+            .all(|&s| s == source || get_distance(s, coord) > source_dist)
+        {
+            if is_border(coord) {
+                return 0;
+            } else {
+                area += 1;
+            }
+        }
+    }
+    area
+}
+
+/// Size of the largest finite area around a source
+pub fn get_max_area(
+    sources: &[Point],
+    get_distance: impl Fn(Point, Point) -> u32,
+    is_coord_on_border: impl Fn(&BoundingBox, Point) -> bool,
+) -> u32 {
+    let bbox = BoundingBox::from_points(sources.iter().copied()).expect("Cannot find sources");
+    let is_border = |c: Point| is_coord_on_border(&bbox, c);
+
+    (0..sources.len())
+        .map(|idx| get_source_area(sources, idx, &get_distance, is_border, bbox.points()))
+        .max()
+        .expect("Cannot find max area")
+}
+
+/// Total distance of a coordinate from all the sources
+pub fn sum_distances_from_sources(
+    coord: Point,
+    sources: &[Point],
+    get_distance: impl Fn(Point, Point) -> u32,
+) -> u32 {
+    sources.iter().map(|&c| get_distance(coord, c)).sum()
+}
+
+/// Size of the region of coordinates whose total distance from all the
+/// sources is less than the threshold
+///
+/// The region reaches past the sources: a coordinate `d` away from their
+/// bounding box is at least `d` away from every source, so only the box
+/// grown by `threshold / sources.len()` on every side is scanned.
+pub fn get_area_within_threshold(
+    sources: &[Point],
+    get_distance: impl Fn(Point, Point) -> u32,
+    threshold: u32,
+) -> u32 {
+    let bbox = match BoundingBox::from_points(sources.iter().copied()) {
+        Some(bbox) => bbox,
+        None => return 0,
+    };
+    let margin = threshold as usize / sources.len();
+    bbox.grow(i32::try_from(margin).unwrap_or(i32::MAX))
+        .points()
+        .map(|c| sum_distances_from_sources(c, sources, &get_distance))
+        .filter(|&dist| dist < threshold)
+        .map(|_| 1)
+        .sum()
+}
+
+/// Draw the areas around the sources in the colour of the closest source,
+/// grey where two sources are as close, lighter inside the region of the
+/// coordinates whose total distance is less than the threshold; the
+/// sources are black. None when there are no sources
+pub fn render_areas(sources: &[Point], threshold: u32) -> Option<Image> {
+    let bbox = BoundingBox::from_points(sources.iter().copied())?.grow(1);
+    let areas = Grid::from_fn(bbox, |coord| {
+        let mut dists = sources.iter().map(|&s| coord.manhattan(s)).enumerate();
+        let (mut closest, mut min_dist) = dists.next().unwrap();
+        let mut tie = false;
+        for (idx, dist) in dists {
+            if dist < min_dist {
+                closest = idx;
+                min_dist = dist;
+                tie = false;
+            } else if dist == min_dist {
+                tie = true;
+            }
+        }
+        let total = sum_distances_from_sources(coord, sources, Point::manhattan);
+        (closest, min_dist, tie, total < threshold)
+    });
+
+    let image = Image::from_grid(&areas, |_, &(closest, dist, tie, in_region)| {
+        let colour = match (dist, tie) {
+            (0, _) => return Rgb::BLACK,
+            (_, true) => Rgb(110, 110, 110),
+            (_, false) => Rgb::palette(closest),
+        };
+        if in_region {
+            colour.mix(Rgb::WHITE, 0.5)
+        } else {
+            colour
+        }
+    });
+    Some(image.scale(2))
+}
+
+/// Random distinct coordinates in the square of the puzzle inputs
+pub fn generate_coords(rng: &mut Rng, count: usize) -> Vec<Point> {
+    let mut coords = Vec::new();
+    // there are far fewer coordinates than room in the square
+    while coords.len() < count.clamp(1, 10_000) {
+        let p = Point::new(rng.range(40..=360) as i32, rng.range(40..=360) as i32);
+        if !coords.contains(&p) {
+            coords.push(p);
+        }
+    }
+    coords
+}
+
+/// Part 1 is the largest finite area, part 2 the region with a total
+/// distance below the threshold
+pub struct Day06 {
+    /// Total distance from the coordinates reached by the region of part 2
+    pub threshold: u32,
+}
+
+impl Default for Day06 {
+    fn default() -> Day06 {
+        Day06 { threshold: 10000 }
+    }
+}
+
+impl Solution for Day06 {
+    const DAY: u8 = 6;
+    const README: &'static str = include_str!("../README.md");
+
+    type Input = Vec<Point>;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    const FLAGS: &'static [Flag] = &[Flag {
+        name: "threshold",
+        value: "N",
+        help: "total distance reached by the region of part 2 (10000)",
+    }];
+
+    fn set_flag(&mut self, flag: &str, value: &str) -> Result<(), String> {
+        match flag {
+            "threshold" => self.threshold = flag_value(flag, value)?,
+            _ => return Err(format!("Unknown flag --{}", flag)),
+        }
+        Ok(())
+    }
+
+    fn parse(&self, input: &str) -> Result<Vec<Point>, ParseError> {
+        parse_coords(input)
+    }
+
+    fn part1(&self, sources: &Vec<Point>) -> u32 {
+        get_max_area(sources, Point::manhattan, BoundingBox::is_on_border)
+    }
+
+    fn part2(&self, sources: &Vec<Point>) -> u32 {
+        get_area_within_threshold(sources, Point::manhattan, self.threshold)
+    }
+
+    fn render(&self, sources: &Vec<Point>) -> Option<Image> {
+        render_areas(sources, self.threshold)
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        generate_coords(rng, size)
+            .iter()
+            .map(|&p| format!("{}\n", Coordinate(p)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        generate_coords, get_area_within_threshold, get_max_area, get_source_area, parse_coord,
+        parse_coords, render_areas, sum_distances_from_sources, Coordinate, Day06,
+    };
+    use common::{set_flags, BoundingBox, Point, Rgb, Rng, Solution};
+
+    fn points(coords: &[(i32, i32)]) -> Vec<Point> {
+        coords.iter().map(|&c| Point::from(c)).collect()
+    }
+
+    #[test]
+    fn test_parse_coord() {
+        let tests = [
+            ("1, 1", (1, 1)),
+            ("11, 1", (11, 1)),
+            ("1, 11", (1, 11)),
+            ("11, 11", (11, 11)),
+            ("111, 1", (111, 1)),
+            ("1, 111", (1, 111)),
+            ("111, 11", (111, 11)),
+            ("11, 111", (11, 111)),
+            ("111, 111", (111, 111)),
+            ("-3, 4", (-3, 4)),
+        ];
+        for (line, coord) in &tests {
+            assert_eq!(parse_coord(line), Ok(Point::from(*coord)));
+        }
+    }
+
+    #[test]
+    fn test_parse_coords() {
+        assert_eq!(
+            points(&[(1, 1), (11, 1), (1, 11), (111, 11), (11, 111), (111, 111)]),
+            parse_coords(
+                "1, 1\n\
+                 11, 1\n\
+                 1, 11\n\
+                 111, 11\n\
+                 11, 111\n\
+                 111, 111\n"
+            )
+            .unwrap()
+        );
+
+        let err = parse_coords("1, 1\n11 1\n").unwrap_err();
+        assert_eq!((err.line(), err.column()), (Some(2), Some(3)));
+        assert_eq!(err.message(), "expected `,`");
+    }
+
+    /*
+     ***
+     *A*
+     ***
+     */
+    #[test]
+    fn test_get_source_area_one_infinity() {
+        let bbox = BoundingBox::new(Point::new(0, 0), Point::new(2, 2));
+        let sources = points(&[(1, 1)]);
+
+        let is_border = |c: Point| bbox.is_on_border(c);
+        let area = get_source_area(&sources, 0, Point::manhattan, is_border, bbox.points());
+
+        assert_eq!(area, 0);
+    }
+
+    /*
+    *A*
+    BCD
+    *E*
+    */
+    #[test]
+    fn test_get_source_area_one_finite() {
+        let bbox = BoundingBox::new(Point::new(0, 0), Point::new(2, 2));
+        let sources = points(&[(0, 1), (1, 0), (1, 1), (1, 2), (2, 1)]);
+
+        let is_border = |c: Point| bbox.is_on_border(c);
+        let get_area =
+            |idx: usize| get_source_area(&sources, idx, Point::manhattan, is_border, bbox.points());
+        (0..sources.len()).filter(|idx| *idx != 2).for_each(|idx| {
+            assert_eq!(get_area(idx), 0);
+        });
+        assert_eq!(get_area(2), 1);
+    }
+
+    /*
+    ..........
+    .A........
+    ..........
+    ........C.
+    ...D......
+    .....E....
+    .B........
+    ..........
+    ..........
+    ........F.
+    */
+    #[test]
+    fn test_get_source_area_example() {
+        let sources = points(&[(1, 1), (1, 6), (8, 3), (3, 4), (5, 5), (8, 9)]);
+        let bbox = BoundingBox::from_points(sources.iter().copied()).unwrap();
+
+        let is_border = |c: Point| bbox.is_on_border(c);
+        let get_area =
+            |idx: usize| get_source_area(&sources, idx, Point::manhattan, is_border, bbox.points());
+        (0..3).chain(5..=5).filter(|idx| *idx != 2).for_each(|idx| {
+            assert_eq!(get_area(idx), 0);
+        });
+
+        assert_eq!(get_area(3), 9);
+        assert_eq!(get_area(4), 17);
+    }
+
+    #[test]
+    fn test_get_max_area_example() {
+        let sources = points(&[(1, 1), (1, 6), (8, 3), (3, 4), (5, 5), (8, 9)]);
+        let max_area =
+            |sources: &[Point]| get_max_area(sources, Point::manhattan, BoundingBox::is_on_border);
+        assert_eq!(max_area(&sources), 17);
+
+        // the same areas moved to negative coordinates
+        let moved: Vec<_> = sources.iter().map(|&p| p - Point::new(20, 30)).collect();
+        assert_eq!(max_area(&moved), 17);
+    }
+
+    #[test]
+    fn test_sum_distances_from_sources_example() {
+        let sources = points(&[(1, 1), (1, 6), (8, 3), (3, 4), (5, 5), (8, 9)]);
+        assert_eq!(
+            sum_distances_from_sources(Point::new(4, 3), &sources, Point::manhattan),
+            30
+        );
+    }
+
+    #[test]
+    fn test_get_area_within_threshold_example() {
+        let sources = points(&[(1, 1), (1, 6), (8, 3), (3, 4), (5, 5), (8, 9)]);
+        assert_eq!(
+            get_area_within_threshold(&sources, Point::manhattan, 32),
+            16
+        );
+        assert_eq!(get_area_within_threshold(&[], Point::manhattan, 32), 0);
+    }
+
+    #[test]
+    fn test_get_area_within_threshold_past_sources() {
+        // the diamond of the coordinates less than 10 away
+        let area = get_area_within_threshold(&points(&[(0, 0)]), Point::manhattan, 10);
+        assert_eq!(area, 181);
+
+        // counted over a box far larger than the region
+        let sources = points(&[(1, 1), (6, 6)]);
+        let expected = BoundingBox::new(Point::new(-100, -100), Point::new(100, 100))
+            .points()
+            .filter(|&c| sum_distances_from_sources(c, &sources, Point::manhattan) < 100)
+            .count();
+        assert_eq!(
+            get_area_within_threshold(&sources, Point::manhattan, 100) as usize,
+            expected
+        );
+        assert!(expected > 36);
+    }
+
+    #[test]
+    fn test_solution() {
+        let mut day = Day06::default();
+        let sources = day.parse("1, 1\n1, 6\n8, 3\n3, 4\n5, 5\n8, 9").unwrap();
+
+        assert_eq!(day.part1(&sources), 17);
+
+        set_flags(&mut day, &[("threshold".to_string(), "32".to_string())]).unwrap();
+        assert_eq!(day.part2(&sources), 16);
+        assert_eq!(
+            day.set_flag("limit", "5"),
+            Err("Unknown flag --limit".to_string())
+        );
+        assert_eq!(day.threshold, 32);
+    }
+
+    #[test]
+    fn test_render_areas() {
+        let sources = points(&[(1, 1), (1, 6), (8, 3), (3, 4), (5, 5), (8, 9)]);
+        let image = render_areas(&sources, 32).unwrap();
+
+        // the bounding box from 1,1 to 8,9 with a border of one coordinate
+        // and two pixels per coordinate
+        assert_eq!((image.width(), image.height()), (20, 22));
+        assert_eq!(image.get(2, 2), Some(Rgb::BLACK));
+        assert_eq!(image.get(0, 0), Some(Rgb::palette(0)));
+        // 5,0 is as far from A and from E, 4,3 is in the region
+        assert_eq!(image.get(10, 0), Some(Rgb(110, 110, 110)));
+        assert_eq!(image.get(8, 6), Some(Rgb::palette(3).mix(Rgb::WHITE, 0.5)));
+        assert_eq!(render_areas(&[], 32), None);
+    }
+
+    #[test]
+    fn test_generate() {
+        for seed in 0..5 {
+            let mut rng = Rng::new(seed);
+            let coords = generate_coords(&mut rng, 50);
+            assert_eq!(coords.len(), 50);
+            assert!(coords.iter().all(|p| (40..=360).contains(&p.x)));
+
+            // a lower threshold for fewer sources, the region growing with
+            // the threshold over the number of sources
+            let day = Day06 { threshold: 3_000 };
+            let coords = day.parse(&day.generate(&mut rng, 10)).unwrap();
+            assert_eq!(coords.len(), 10);
+            day.part1(&coords);
+            assert!(day.part2(&coords) > 0);
+        }
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let mut rng = Rng::new(0);
+        for _ in 0..1_000 {
+            let mut number = || rng.next_u64() as i32 >> rng.below(32);
+            let p = Point::new(number(), number());
+            assert_eq!(parse_coord(&Coordinate(p).to_string()), Ok(p));
+        }
+        assert_eq!(Coordinate(Point::new(1, -6)).to_string(), "1, -6");
+    }
+}
@@ -0,0 +1,494 @@
+//! Day 7: The Sum of Its Parts
+
+use common::{flag_value, parse_lines, Flag, ParseError, Rng, Scanner, Solution};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Display};
+
+/// The first step must be finished before the second can begin
+pub type Edge = (u8, u8);
+/// Every step with the steps that depend on it
+pub type Graph = HashMap<u8, Vec<u8>>;
+
+/// An edge printed as the instruction of the puzzle,
+/// `Step C must be finished before step A can begin.`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Instruction(pub Edge);
+
+impl Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (from, to) = self.0;
+        write!(
+            f,
+            "Step {} must be finished before step {} can begin.",
+            from as char, to as char
+        )
+    }
+}
+
+/// Build the graph of the steps, steps without dependents included
+pub fn from_edges(edges: impl IntoIterator<Item = Edge>) -> Graph {
+    edges
+        .into_iter()
+        .fold(Graph::new(), |mut graph, (from, to)| {
+            graph.entry(to).or_default();
+            graph.entry(from).or_default().push(to);
+            graph
+        })
+}
+
+/// Parse an instruction like
+/// `Step C must be finished before step A can begin.`
+pub fn parse_edge(line: &str) -> Result<Edge, ParseError> {
+    let step = |s: &mut Scanner| s.char_if(|c| c.is_ascii_uppercase(), "an uppercase step");
+
+    let mut s = Scanner::new(line);
+    s.literal("Step ")?;
+    let from = step(&mut s)?;
+    s.literal(" must be finished before step ")?;
+    let to = step(&mut s)?;
+    s.literal(" can begin.")?;
+    s.end()?;
+    Ok((from as u8, to as u8))
+}
+
+/// Parse one instruction per line
+pub fn parse_edges(input: &str) -> Result<Vec<Edge>, ParseError> {
+    parse_lines(input, parse_edge)
+}
+
+/// Parse the instructions into the graph of the steps, pointing at an
+/// instruction of a cycle of steps depending on each other
+pub fn parse_graph(input: &str) -> Result<Graph, ParseError> {
+    let edges = parse_edges(input)?;
+    let graph = from_edges(edges.iter().cloned());
+    let cycle = match find_cycle(&graph) {
+        Some(cycle) => cycle,
+        None => return Ok(graph),
+    };
+
+    let steps: Vec<String> = cycle
+        .iter()
+        .map(|&(from, _)| from as char)
+        .chain(cycle.first().map(|&(from, _)| from as char))
+        .map(String::from)
+        .collect();
+    let (idx, line) = input
+        .lines()
+        .enumerate()
+        .zip(&edges)
+        .find(|(_, edge)| cycle.contains(edge))
+        .map(|(line, _)| line)
+        .expect("An instruction of the cycle");
+    let message = format!("cycle of steps {}", steps.join(" -> "));
+    Err(ParseError::at(0, line.len(), message).on_line(idx, line))
+}
+
+/// Edges of a cycle of steps depending on each other in the order of the
+/// cycle, none when the steps can all be ordered
+pub fn find_cycle(graph: &Graph) -> Option<Vec<Edge>> {
+    let ordered: HashSet<u8> = topological_order(graph).into_iter().collect();
+    // every step left has a dependency among the steps left, going back
+    // through them ends up in a cycle
+    let mut dependencies: HashMap<u8, u8> = HashMap::new();
+    let mut left: Vec<u8> = graph
+        .keys()
+        .cloned()
+        .filter(|step| !ordered.contains(step))
+        .collect();
+    left.sort_unstable();
+    for &from in &left {
+        for &to in &graph[&from] {
+            dependencies.entry(to).or_insert(from);
+        }
+    }
+
+    let mut path = vec![*left.first()?];
+    loop {
+        let step = dependencies[path.last().unwrap()];
+        if let Some(start) = path.iter().position(|&s| s == step) {
+            // the path goes against the edges
+            let mut steps = path.split_off(start);
+            steps[1..].reverse();
+            let next = steps[1..].iter().chain(&steps[..1]);
+            return Some(steps.iter().cloned().zip(next.cloned()).collect());
+        }
+        path.push(step);
+    }
+}
+
+fn get_in_edges_count(graph: &Graph) -> HashMap<&u8, u32> {
+    graph
+        .iter()
+        .fold(HashMap::new(), |mut in_edges, (k, edges)| {
+            in_edges.entry(k).or_default();
+            edges.iter().fold(in_edges, |mut in_edges, edge| {
+                *in_edges.entry(edge).or_default() += 1;
+                in_edges
+            })
+        })
+}
+
+fn init_queue<'a>(in_edges: &HashMap<&'a u8, u32>) -> BinaryHeap<Reverse<&'a u8>> {
+    in_edges
+        .iter()
+        .filter(|(_, &v)| v == 0)
+        .map(|(k, _)| Reverse(*k))
+        .collect()
+}
+
+/// Order of the steps, when more steps are available the first in
+/// alphabetical order goes first
+pub fn topological_order(graph: &Graph) -> Vec<u8> {
+    let mut in_edges = get_in_edges_count(graph);
+
+    let mut queue = init_queue(&in_edges);
+
+    let mut order: Vec<u8> = Vec::with_capacity(graph.len());
+
+    while !queue.is_empty() {
+        let Reverse(k) = queue.pop().unwrap();
+        order.push(*k);
+
+        graph.get(k).unwrap().iter().for_each(|edge| {
+            in_edges.entry(edge).and_modify(|c| {
+                *c -= 1;
+                if *c == 0 {
+                    queue.push(Reverse(edge));
+                }
+            });
+        });
+    }
+
+    order
+}
+
+/// Complete the steps with `worker_n` workers, returning every step with
+/// the time it is completed, in order of completion
+pub fn schedule_tasks(
+    graph: Graph,
+    get_time: impl Fn(u8) -> u32,
+    worker_n: usize,
+) -> Vec<(u8, u32)> {
+    let mut in_edges = get_in_edges_count(&graph);
+
+    let mut queue = init_queue(&in_edges);
+
+    let mut order: Vec<(u8, u32)> = Vec::with_capacity(graph.len());
+
+    let mut workers: Vec<(u32, u8)> = Vec::with_capacity(worker_n);
+
+    let mut time: u32 = 0;
+    while !queue.is_empty() || !workers.is_empty() {
+        // fill workers
+        while workers.len() < worker_n && !queue.is_empty() {
+            let Reverse(k) = queue.pop().expect("Queue is empty");
+            workers.push((time + get_time(*k), *k));
+        }
+
+        // do one step
+        let (idx, (task_time, task)) = workers
+            .iter()
+            .cloned()
+            .enumerate()
+            .min_by_key(|(_, data)| *data)
+            .expect("No elements in workers");
+        workers.remove(idx);
+
+        time = task_time;
+        order.push((task, task_time));
+
+        graph.get(&task).unwrap().iter().for_each(|edge| {
+            in_edges.entry(edge).and_modify(|c| {
+                *c -= 1;
+                if *c == 0 {
+                    queue.push(Reverse(edge));
+                }
+            });
+        });
+    }
+
+    order
+}
+
+/// Random instructions of about `count` lines between up to 26 steps,
+/// every step but the first depending on another one
+pub fn generate_edges(rng: &mut Rng, count: usize) -> Vec<Edge> {
+    let n = count.clamp(2, 26);
+    let mut steps: Vec<u8> = (b'A'..b'A' + 26).collect();
+    rng.shuffle(&mut steps);
+    steps.truncate(n);
+
+    // the steps depend on steps before them in the shuffled order, so that
+    // there is no cycle
+    let extra = count.saturating_sub(n - 1) as f64 / ((n - 1) * (n - 2) / 2).max(1) as f64;
+    let mut edges = Vec::new();
+    for to in 1..n {
+        let first = rng.index(to);
+        for from in 0..to {
+            if from == first || rng.chance(extra) {
+                edges.push((steps[from], steps[to]));
+            }
+        }
+    }
+    rng.shuffle(&mut edges);
+    edges
+}
+
+/// Part 1 is the order of the steps, part 2 the time needed by the workers
+/// when a step takes the base time plus its position in the alphabet
+pub struct Day07 {
+    pub workers: usize,
+    /// Seconds taken by a step besides its position in the alphabet
+    pub base_time: u32,
+}
+
+impl Default for Day07 {
+    fn default() -> Day07 {
+        Day07 {
+            workers: 5,
+            base_time: 60,
+        }
+    }
+}
+
+impl Solution for Day07 {
+    const DAY: u8 = 7;
+    const README: &'static str = include_str!("../README.md");
+
+    type Input = Graph;
+    type Part1 = String;
+    type Part2 = u32;
+
+    const FLAGS: &'static [Flag] = &[
+        Flag {
+            name: "workers",
+            value: "N",
+            help: "workers completing the steps in part 2 (5)",
+        },
+        Flag {
+            name: "base-time",
+            value: "SECONDS",
+            help: "time of a step besides its position in the alphabet (60)",
+        },
+    ];
+
+    fn set_flag(&mut self, flag: &str, value: &str) -> Result<(), String> {
+        match flag {
+            "workers" => match flag_value(flag, value)? {
+                0 => return Err("There must be at least one worker".to_string()),
+                workers => self.workers = workers,
+            },
+            "base-time" => self.base_time = flag_value(flag, value)?,
+            _ => return Err(format!("Unknown flag --{}", flag)),
+        }
+        Ok(())
+    }
+
+    fn parse(&self, input: &str) -> Result<Graph, ParseError> {
+        parse_graph(input)
+    }
+
+    fn part1(&self, graph: &Graph) -> String {
+        String::from_utf8_lossy(&topological_order(graph)).to_string()
+    }
+
+    fn part2(&self, graph: &Graph) -> u32 {
+        let get_time = |t| self.base_time + (t - b'A') as u32 + 1;
+        // no time at all without steps
+        schedule_tasks(graph.clone(), get_time, self.workers)
+            .last()
+            .map_or(0, |&(_, time)| time)
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        generate_edges(rng, size)
+            .iter()
+            .map(|&edge| format!("{}\n", Instruction(edge)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        find_cycle, from_edges, generate_edges, parse_edge, parse_edges, parse_graph,
+        schedule_tasks, topological_order, Day07, Instruction,
+    };
+    use common::{set_flags, Rng, Solution};
+
+    #[test]
+    fn test_parse_edge() {
+        let input = "Step C must be finished before step A can begin.";
+        assert_eq!(parse_edge(input), Ok((b'C', b'A')));
+
+        let input = "Step C must be finished before step a can begin.";
+        let err = parse_edge(input).unwrap_err().on_line(0, input);
+        assert_eq!(
+            (err.column(), err.message()),
+            (Some(37), "expected an uppercase step")
+        );
+    }
+
+    #[test]
+    fn test_parse_edges() {
+        let input = "Step C must be finished before step A can begin.\n\
+                     Step C must be finished before step F can begin.\n\
+                     Step A must be finished before step B can begin.";
+        let edges = vec![(b'C', b'A'), (b'C', b'F'), (b'A', b'B')];
+        assert_eq!(parse_edges(input).unwrap(), edges);
+    }
+
+    #[test]
+    fn test_parse_graph_cycle() {
+        let input = "Step C must be finished before step A can begin.\n\
+                     Step A must be finished before step B can begin.\n\
+                     Step B must be finished before step D can begin.\n\
+                     Step D must be finished before step A can begin.";
+        let graph = from_edges(parse_edges(input).unwrap());
+        assert_eq!(
+            find_cycle(&graph),
+            Some(vec![(b'A', b'B'), (b'B', b'D'), (b'D', b'A')])
+        );
+        let err = parse_graph(input).unwrap_err();
+        assert_eq!((err.line(), err.column()), (Some(2), Some(1)));
+        assert_eq!(err.message(), "cycle of steps A -> B -> D -> A");
+
+        let err = parse_graph("Step E must be finished before step E can begin.").unwrap_err();
+        assert_eq!(err.message(), "cycle of steps E -> E");
+
+        let graph = from_edges(parse_edges(&input[..input.rfind('\n').unwrap()]).unwrap());
+        assert_eq!(find_cycle(&graph), None);
+    }
+
+    #[test]
+    fn test_from_edges() {
+        let edges = vec![(b'C', b'A'), (b'C', b'F'), (b'A', b'B')];
+        let graph = from_edges(edges);
+        assert!(graph.contains_key(&b'C'));
+        assert_eq!(*graph.get(&b'C').unwrap(), vec![b'A', b'F']);
+        assert!(graph.contains_key(&b'A'));
+        assert_eq!(*graph.get(&b'A').unwrap(), vec![b'B']);
+        assert!(graph.contains_key(&b'F'));
+        assert_eq!(*graph.get(&b'F').unwrap(), vec![]);
+        assert!(graph.contains_key(&b'B'));
+        assert_eq!(*graph.get(&b'B').unwrap(), vec![]);
+    }
+
+    #[test]
+    fn test_topological_order() {
+        let edges = vec![(b'C', b'A'), (b'C', b'F'), (b'A', b'B')];
+        let graph = from_edges(edges);
+        assert_eq!(topological_order(&graph), vec![b'C', b'A', b'B', b'F']);
+    }
+
+    #[test]
+    fn test_topological_order_example() {
+        let input = "Step C must be finished before step A can begin.\n\
+                     Step C must be finished before step F can begin.\n\
+                     Step A must be finished before step B can begin.\n\
+                     Step A must be finished before step D can begin.\n\
+                     Step B must be finished before step E can begin.\n\
+                     Step D must be finished before step E can begin.\n\
+                     Step F must be finished before step E can begin.";
+        assert_eq!(
+            topological_order(&from_edges(parse_edges(input).unwrap())),
+            vec![b'C', b'A', b'B', b'D', b'F', b'E']
+        );
+    }
+
+    #[test]
+    fn test_schedule_tasks() {
+        let input = "Step C must be finished before step A can begin.\n\
+                     Step C must be finished before step F can begin.\n\
+                     Step A must be finished before step B can begin.\n\
+                     Step A must be finished before step D can begin.\n\
+                     Step B must be finished before step E can begin.\n\
+                     Step D must be finished before step E can begin.\n\
+                     Step F must be finished before step E can begin.";
+        assert_eq!(
+            schedule_tasks(
+                from_edges(parse_edges(input).unwrap()),
+                |t| (t - b'A') as u32 + 1,
+                2
+            ),
+            vec![
+                (b'C', 3),
+                (b'A', 4),
+                (b'B', 6),
+                (b'F', 9),
+                (b'D', 10),
+                (b'E', 15)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_solution() {
+        let input = "Step C must be finished before step A can begin.\n\
+                     Step C must be finished before step F can begin.\n\
+                     Step A must be finished before step B can begin.\n\
+                     Step A must be finished before step D can begin.\n\
+                     Step B must be finished before step E can begin.\n\
+                     Step D must be finished before step E can begin.\n\
+                     Step F must be finished before step E can begin.";
+        let mut day = Day07::default();
+        let graph = day.parse(input).unwrap();
+
+        assert_eq!(day.part1(&graph), "CABDFE".to_string());
+
+        let flags = [("workers", "2"), ("base-time", "0")];
+        let flags: Vec<_> = flags
+            .iter()
+            .map(|&(flag, value)| (flag.to_string(), value.to_string()))
+            .collect();
+        set_flags(&mut day, &flags).unwrap();
+        assert_eq!(day.part2(&graph), 15);
+
+        let flags = [("workers".to_string(), "0".to_string())];
+        assert!(set_flags(&mut day, &flags).is_err());
+        assert_eq!(
+            day.set_flag("base", "5"),
+            Err("Unknown flag --base".to_string())
+        );
+        assert_eq!(day.base_time, 0);
+
+        let graph = day.parse("").unwrap();
+        assert_eq!((day.part1(&graph), day.part2(&graph)), (String::new(), 0));
+    }
+
+    #[test]
+    fn test_generate() {
+        for seed in 0..10 {
+            let mut rng = Rng::new(seed);
+            let edges = generate_edges(&mut rng, 100);
+            assert!(edges.len() > 50 && edges.len() < 150);
+            let order = topological_order(&from_edges(edges.iter().cloned()));
+            assert_eq!(order.len(), 26);
+            let position = |step| order.iter().position(|&s| s == step);
+            assert!(edges
+                .iter()
+                .all(|&(from, to)| position(from) < position(to)));
+
+            let day = Day07::default();
+            let graph = day.parse(&day.generate(&mut rng, 1)).unwrap();
+            assert_eq!(day.part1(&graph).len(), 2);
+            assert!(day.part2(&graph) > 120);
+        }
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let mut rng = Rng::new(0);
+        for _ in 0..1_000 {
+            let mut step = || b'A' + rng.below(26) as u8;
+            let edge = (step(), step());
+            assert_eq!(parse_edge(&Instruction(edge).to_string()), Ok(edge));
+        }
+        assert_eq!(
+            Instruction((b'C', b'A')).to_string(),
+            "Step C must be finished before step A can begin."
+        );
+    }
+}
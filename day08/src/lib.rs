@@ -0,0 +1,350 @@
+//! Day 8: Memory Maneuver
+
+use common::{ParseError, Rng, Solution};
+use std::fmt::{self, Display};
+
+/// The numbers of a license file printed like the puzzle input, on one
+/// line separated by spaces
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LicenseFile<'a>(pub &'a [u32]);
+
+impl Display for LicenseFile<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (n, number) in self.0.iter().enumerate() {
+            if n > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", number)?;
+        }
+        Ok(())
+    }
+}
+
+// spans of the whitespace separated words of the input, as line index,
+// line, byte offset and length
+fn words(input: &str) -> impl Iterator<Item = (usize, &str, usize, usize)> {
+    input.lines().enumerate().flat_map(|(idx, line)| {
+        line.split_whitespace().map(move |word| {
+            let offset = word.as_ptr() as usize - line.as_ptr() as usize;
+            (idx, line, offset, word.len())
+        })
+    })
+}
+
+/// Parse the numbers of the license file
+pub fn parse_input(input: &str) -> Result<Vec<u32>, ParseError> {
+    words(input)
+        .map(|(idx, line, offset, len)| {
+            line[offset..offset + len].parse().map_err(|err| {
+                let message = format!("invalid number: {}", err);
+                ParseError::at(offset, len, message).on_line(idx, line)
+            })
+        })
+        .collect()
+}
+
+/// Count of the numbers making the node at the start of the license file,
+/// `None` if the numbers end before the node is complete
+pub fn tree_len(numbers: &[u32]) -> Option<usize> {
+    let mut pos = 0;
+    // children left to read and metadata entries of the nodes being read
+    let mut open: Vec<(u32, usize)> = Vec::new();
+    loop {
+        let children_n = *numbers.get(pos)?;
+        let metadata_n = *numbers.get(pos + 1)? as usize;
+        pos += 2;
+        open.push((children_n, metadata_n));
+
+        while let Some(&(0, metadata_n)) = open.last() {
+            open.pop();
+            pos += metadata_n;
+            if pos > numbers.len() {
+                return None;
+            }
+            match open.last_mut() {
+                Some((children_n, _)) => *children_n -= 1,
+                None => return Some(pos),
+            }
+        }
+    }
+}
+
+/// Parse the license file checking that it holds exactly one tree
+pub fn parse_tree(input: &str) -> Result<Vec<u32>, ParseError> {
+    let numbers = parse_input(input)?;
+    match tree_len(&numbers) {
+        Some(len) if len == numbers.len() => Ok(numbers),
+        Some(len) => {
+            let (idx, line, offset, _) = words(input).nth(len).expect("Extra number");
+            let err = ParseError::at(offset, line.len() - offset, "numbers after the root node");
+            Err(err.on_line(idx, line))
+        }
+        None => {
+            let message = "license file ends before the root node";
+            match input
+                .lines()
+                .enumerate()
+                .filter(|(_, l)| !l.trim().is_empty())
+                .last()
+            {
+                Some((idx, line)) => {
+                    let end = line.trim_end().len();
+                    Err(ParseError::at(end, 1, message).on_line(idx, line))
+                }
+                None => Err(ParseError::new(message)),
+            }
+        }
+    }
+}
+
+/// Sum of the metadata entries of the node read from the input and of
+/// all its descendants
+pub fn sum_metadata(input: &mut impl Iterator<Item = u32>) -> u32 {
+    let children_n = input.next().expect("Number of children");
+    let metadata_n = input.next().expect("Number of metadata") as usize;
+    let children_sum: u32 = (0..children_n).map(|_| sum_metadata(input.by_ref())).sum();
+    let metadata_sum: u32 = input.take(metadata_n).sum();
+    children_sum + metadata_sum
+}
+
+/// Value of the node read from the input: the sum of its metadata if it
+/// has no children, otherwise the sum of the values of the children
+/// referenced by its metadata
+pub fn node_value(input: &mut impl Iterator<Item = u32>) -> u32 {
+    let children_n = input.next().expect("Number of children");
+    let metadata_n = input.next().expect("Number of metadata") as usize;
+    let childrens_values: Vec<u32> = (0..children_n)
+        .map(|_| node_value(input.by_ref()))
+        .collect();
+
+    let metadata = input.take(metadata_n);
+    if children_n == 0 {
+        return metadata.sum();
+    }
+    metadata
+        .filter_map(|idx| {
+            let idx = idx as usize;
+            if idx > 0 && idx <= children_n as usize {
+                if let Some(child_value) = childrens_values.get(idx - 1) {
+                    return Some(child_value);
+                }
+            }
+            None
+        })
+        .sum()
+}
+
+/// Random license file of a tree of `count` nodes
+pub fn generate_tree(rng: &mut Rng, count: usize) -> Vec<u32> {
+    let mut numbers = Vec::new();
+    generate_node(rng, count.max(1), &mut numbers);
+    numbers
+}
+
+// a node and its descendants, `count` nodes in all
+fn generate_node(rng: &mut Rng, count: usize, numbers: &mut Vec<u32>) {
+    let descendants = count - 1;
+    let children_n = match descendants {
+        0 => 0,
+        _ => rng.range(1..=descendants.min(5) as i64) as usize,
+    };
+    let metadata_n = rng.range(1..=3) as usize;
+    numbers.extend([children_n as u32, metadata_n as u32]);
+
+    // the descendants split between the children, at least one each
+    let mut cuts: Vec<usize> = Vec::new();
+    while cuts.len() + 1 < children_n {
+        let cut = 1 + rng.index(descendants - 1);
+        if !cuts.contains(&cut) {
+            cuts.push(cut);
+        }
+    }
+    cuts.sort_unstable();
+    cuts.push(descendants);
+    let mut start = 0;
+    for cut in cuts.into_iter().take(children_n) {
+        generate_node(rng, cut - start, numbers);
+        start = cut;
+    }
+    numbers.extend((0..metadata_n).map(|_| rng.range(1..=9) as u32));
+}
+
+/// Part 1 sums all the metadata entries, part 2 is the value of the root
+#[derive(Default)]
+pub struct Day08;
+
+impl Solution for Day08 {
+    const DAY: u8 = 8;
+    const README: &'static str = include_str!("../README.md");
+
+    type Input = Vec<u32>;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    fn parse(&self, input: &str) -> Result<Vec<u32>, ParseError> {
+        parse_tree(input)
+    }
+
+    fn part1(&self, tree: &Vec<u32>) -> u32 {
+        sum_metadata(&mut tree.iter().cloned())
+    }
+
+    fn part2(&self, tree: &Vec<u32>) -> u32 {
+        node_value(&mut tree.iter().cloned())
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        format!("{}\n", LicenseFile(&generate_tree(rng, size)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        generate_tree, node_value, parse_input, parse_tree, sum_metadata, tree_len, Day08,
+        LicenseFile,
+    };
+    use common::{Rng, Solution};
+
+    #[test]
+    fn test_parse_input() {
+        let input = "1 2 3 4 5";
+        assert_eq!(parse_input(input), Ok(vec![1, 2, 3, 4, 5]));
+
+        let err = parse_input("1 2\n3 4a 5").unwrap_err();
+        assert_eq!((err.line(), err.column()), (Some(2), Some(3)));
+    }
+
+    #[test]
+    fn test_parse_tree() {
+        assert_eq!(tree_len(&[1, 1, 0, 1, 5, 6]), Some(6));
+        assert_eq!(tree_len(&[1, 1, 0, 1, 5, 6, 7]), Some(6));
+        assert_eq!(tree_len(&[1, 1, 0, 1, 5]), None);
+        assert_eq!(tree_len(&[]), None);
+
+        let err = parse_tree("1 1 0 1 5 6 7 8").unwrap_err();
+        assert_eq!(
+            (err.column(), err.message()),
+            (Some(13), "numbers after the root node")
+        );
+
+        let err = parse_tree("1 1 0 1 5\n\n").unwrap_err();
+        assert_eq!((err.line(), err.column()), (Some(1), Some(10)));
+    }
+
+    #[test]
+    fn test_sum_metadata_zero() {
+        let mut input = parse_input("0 0").unwrap().into_iter();
+        assert_eq!(sum_metadata(&mut input), 0);
+    }
+
+    #[test]
+    fn test_sum_metadata_metadata_only() {
+        let mut input = parse_input("0 2 11 12").unwrap().into_iter();
+        assert_eq!(sum_metadata(&mut input), 23);
+    }
+
+    #[test]
+    fn test_sum_metadata_empty_child() {
+        let mut input = parse_input("1 2 0 0 11 12").unwrap().into_iter();
+        assert_eq!(sum_metadata(&mut input), 23);
+    }
+
+    #[test]
+    fn test_sum_metadata_normal_child() {
+        let mut input = parse_input("1 2 0 2 13 14 11 12").unwrap().into_iter();
+        assert_eq!(sum_metadata(&mut input), 50);
+    }
+
+    #[test]
+    fn test_sum_metadata_grandchildren() {
+        let mut input = parse_input("1 2 2 2 0 1 15 0 1 16 13 14 11 12")
+            .unwrap()
+            .into_iter();
+        assert_eq!(sum_metadata(&mut input), 81);
+    }
+
+    #[test]
+    fn test_sum_metadata_chilrend_and_grandchildren() {
+        let mut input = parse_input("2 2 2 2 0 1 15 0 1 16 13 14 2 2 0 1 17 0 1 18 19 20 11 12")
+            .unwrap()
+            .into_iter();
+        assert_eq!(sum_metadata(&mut input), 155);
+    }
+
+    #[test]
+    fn test_sum_metadata_example() {
+        let mut input = parse_input("2 3 0 3 10 11 12 1 1 0 1 99 2 1 1 2")
+            .unwrap()
+            .into_iter();
+        assert_eq!(sum_metadata(&mut input), 138);
+    }
+
+    #[test]
+    fn test_node_value_zero() {
+        let mut input = parse_input("0 0").unwrap().into_iter();
+        assert_eq!(node_value(&mut input), 0);
+    }
+
+    #[test]
+    fn test_node_value_metadata_only() {
+        let mut input = parse_input("0 2 11 12").unwrap().into_iter();
+        assert_eq!(node_value(&mut input), 23);
+    }
+
+    #[test]
+    fn test_node_value_child() {
+        let mut input = parse_input("1 4 0 1 10 0 1 1 2").unwrap().into_iter();
+        assert_eq!(node_value(&mut input), 20);
+    }
+
+    #[test]
+    fn test_node_value_example() {
+        let mut input = parse_input("2 3 0 3 10 11 12 1 1 0 1 99 2 1 1 2")
+            .unwrap()
+            .into_iter();
+        assert_eq!(node_value(&mut input), 66);
+    }
+
+    #[test]
+    fn test_solution() {
+        let tree = Day08.parse("2 3 0 3 10 11 12 1 1 0 1 99 2 1 1 2").unwrap();
+
+        assert_eq!(Day08.part1(&tree), 138);
+        assert_eq!(Day08.part2(&tree), 66);
+    }
+
+    fn count_nodes(input: &mut impl Iterator<Item = u32>) -> usize {
+        let children_n = input.next().unwrap();
+        let metadata_n = input.next().unwrap() as usize;
+        let descendants: usize = (0..children_n).map(|_| count_nodes(input.by_ref())).sum();
+        input.take(metadata_n).for_each(drop);
+        1 + descendants
+    }
+
+    #[test]
+    fn test_generate() {
+        for seed in 0..10 {
+            let mut rng = Rng::new(seed);
+            let tree = generate_tree(&mut rng, 500);
+            assert_eq!(tree_len(&tree), Some(tree.len()));
+            assert_eq!(count_nodes(&mut tree.iter().cloned()), 500);
+
+            let tree = Day08.parse(&Day08.generate(&mut rng, 1)).unwrap();
+            assert_eq!(tree.len(), 2 + tree[1] as usize);
+            assert_eq!(Day08.part1(&tree), Day08.part2(&tree));
+        }
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let mut rng = Rng::new(0);
+        for _ in 0..200 {
+            let count = rng.range(1..=200) as usize;
+            let tree = generate_tree(&mut rng, count);
+            assert_eq!(parse_tree(&LicenseFile(&tree).to_string()), Ok(tree));
+        }
+        assert_eq!(LicenseFile(&[0, 1, 99]).to_string(), "0 1 99");
+        assert_eq!(LicenseFile(&[]).to_string(), "");
+    }
+}
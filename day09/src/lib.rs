@@ -0,0 +1,336 @@
+//! Day 9: Marble Mania
+
+use common::{flag_value, single_line, Flag, ParseError, Rng, Scanner, Solution};
+use std::fmt::{self, Display};
+
+// double linked list like structure
+// instead of having pointers to prev and next nodes it
+// keep their indexes in the vector
+#[derive(Debug)]
+struct MarbleNode {
+    value: u32,
+    prev: usize,
+    next: usize,
+}
+
+/// Position of a marble in the [`MarbleList`]
+pub type MarbleIndex = usize;
+
+/// Circle of marbles
+#[derive(Debug)]
+pub struct MarbleList {
+    nodes: Vec<MarbleNode>,
+}
+
+impl MarbleList {
+    /// Circle with only the marble 0, with room for `marbles` marbles
+    pub fn new(marbles: usize) -> MarbleList {
+        let mut nodes = Vec::with_capacity(marbles);
+        nodes.push(MarbleNode {
+            value: 0,
+            prev: 0,
+            next: 0,
+        });
+        MarbleList { nodes }
+    }
+
+    /// Value of the marble
+    pub fn get(&self, cur: MarbleIndex) -> u32 {
+        self.nodes.get(cur).expect("Cannot find MarbleNode").value
+    }
+
+    /// Place a marble between the marbles 1 and 2 positions clockwise of
+    /// the current one
+    pub fn add(&mut self, cur: MarbleIndex, value: u32) -> MarbleIndex {
+        let new = self.nodes.len();
+        let cur_node = self.nodes.get(cur).expect("Cannot find MarbleNode");
+
+        let prev = cur_node.next;
+        let prev_node = self.nodes.get_mut(prev).expect("Cannot find MarbleNode");
+        let next = prev_node.next;
+        prev_node.next = new;
+
+        let next_node = self.nodes.get_mut(next).expect("Cannot find MarbleNode");
+        next_node.prev = new;
+
+        self.nodes.push(MarbleNode { value, prev, next });
+
+        new
+    }
+
+    /// Marble 7 positions counter-clockwise of the current one
+    pub fn back(&self, cur: MarbleIndex) -> MarbleIndex {
+        let mut node = self.nodes.get(cur).expect("Cannot find MarbleNode");
+        for _ in 0..6 {
+            node = self.nodes.get(node.prev).expect("Cannot find MarbleNode");
+        }
+        node.prev
+    }
+
+    // we are leaving hole in the vector :(
+    /// Remove the marble, returning the one clockwise of it
+    pub fn remove(&mut self, cur: MarbleIndex) -> MarbleIndex {
+        let node = self.nodes.get(cur).expect("Cannot find MarbleNode");
+        let prev = node.prev;
+        let next = node.next;
+        let prev_node = self.nodes.get_mut(prev).expect("Cannot find MarbleNode");
+        prev_node.next = next;
+
+        let next_node = self.nodes.get_mut(next).expect("Cannot find MarbleNode");
+        next_node.prev = prev;
+
+        next
+    }
+}
+
+/// Circle of marbles and scores of the players during a game
+pub struct GameState {
+    marbles: MarbleList,
+    marble_cur: MarbleIndex,
+    pub scores: Vec<u32>,
+    marble_value: u32,
+}
+
+impl GameState {
+    pub fn new(players_n: usize, marbles_n: usize) -> GameState {
+        let scores: Vec<u32> = vec![0; players_n];
+        GameState {
+            marbles: MarbleList::new(marbles_n),
+            marble_cur: 0,
+            scores,
+            marble_value: 0,
+        }
+    }
+}
+
+/// Advance the game until some player score
+pub fn next_state(mut gs: GameState) -> GameState {
+    for _ in 0..22 {
+        gs.marble_value += 1;
+        gs.marble_cur = gs.marbles.add(gs.marble_cur, gs.marble_value);
+    }
+
+    gs.marble_value += 1;
+    let player = gs.marble_value as usize % gs.scores.len();
+    gs.scores[player] += gs.marble_value;
+
+    let to_remove = gs.marbles.back(gs.marble_cur);
+    gs.scores[player] += gs.marbles.get(to_remove);
+
+    gs.marble_cur = gs.marbles.remove(to_remove);
+
+    gs
+}
+
+/// Play until the last marble, worth `marbles` points, is used
+pub fn play_game(marbles: usize, players: usize) -> GameState {
+    let turns = marbles / 23;
+    (0..turns).fold(GameState::new(players, marbles), |gs, _| next_state(gs))
+}
+
+/// Score of the winning player
+pub fn max_score(gs: &GameState) -> u32 {
+    *gs.scores.iter().max().expect("Maximum score")
+}
+
+/// The number of players and the points of the last marble printed like
+/// the puzzle input
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Game {
+    pub players: usize,
+    pub marbles: usize,
+}
+
+impl Display for Game {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} players; last marble is worth {} points",
+            self.players, self.marbles
+        )
+    }
+}
+
+/// Parse the number of players and the points of the last marble from
+/// `10 players; last marble is worth 1618 points`
+pub fn parse_game(input: &str) -> Result<(usize, usize), ParseError> {
+    let line = single_line(input)?;
+    let parse = || {
+        let mut s = Scanner::new(line);
+        let players = s.number()?;
+        if players == 0 {
+            return Err(ParseError::at(0, 1, "expected at least one player"));
+        }
+        s.literal(" players; last marble is worth ")?;
+        let marbles = s.number()?;
+        s.literal(" points")?;
+        s.end()?;
+        Ok((players, marbles))
+    };
+    parse().map_err(|err| err.on_line(0, line))
+}
+
+/// Random number of players and points of the last marble, about
+/// `size` * 100 points but few enough for the scores of part 2 to fit
+pub fn generate_game(rng: &mut Rng, size: usize) -> (usize, usize) {
+    let players = rng.range(10..=500) as usize;
+    // the scores add up to about a thirtieth of the square of the last
+    // marble of part 2, a hundred times larger
+    let max = ((45e9 * players as f64).sqrt() / 100.0) as usize;
+    let marbles = (size * 100).clamp(25, max);
+    (
+        players,
+        rng.range(marbles as i64 / 2..=marbles as i64) as usize,
+    )
+}
+
+/// Part 1 is the winning score, part 2 the winning score with a last
+/// marble `multiplier` times larger
+pub struct Day09 {
+    pub multiplier: usize,
+}
+
+impl Default for Day09 {
+    fn default() -> Day09 {
+        Day09 { multiplier: 100 }
+    }
+}
+
+impl Solution for Day09 {
+    const DAY: u8 = 9;
+    const README: &'static str = include_str!("../README.md");
+
+    type Input = (usize, usize);
+    type Part1 = u32;
+    type Part2 = u32;
+
+    const FLAGS: &'static [Flag] = &[Flag {
+        name: "multiplier",
+        value: "N",
+        help: "how many times the last marble is larger in part 2 (100)",
+    }];
+
+    fn set_flag(&mut self, flag: &str, value: &str) -> Result<(), String> {
+        match flag {
+            "multiplier" => self.multiplier = flag_value(flag, value)?,
+            _ => return Err(format!("Unknown flag --{}", flag)),
+        }
+        Ok(())
+    }
+
+    fn parse(&self, input: &str) -> Result<(usize, usize), ParseError> {
+        parse_game(input)
+    }
+
+    fn part1(&self, &(players, marbles): &(usize, usize)) -> u32 {
+        max_score(&play_game(marbles, players))
+    }
+
+    fn part2(&self, &(players, marbles): &(usize, usize)) -> u32 {
+        max_score(&play_game(marbles * self.multiplier, players))
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        let (players, marbles) = generate_game(rng, size);
+        format!("{}\n", Game { players, marbles })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{generate_game, parse_game, play_game, Day09, Game};
+    use common::{Rng, Solution};
+
+    #[test]
+    fn test_parse_game_examples() {
+        let input = "10 players; last marble is worth 1618 points";
+        assert_eq!(parse_game(input), Ok((10, 1618)));
+
+        let err = parse_game("0 players; last marble is worth 1618 points").unwrap_err();
+        assert_eq!(err.message(), "expected at least one player");
+
+        let err = parse_game("10 players; last marble is worth 1618").unwrap_err();
+        assert_eq!(
+            (err.column(), err.message()),
+            (Some(38), "expected ` points`")
+        );
+    }
+
+    #[test]
+    fn test_play_game_examples() {
+        let gs = play_game(25, 9);
+        assert_eq!(*gs.scores.iter().max().unwrap(), 32);
+
+        let gs = play_game(1618, 10);
+        assert_eq!(*gs.scores.iter().max().unwrap(), 8317);
+
+        let gs = play_game(7999, 13);
+        assert_eq!(*gs.scores.iter().max().unwrap(), 146_373);
+
+        let gs = play_game(1104, 17);
+        assert_eq!(*gs.scores.iter().max().unwrap(), 2764);
+
+        let gs = play_game(6111, 21);
+        assert_eq!(*gs.scores.iter().max().unwrap(), 54718);
+
+        let gs = play_game(5807, 30);
+        assert_eq!(*gs.scores.iter().max().unwrap(), 37305);
+    }
+
+    #[test]
+    fn test_solution() {
+        let day = Day09::default();
+        let game = day
+            .parse("10 players; last marble is worth 1618 points")
+            .unwrap();
+
+        assert_eq!(day.part1(&game), 8317);
+        assert_eq!(Day09 { multiplier: 1 }.part2(&game), 8317);
+
+        let mut day = Day09::default();
+        assert_eq!(
+            day.set_flag("times", "1"),
+            Err("Unknown flag --times".to_string())
+        );
+        assert_eq!(day.multiplier, 100);
+    }
+
+    #[test]
+    fn test_generate() {
+        for seed in 0..10 {
+            let mut rng = Rng::new(seed);
+            let (players, marbles) = generate_game(&mut rng, 1000);
+            assert!((10..=500).contains(&players));
+            assert!(marbles <= 100_000);
+
+            let day = Day09::default();
+            let game = day.parse(&day.generate(&mut rng, 10)).unwrap();
+            assert!(game.1 >= 25);
+            day.part1(&game);
+        }
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let mut rng = Rng::new(0);
+        for _ in 0..1_000 {
+            let mut number = || (rng.next_u64() >> rng.below(64)) as usize;
+            let game = Game {
+                players: number().max(1),
+                marbles: number(),
+            };
+            assert_eq!(
+                parse_game(&game.to_string()),
+                Ok((game.players, game.marbles))
+            );
+        }
+        let game = Game {
+            players: 10,
+            marbles: 1618,
+        };
+        assert_eq!(
+            game.to_string(),
+            "10 players; last marble is worth 1618 points"
+        );
+    }
+}
@@ -0,0 +1,443 @@
+//! Day 10: The Stars Align
+
+use common::{
+    parse_lines, BoundingBox, Grid, Image, ParseError, Point, Rgb, Rng, Scanner, Simulation,
+    Solution,
+};
+use std::fmt::{self, Display};
+
+pub mod oracle;
+
+/// Position and velocity of a point of light
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Light {
+    pub position: Point,
+    pub velocity: Point,
+}
+
+impl Light {
+    pub fn new(position: Point, velocity: Point) -> Light {
+        Light { position, velocity }
+    }
+
+    /// Move the light by its velocity `step` times
+    pub fn translate(&mut self, step: i32) {
+        self.position += self.velocity * step;
+    }
+}
+
+/// Printed in the format of the puzzle, the numbers aligned in columns
+/// like `position=<     9,      1> velocity=< 0,  2>`
+impl Display for Light {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (p, v) = (self.position, self.velocity);
+        write!(
+            f,
+            "position=<{:>6}, {:>6}> velocity=<{:>2}, {:>2}>",
+            p.x, p.y, v.x, v.y
+        )
+    }
+}
+
+/// Parse a line like `position=< 9,  1> velocity=< 0,  2>`
+pub fn parse_light(line: &str) -> Result<Light, ParseError> {
+    // `<x, y>` with spaces allowed around the numbers
+    let pair = |s: &mut Scanner| -> Result<Point, ParseError> {
+        s.literal("<")?;
+        s.skip_whitespace();
+        let x = s.number()?;
+        s.literal(",")?;
+        s.skip_whitespace();
+        let y = s.number()?;
+        s.skip_whitespace();
+        s.literal(">")?;
+        Ok(Point::new(x, y))
+    };
+
+    let mut s = Scanner::new(line);
+    s.literal("position=")?;
+    let position = pair(&mut s)?;
+    s.literal(" velocity=")?;
+    let velocity = pair(&mut s)?;
+    s.end()?;
+    Ok(Light::new(position, velocity))
+}
+
+/// Parse one light per line
+pub fn parse_lights(input: &str) -> Result<Vec<Light>, ParseError> {
+    parse_lines(input, parse_light)
+}
+
+/// Bounding box of the lights, none when there are no lights
+pub fn get_bbox(lights: &[Light]) -> Option<BoundingBox> {
+    BoundingBox::from_points(lights.iter().map(|light| light.position))
+}
+
+/// Draw the lights inside the bounding box, `#` for a light and `.` for
+/// the sky
+pub fn format_grid(bbox: &BoundingBox, lights: &[Light]) -> String {
+    let mut sky = Grid::new(*bbox, '.');
+    for light in lights {
+        if let Some(c) = sky.get_mut(light.position) {
+            *c = '#';
+        }
+    }
+    sky.rows()
+        .map(|row| row.iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Number of seconds after which the lights are the closest vertically,
+/// the first of them if they stay as close for a while; none when they
+/// still get closer after `i32::MAX` seconds or when their coordinates no
+/// longer fit an `i32` by then
+pub fn find_message_steps(lights: &[Light]) -> Option<i32> {
+    // in i64, where the rows cannot overflow for up to `i32::MAX` seconds
+    let height = |steps: i64| {
+        let rows = lights
+            .iter()
+            .map(|light| i64::from(light.position.y) + i64::from(light.velocity.y) * steps);
+        let (max, min) = (rows.clone().max().unwrap_or(0), rows.min().unwrap_or(0));
+        max.saturating_sub(min)
+    };
+    // the height shrinks then grows with the seconds, so the seconds after
+    // which it stops shrinking are found by bisection
+    let (mut low, mut high) = (0, i64::from(i32::MAX) + 1);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if height(mid + 1) < height(mid) {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    if low > i64::from(i32::MAX) {
+        return None;
+    }
+
+    let steps = low as i32;
+    let fits = |light: &Light| {
+        let moved = |p: i32, v: i32| v.checked_mul(steps).and_then(|d| p.checked_add(d));
+        moved(light.position.x, light.velocity.x).is_some()
+            && moved(light.position.y, light.velocity.y).is_some()
+    };
+    lights.iter().all(fits).then_some(steps)
+}
+
+/// Parse the lights and check that they come the closest after a number
+/// of seconds that the coordinates can hold
+pub fn parse_sky(input: &str) -> Result<Vec<Light>, ParseError> {
+    let lights = parse_lights(input)?;
+    match find_message_steps(&lights) {
+        Some(_) => Ok(lights),
+        None => Err(ParseError::new(
+            "the lights never come the closest with coordinates in the i32 range",
+        )),
+    }
+}
+
+/// Move the lights to the message and return it with the seconds needed,
+/// none when they never come the closest, see [`find_message_steps`]
+pub fn find_message(mut lights: Vec<Light>) -> Option<(String, i32)> {
+    let steps = find_message_steps(&lights)?;
+    lights.iter_mut().for_each(|light| light.translate(steps));
+    let message = get_bbox(&lights).map_or_else(String::new, |bbox| format_grid(&bbox, &lights));
+    Some((message, steps))
+}
+
+const SKY: Rgb = Rgb(10, 10, 40);
+const LIGHT: Rgb = Rgb(250, 230, 120);
+
+// whether there is a light at every point of the viewport
+fn lights_grid(viewport: &BoundingBox, lights: &[Light]) -> Grid<bool> {
+    let mut sky = Grid::new(*viewport, false);
+    for light in lights {
+        if let Some(lit) = sky.get_mut(light.position) {
+            *lit = true;
+        }
+    }
+    sky
+}
+
+// the lights inside the viewport, four pixels per point of the sky
+fn draw_lights(viewport: &BoundingBox, lights: &[Light]) -> Image {
+    let sky = lights_grid(viewport, lights);
+    Image::from_grid(&sky, |_, &lit| if lit { LIGHT } else { SKY }).scale(4)
+}
+
+/// Draw the lights once they show the message, four pixels per point of
+/// the sky; none when there are no lights or no message
+pub fn render_message(mut lights: Vec<Light>) -> Option<Image> {
+    let steps = find_message_steps(&lights)?;
+    lights.iter_mut().for_each(|light| light.translate(steps));
+    let bbox = get_bbox(&lights)?.grow(1);
+    Some(draw_lights(&bbox, &lights))
+}
+
+/// The lights moving one second at a time until they show the message,
+/// seen through a window around the message
+pub struct LightsSimulation {
+    lights: Vec<Light>,
+    viewport: BoundingBox,
+    remaining: i32,
+}
+
+impl LightsSimulation {
+    /// None when there are no lights or no message
+    pub fn new(lights: Vec<Light>) -> Option<LightsSimulation> {
+        let steps = find_message_steps(&lights)?;
+        let mut message = lights.clone();
+        message.iter_mut().for_each(|light| light.translate(steps));
+        let bbox = get_bbox(&message)?;
+        // room for the lights closing in on the message
+        let margin = (bbox.width().max(bbox.height()) / 2) as i32;
+        Some(LightsSimulation {
+            lights,
+            viewport: bbox.grow(margin.max(1)),
+            remaining: steps.max(0),
+        })
+    }
+}
+
+impl Simulation for LightsSimulation {
+    fn step(&mut self) -> bool {
+        if self.remaining == 0 {
+            return false;
+        }
+        self.lights.iter_mut().for_each(|light| light.translate(1));
+        self.remaining -= 1;
+        true
+    }
+
+    fn render(&self) -> Image {
+        draw_lights(&self.viewport, &self.lights)
+    }
+
+    fn text(&self) -> Grid<(char, Rgb)> {
+        // the sky lighter than in the pictures to be seen on a dark terminal
+        lights_grid(&self.viewport, &self.lights).map(|_, &lit| match lit {
+            true => ('#', LIGHT),
+            false => ('.', Rgb(70, 70, 130)),
+        })
+    }
+
+    fn event(&self) -> Option<String> {
+        if self.remaining == 0 {
+            Some("message".to_string())
+        } else {
+            None
+        }
+    }
+}
+
+/// Random lights meeting in a box of 60x10 points after the returned
+/// number of seconds, every light moving up or down
+pub fn generate_lights(rng: &mut Rng, count: usize) -> (Vec<Light>, i32) {
+    let steps = rng.range(5_000..=15_000) as i32;
+    let lights = (0..count.max(1))
+        .map(|_| {
+            let message = Point::new(rng.range(100..=159) as i32, rng.range(100..=109) as i32);
+            let vy = rng.range(1..=5) as i32 * if rng.chance(0.5) { 1 } else { -1 };
+            let velocity = Point::new(rng.range(-5..=5) as i32, vy);
+            Light::new(message - velocity * steps, velocity)
+        })
+        .collect();
+    (lights, steps)
+}
+
+/// Part 1 is the message drawn by the points, part 2 the seconds needed to
+/// see it
+#[derive(Default)]
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u8 = 10;
+    const README: &'static str = include_str!("../README.md");
+
+    type Input = Vec<Light>;
+    type Part1 = String;
+    type Part2 = i32;
+
+    fn parse(&self, input: &str) -> Result<Vec<Light>, ParseError> {
+        parse_sky(input)
+    }
+
+    fn part1(&self, lights: &Vec<Light>) -> String {
+        let (message, _) = find_message(lights.clone()).expect("No message");
+        message
+    }
+
+    fn part2(&self, lights: &Vec<Light>) -> i32 {
+        find_message_steps(lights).expect("No message")
+    }
+
+    fn render(&self, lights: &Vec<Light>) -> Option<Image> {
+        render_message(lights.clone())
+    }
+
+    fn simulate(&self, lights: &Vec<Light>) -> Option<Box<dyn Simulation>> {
+        let simulation = LightsSimulation::new(lights.clone())?;
+        Some(Box::new(simulation))
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        let (lights, _) = generate_lights(rng, size);
+        lights.iter().map(|light| format!("{}\n", light)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        find_message_steps, generate_lights, get_bbox, parse_light, parse_lights, render_message,
+        Day10, Light, LightsSimulation,
+    };
+    use common::{Point, Rgb, Rng, Simulation, Solution};
+
+    #[test]
+    fn test_parse_light() {
+        let line = "position=< 9,  1> velocity=< 0,  2>";
+        assert_eq!(
+            parse_light(line),
+            Ok(Light::new(Point::new(9, 1), Point::new(0, 2)))
+        );
+
+        let line = "position=<-3, 11> velocity=<1, -2>";
+        assert_eq!(
+            parse_light(line),
+            Ok(Light::new(Point::new(-3, 11), Point::new(1, -2)))
+        );
+
+        let err =
+            parse_lights("position=< 9,  1> velocity=< 0,  2>\nposition=< 9,  1>").unwrap_err();
+        assert_eq!((err.line(), err.column()), (Some(2), Some(18)));
+        assert_eq!(err.message(), "expected ` velocity=`");
+    }
+
+    #[test]
+    fn test_find_message_steps_overflow() {
+        // moving apart from the start, the height does not fit an i32
+        let lights = parse_lights(
+            "position=<0, 2000000000> velocity=<0, 5>\n\
+             position=<0, -2000000000> velocity=<0, -5>",
+        )
+        .unwrap();
+        assert_eq!(find_message_steps(&lights), Some(0));
+
+        let lights = parse_lights(
+            "position=<0, 2000000000> velocity=<0, -5>\n\
+             position=<0, -2000000000> velocity=<0, 5>",
+        )
+        .unwrap();
+        assert_eq!(find_message_steps(&lights), Some(400_000_000));
+
+        // still getting closer after i32::MAX seconds
+        let input = "position=<0, 2147483647> velocity=<0, -1>\n\
+                     position=<0, -2147483648> velocity=<0, 0>";
+        assert_eq!(find_message_steps(&parse_lights(input).unwrap()), None);
+        let err = Day10.parse(input).unwrap_err();
+        assert_eq!(
+            err.message(),
+            "the lights never come the closest with coordinates in the i32 range"
+        );
+
+        // a light out of range once the others meet
+        let input = "position=<0, 100> velocity=<0, -1>\n\
+                     position=<0, -100> velocity=<0, 1>\n\
+                     position=<2147483600, 0> velocity=<1, 0>";
+        assert_eq!(find_message_steps(&parse_lights(input).unwrap()), None);
+        assert!(Day10.parse(input).is_err());
+    }
+
+    #[test]
+    fn test_solution() {
+        let lights = Day10.parse(include_str!("../test_input0")).unwrap();
+        let message = "#...#..###\n\
+                       #...#...#.\n\
+                       #...#...#.\n\
+                       #####...#.\n\
+                       #...#...#.\n\
+                       #...#...#.\n\
+                       #...#...#.\n\
+                       #...#..###";
+
+        assert_eq!(Day10.part1(&lights), message);
+        assert_eq!(Day10.part2(&lights), 3);
+    }
+
+    #[test]
+    fn test_render_message() {
+        let lights = Day10.parse(include_str!("../test_input0")).unwrap();
+        let image = render_message(lights).unwrap();
+
+        // the message of 10x8 points with a border of one point
+        assert_eq!((image.width(), image.height()), (12 * 4, 10 * 4));
+        assert_eq!(image.get(4, 4), Some(Rgb(250, 230, 120)));
+        assert_eq!(image.get(8, 4), Some(Rgb(10, 10, 40)));
+        assert_eq!(render_message(Vec::new()), None);
+    }
+
+    #[test]
+    fn test_simulation() {
+        let lights = Day10.parse(include_str!("../test_input0")).unwrap();
+        let mut simulation = LightsSimulation::new(lights.clone()).unwrap();
+        let first = simulation.render();
+        assert!((0..3).all(|_| simulation.step()));
+        assert!(!simulation.step());
+
+        // the same window at every step, the message in the middle
+        let last = simulation.render();
+        assert_eq!(
+            (first.width(), first.height()),
+            (last.width(), last.height())
+        );
+        assert_eq!((last.width(), last.height()), (20 * 4, 18 * 4));
+        assert_eq!(last.get(5 * 4, 5 * 4), Some(Rgb(250, 230, 120)));
+        assert_eq!(simulation.event(), Some("message".to_string()));
+        let text = simulation.text();
+        let corner = text.bounds().min + Point::new(5, 5);
+        assert_eq!(text[corner], ('#', Rgb(250, 230, 120)));
+        assert_eq!(text[corner + Point::new(1, 0)].0, '.');
+        assert!(LightsSimulation::new(Vec::new()).is_none());
+    }
+
+    #[test]
+    fn test_generate() {
+        let area = |lights: &[Light], steps: i32| {
+            let mut lights = lights.to_vec();
+            lights.iter_mut().for_each(|light| light.translate(steps));
+            let bbox = get_bbox(&lights).unwrap();
+            bbox.width() * bbox.height()
+        };
+        for seed in 0..10 {
+            let mut rng = Rng::new(seed);
+            let (lights, steps) = generate_lights(&mut rng, 300);
+            assert!(area(&lights, steps) <= 60 * 10);
+            assert!(area(&lights, steps - 1) > area(&lights, steps));
+            assert!(area(&lights, steps + 1) > area(&lights, steps));
+
+            let lights = Day10.parse(&Day10.generate(&mut rng, 50)).unwrap();
+            assert_eq!(lights.len(), 50);
+            Day10.part1(&lights);
+        }
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let mut rng = Rng::new(0);
+        for _ in 0..1_000 {
+            let mut number = || rng.next_u64() as i32 >> rng.below(32);
+            let light = Light::new(
+                Point::new(number(), number()),
+                Point::new(number(), number()),
+            );
+            assert_eq!(parse_light(&light.to_string()), Ok(light));
+        }
+        let light = Light::new(Point::new(9, -1), Point::new(0, 2));
+        assert_eq!(
+            light.to_string(),
+            "position=<     9,     -1> velocity=< 0,  2>"
+        );
+    }
+}
@@ -0,0 +1,63 @@
+//! Lights moved one second at a time, the oracle of the differential
+//! tests of the step formula of
+//! [`find_message_steps`](crate::find_message_steps)
+
+use crate::Light;
+
+// distance between the top and the bottom lights after `steps` seconds
+fn height(lights: &[Light], steps: i32) -> i32 {
+    let rows = lights
+        .iter()
+        .map(|light| light.position.y + light.velocity.y * steps);
+    rows.clone().max().unwrap_or(0) - rows.min().unwrap_or(0)
+}
+
+/// Seconds after which the lights are the closest vertically, moving them
+/// while they get closer
+pub fn message_steps(lights: &[Light]) -> i32 {
+    let mut steps = 0;
+    while height(lights, steps + 1) < height(lights, steps) {
+        steps += 1;
+    }
+    steps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::message_steps;
+    use crate::{find_message_steps, generate_lights, Day10, Light};
+    use common::{differential, shrink_vec, Rng, Solution};
+
+    // lights meeting after up to a few hundred seconds, some of the
+    // puzzle inputs are that short
+    fn generate(rng: &mut Rng) -> Vec<Light> {
+        let count = rng.range(1..=30) as usize;
+        let (mut lights, steps) = generate_lights(rng, count);
+        let earlier = steps - rng.range(0..=300) as i32;
+        lights.iter_mut().for_each(|light| light.translate(earlier));
+        lights
+    }
+
+    #[test]
+    fn test_message_steps() {
+        let lights = Day10.parse(include_str!("../test_input0")).unwrap();
+        assert_eq!(message_steps(&lights), 3);
+
+        let shrink = |lights: &Vec<Light>| {
+            shrink_vec(lights)
+                .into_iter()
+                .filter(|lights| !lights.is_empty())
+                .collect()
+        };
+        let result = differential(
+            300,
+            generate,
+            shrink,
+            |lights| Some(message_steps(lights)),
+            |lights| find_message_steps(lights),
+        );
+        if let Err(mismatch) = result {
+            panic!("{}", mismatch);
+        }
+    }
+}
@@ -0,0 +1,472 @@
+//! Day 11: Chronal Charge
+
+use common::{
+    flag_value, single_line, Answer, BoundingBox, Flag, Grid, Image, Json, ParseError, Point, Rgb,
+    Rng, Scanner, Solution,
+};
+use std::fmt::{self, Display};
+
+pub mod oracle;
+
+/// Square grid of side `side` with the 1-based coordinates of the fuel
+/// cells
+pub fn grid_bounds(side: usize) -> BoundingBox {
+    BoundingBox::with_size(Point::new(1, 1), side, side)
+}
+
+/// Power level of the fuel cell for the grid serial number
+pub fn get_power_level(coord: Point, serial_n: i32) -> i8 {
+    let id = coord.x + 10;
+    let pl = id * coord.y;
+    let pl = pl + serial_n;
+    let pl = pl * id;
+    let pl = (pl / 100 % 10) as i8;
+
+    pl - 5
+}
+
+/// Grid of the power levels of the fuel cells
+pub fn init_grid(side: usize, serial_n: i32) -> Grid<i32> {
+    Grid::from_fn(grid_bounds(side), |coord| {
+        get_power_level(coord, serial_n) as i32
+    })
+}
+
+/// Grid of the total power of the 3x3 squares, indexed by their top-left
+/// cell
+pub fn compute_sub_squares(grid: &Grid<i32>) -> Grid<i32> {
+    Grid::from_fn(grid_bounds(grid.width() - 2), |coord| {
+        BoundingBox::with_size(coord, 3, 3)
+            .points()
+            .map(|cell| grid[cell])
+            .sum()
+    })
+}
+
+/// Grid of the total power of the squares one cell larger than the ones
+/// of `prev_grid`, computed from the power levels of `base_grid`
+pub fn compute_sub_squares_from_prev(base_grid: &Grid<i32>, prev_grid: &Grid<i32>) -> Grid<i32> {
+    let side = prev_grid.width() - 1;
+    let size = base_grid.width() - side;
+    // rows indexed from the top-left cell, faster than indexing the grids
+    // by point which checks the bounds of every cell
+    let base: Vec<&[i32]> = base_grid.rows().collect();
+    let prev: Vec<&[i32]> = prev_grid.rows().collect();
+
+    Grid::from_fn(grid_bounds(side), |coord| {
+        let (x, y) = ((coord.x - 1) as usize, (coord.y - 1) as usize);
+        // power level of the previous square
+        // plus the line under it, the column at its right
+        // and the cell in the corner
+        prev[y][x]
+            + base[y + size][x..=x + size].iter().sum::<i32>()
+            + base[y..y + size]
+                .iter()
+                .map(|row| row[x + size])
+                .sum::<i32>()
+    })
+}
+
+/// Grid of the total power of the squares of side `size`, computed from
+/// the power levels of `grid`
+pub fn compute_squares_of_size(grid: &Grid<i32>, size: usize) -> Grid<i32> {
+    (1..size).fold(grid.clone(), |prev_grid, _| {
+        compute_sub_squares_from_prev(grid, &prev_grid)
+    })
+}
+
+/// Largest total power in the grid and its coordinate, the rightmost and
+/// then lowest one on ties
+pub fn get_max_square(grid: &Grid<i32>) -> (i32, Point) {
+    grid.iter()
+        .map(|(coord, &total)| (total, coord))
+        .max()
+        .expect("Cannot find square")
+}
+
+/// Largest total power of a square of any size with its coordinate and
+/// size
+pub fn get_max_square_with_size(grid: &Grid<i32>) -> (i32, Point, usize) {
+    (2..=grid.width())
+        .scan(grid.clone(), |prev_grid, size| {
+            *prev_grid = compute_sub_squares_from_prev(grid, prev_grid);
+            let (total, coord) = get_max_square(prev_grid);
+            Some((total, coord, size))
+        })
+        .max()
+        .unwrap()
+}
+
+/// Draw the power levels as a heatmap from blue for -5 to red for 4, two
+/// pixels per fuel cell, with the border of the square of side `square`
+/// with the largest total power in white
+pub fn render_power_levels(grid: &Grid<i32>, square: usize) -> Image {
+    let (_, best) = get_max_square(&compute_squares_of_size(grid, square));
+    let outline = BoundingBox::with_size(best, square, square).grow(1);
+    let image = Image::from_grid(grid, |coord, &pl| {
+        if outline.contains(coord) && outline.is_on_border(coord) {
+            Rgb::WHITE
+        } else {
+            Rgb::heat((pl + 5) as f64 / 9.0)
+        }
+    });
+    image.scale(2)
+}
+
+/// Square of the size of part 1 with the largest total power, by its
+/// top-left fuel cell
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Square {
+    pub coord: Point,
+    pub total: i32,
+}
+
+impl Display for Square {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} with a total of {}", self.coord, self.total)
+    }
+}
+
+impl Answer for Square {
+    fn fields(&self) -> Vec<(&'static str, Json)> {
+        vec![
+            ("x", self.coord.x.into()),
+            ("y", self.coord.y.into()),
+            ("total", self.total.into()),
+        ]
+    }
+}
+
+/// Square of any size with the largest total power
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SizedSquare {
+    pub coord: Point,
+    pub size: usize,
+    pub total: i32,
+}
+
+impl Display for SizedSquare {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} of size {} with a total of {}",
+            self.coord, self.size, self.total
+        )
+    }
+}
+
+impl Answer for SizedSquare {
+    fn fields(&self) -> Vec<(&'static str, Json)> {
+        vec![
+            ("x", self.coord.x.into()),
+            ("y", self.coord.y.into()),
+            ("size", self.size.into()),
+            ("total", self.total.into()),
+        ]
+    }
+}
+
+/// Part 1 finds the square of the given size with the largest total
+/// power, part 2 the square of any size
+pub struct Day11 {
+    /// Side of the grid of fuel cells
+    pub side: usize,
+    /// Side of the square of part 1
+    pub square: usize,
+}
+
+impl Default for Day11 {
+    fn default() -> Day11 {
+        Day11 {
+            side: 300,
+            square: 3,
+        }
+    }
+}
+
+impl Solution for Day11 {
+    const DAY: u8 = 11;
+    const README: &'static str = include_str!("../README.md");
+
+    type Input = i32;
+    type Part1 = Square;
+    type Part2 = SizedSquare;
+
+    const FLAGS: &'static [Flag] = &[
+        Flag {
+            name: "side",
+            value: "N",
+            help: "side of the grid of fuel cells (300)",
+        },
+        Flag {
+            name: "square",
+            value: "N",
+            help: "side of the square of part 1 (3)",
+        },
+    ];
+
+    fn set_flag(&mut self, flag: &str, value: &str) -> Result<(), String> {
+        // part 2 looks at the squares from size 2
+        match flag {
+            "side" => match flag_value(flag, value)? {
+                side if side >= 2 => self.side = side,
+                _ => return Err("--side must be at least 2".to_string()),
+            },
+            "square" => match flag_value(flag, value)? {
+                0 => return Err("--square must be at least 1".to_string()),
+                square => self.square = square,
+            },
+            _ => return Err(format!("Unknown flag --{}", flag)),
+        }
+        if self.square > self.side {
+            return Err("The square cannot be larger than the grid".to_string());
+        }
+        Ok(())
+    }
+
+    fn parse(&self, input: &str) -> Result<i32, ParseError> {
+        let line = single_line(input)?;
+        let mut s = Scanner::new(line);
+        s.number()
+            .and_then(|serial_n| s.end().map(|_| serial_n))
+            .map_err(|err| err.on_line(0, line))
+    }
+
+    fn part1(&self, &serial_n: &i32) -> Square {
+        let power_levels_grid = init_grid(self.side, serial_n);
+        let squares_grid = compute_squares_of_size(&power_levels_grid, self.square);
+        let (total, coord) = get_max_square(&squares_grid);
+        Square { coord, total }
+    }
+
+    fn part2(&self, &serial_n: &i32) -> SizedSquare {
+        let power_levels_grid = init_grid(self.side, serial_n);
+        let (total, coord, size) = get_max_square_with_size(&power_levels_grid);
+        SizedSquare { coord, size, total }
+    }
+
+    fn render(&self, &serial_n: &i32) -> Option<Image> {
+        let power_levels_grid = init_grid(self.side, serial_n);
+        Some(render_power_levels(&power_levels_grid, self.square))
+    }
+
+    /// A serial number of up to 4 digits, whatever the size
+    fn generate(&self, rng: &mut Rng, _size: usize) -> String {
+        format!("{}\n", rng.range(1..=9999))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        compute_squares_of_size, compute_sub_squares, compute_sub_squares_from_prev,
+        get_max_square, get_max_square_with_size, get_power_level, init_grid, render_power_levels,
+        Day11, SizedSquare, Square,
+    };
+    use common::{set_flags, Answer, Grid, Json, Point, Rgb, Rng, Solution};
+
+    // values of the grid column by column
+    fn by_column(grid: &Grid<i32>) -> Vec<i32> {
+        grid.values_by_column().copied().collect()
+    }
+
+    #[test]
+    fn test_get_power_level() {
+        assert_eq!(get_power_level(Point::new(3, 5), 8), 4);
+        assert_eq!(get_power_level(Point::new(122, 79), 57), -5);
+        assert_eq!(get_power_level(Point::new(217, 196), 39), 0);
+        assert_eq!(get_power_level(Point::new(101, 153), 71), 4);
+    }
+
+    #[test]
+    fn test_init_grid() {
+        let pls_grid = init_grid(3, 0);
+        assert_eq!(
+            by_column(&pls_grid),
+            vec![-4, -3, -2, -4, -3, -1, -4, -2, 0]
+        );
+        let pls_grid = init_grid(3, 10);
+        assert_eq!(by_column(&pls_grid), vec![-3, -2, -1, -3, -1, 0, -3, -1, 1]);
+        let pls_grid = init_grid(4, 0);
+        assert_eq!(
+            by_column(&pls_grid),
+            vec![-4, -3, -2, -1, -4, -3, -1, 0, -4, -2, 0, 1, -4, -2, 0, 2]
+        );
+    }
+
+    #[test]
+    fn test_compute_sub_squares() {
+        let pls_grid = init_grid(3, 0);
+        let squares_grid = compute_sub_squares(&pls_grid);
+
+        assert_eq!(by_column(&squares_grid), vec![-23]);
+
+        let pls_grid = init_grid(4, 0);
+        let squares_grid = compute_sub_squares(&pls_grid);
+
+        assert_eq!(by_column(&squares_grid), vec![-23, -11, -20, -5]);
+    }
+
+    #[test]
+    fn test_get_max_square_18() {
+        let side = 300;
+        let serial_n = 18;
+        let pls_grid = init_grid(side, serial_n);
+        let squares_grid = compute_sub_squares(&pls_grid);
+        assert_eq!(get_max_square(&squares_grid), (29, Point::new(33, 45)));
+    }
+
+    #[test]
+    fn test_get_max_square_42() {
+        let side = 300;
+        let serial_n = 42;
+        let pls_grid = init_grid(side, serial_n);
+        let squares_grid = compute_sub_squares(&pls_grid);
+        assert_eq!(get_max_square(&squares_grid), (30, Point::new(21, 61)));
+    }
+
+    #[test]
+    fn test_compute_sub_squares_from_prev() {
+        let grid = init_grid(3, 0);
+        let two_grid = compute_sub_squares_from_prev(&grid, &grid);
+        let three_grid = compute_sub_squares_from_prev(&grid, &two_grid);
+
+        assert_eq!(by_column(&three_grid), vec![-23]);
+
+        let grid = init_grid(4, 0);
+        let two_grid = compute_sub_squares_from_prev(&grid, &grid);
+        let three_grid = compute_sub_squares_from_prev(&grid, &two_grid);
+
+        assert_eq!(by_column(&three_grid), vec![-23, -11, -20, -5]);
+    }
+
+    #[test]
+    fn test_compute_sub_squares_from_prev_18() {
+        let side = 300;
+        let serial_n = 18;
+        let grid = init_grid(side, serial_n);
+
+        let two_grid = compute_sub_squares_from_prev(&grid, &grid);
+        let three_grid = compute_sub_squares_from_prev(&grid, &two_grid);
+
+        assert_eq!(get_max_square(&three_grid), (29, Point::new(33, 45)));
+    }
+
+    #[test]
+    fn test_compute_sub_squares_from_prev_42() {
+        let side = 300;
+        let serial_n = 42;
+        let grid = init_grid(side, serial_n);
+
+        let two_grid = compute_sub_squares_from_prev(&grid, &grid);
+        let three_grid = compute_sub_squares_from_prev(&grid, &two_grid);
+
+        assert_eq!(get_max_square(&three_grid), (30, Point::new(21, 61)));
+    }
+
+    #[test]
+    fn test_compute_squares_of_size() {
+        let grid = init_grid(300, 18);
+        assert_eq!(
+            compute_squares_of_size(&grid, 3),
+            compute_sub_squares(&grid)
+        );
+        assert_eq!(compute_squares_of_size(&grid, 1), grid);
+        assert_eq!(compute_squares_of_size(&grid, 300).values().len(), 1);
+    }
+
+    #[test]
+    fn test_get_max_square_with_size() {
+        let grid = init_grid(3, 0);
+        let (_, _, size) = get_max_square_with_size(&grid);
+
+        assert_eq!(size, 2);
+    }
+
+    #[test]
+    fn test_solution() {
+        let mut day = Day11::default();
+        let serial_n = day.parse("18\n").unwrap();
+
+        let square = day.part1(&serial_n);
+        assert_eq!(
+            square,
+            Square {
+                coord: Point::new(33, 45),
+                total: 29
+            }
+        );
+        assert_eq!(square.to_string(), "(33, 45) with a total of 29");
+
+        let err = day.parse("18 42\n").unwrap_err();
+        assert_eq!((err.line(), err.column()), (Some(1), Some(3)));
+
+        let flag = |flag: &str, value: &str| vec![(flag.to_string(), value.to_string())];
+        set_flags(&mut day, &flag("side", "10")).unwrap();
+        assert_eq!(
+            day.part1(&serial_n),
+            Square {
+                coord: Point::new(1, 3),
+                total: 12
+            }
+        );
+        assert!(set_flags(&mut day, &flag("square", "11")).is_err());
+        assert!(set_flags(&mut day, &flag("side", "1")).is_err());
+        assert!(set_flags(&mut day, &flag("square", "0")).is_err());
+        assert_eq!(
+            day.set_flag("size", "5"),
+            Err("Unknown flag --size".to_string())
+        );
+        assert_eq!(day.side, 10);
+    }
+
+    #[test]
+    fn test_answer_fields() {
+        let square = SizedSquare {
+            coord: Point::new(90, 269),
+            size: 16,
+            total: 113,
+        };
+        assert_eq!(
+            square.to_string(),
+            "(90, 269) of size 16 with a total of 113"
+        );
+        assert_eq!(
+            square.fields(),
+            vec![
+                ("x", Json::from(90)),
+                ("y", Json::from(269)),
+                ("size", Json::from(16)),
+                ("total", Json::from(113)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_power_levels() {
+        let grid = init_grid(300, 18);
+        let image = render_power_levels(&grid, 3);
+        assert_eq!((image.width(), image.height()), (600, 600));
+
+        // the square at 33,45 is outlined from 32,44 to 36,48, the fuel
+        // cells start from 1,1
+        assert_eq!(image.get(62, 86), Some(Rgb::WHITE));
+        assert_eq!(image.get(71, 95), Some(Rgb::WHITE));
+        assert_ne!(image.get(72, 96), Some(Rgb::WHITE));
+        // 33,45 has a power level of 4
+        assert_eq!(image.get(64, 88), Some(Rgb::heat(1.0)));
+    }
+
+    #[test]
+    fn test_generate() {
+        let day = Day11 {
+            side: 20,
+            square: 3,
+        };
+        for seed in 0..10 {
+            let serial_n = day.parse(&day.generate(&mut Rng::new(seed), 100)).unwrap();
+            assert!((1..=9999).contains(&serial_n));
+            assert!(day.part2(&serial_n).total >= day.part1(&serial_n).total);
+        }
+    }
+}
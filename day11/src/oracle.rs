@@ -0,0 +1,94 @@
+//! Sums of the squares cell by cell, the oracle of the differential tests
+//! of [`compute_sub_squares_from_prev`](crate::compute_sub_squares_from_prev)
+
+use common::{BoundingBox, Grid, Point};
+
+use crate::grid_bounds;
+
+/// Grid of the total power of the squares of side `size`, every square
+/// summed on its own
+pub fn squares_of_size(grid: &Grid<i32>, size: usize) -> Grid<i32> {
+    Grid::from_fn(grid_bounds(grid.width() + 1 - size), |coord| {
+        BoundingBox::with_size(coord, size, size)
+            .points()
+            .map(|cell| grid[cell])
+            .sum()
+    })
+}
+
+/// Largest total power of a square from 2 cells of side, with its
+/// coordinate and size, the largest coordinate and then size on ties
+pub fn max_square_with_size(grid: &Grid<i32>) -> (i32, Point, usize) {
+    (2..=grid.width())
+        .flat_map(|size| {
+            squares_of_size(grid, size)
+                .iter()
+                .map(move |(coord, &total)| (total, coord, size))
+                .collect::<Vec<_>>()
+        })
+        .max()
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{max_square_with_size, squares_of_size};
+    use crate::{compute_squares_of_size, get_max_square_with_size, grid_bounds, init_grid};
+    use common::{differential, Grid, Point, Rng};
+
+    // the grid of a random serial number, or of random power levels
+    fn generate(rng: &mut Rng) -> Grid<i32> {
+        let side = rng.range(2..=16) as usize;
+        match rng.chance(0.5) {
+            true => init_grid(side, rng.range(1..=9999) as i32),
+            false => Grid::from_fn(grid_bounds(side), |_| rng.range(-5..=4) as i32),
+        }
+    }
+
+    // the grid without its last row and column or without its first ones
+    fn shrink(grid: &Grid<i32>) -> Vec<Grid<i32>> {
+        let side = grid.width() - 1;
+        if side < 2 {
+            return Vec::new();
+        }
+        let offsets = [(0, 0), (1, 1), (1, 0), (0, 1)];
+        offsets
+            .iter()
+            .map(|&(dx, dy)| Grid::from_fn(grid_bounds(side), |p| grid[p + Point::new(dx, dy)]))
+            .collect()
+    }
+
+    #[test]
+    fn test_squares_of_size() {
+        let grid = init_grid(300, 18);
+        assert_eq!(squares_of_size(&grid, 3)[Point::new(33, 45)], 29);
+
+        let sizes = |grid: &Grid<i32>| -> Vec<Grid<i32>> {
+            (1..=grid.width())
+                .map(|size| squares_of_size(grid, size))
+                .collect()
+        };
+        let from_prev = |grid: &Grid<i32>| -> Vec<Grid<i32>> {
+            (1..=grid.width())
+                .map(|size| compute_squares_of_size(grid, size))
+                .collect()
+        };
+        if let Err(mismatch) = differential(200, generate, shrink, sizes, from_prev) {
+            panic!("{}", mismatch);
+        }
+    }
+
+    #[test]
+    fn test_max_square_with_size() {
+        let result = differential(
+            200,
+            generate,
+            shrink,
+            max_square_with_size,
+            get_max_square_with_size,
+        );
+        if let Err(mismatch) = result {
+            panic!("{}", mismatch);
+        }
+    }
+}
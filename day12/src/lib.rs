@@ -0,0 +1,918 @@
+//! Day 12: Subterranean Sustainability
+
+use common::{
+    flag_value, BoundingBox, Flag, Grid, Image, ParseError, Point, Rgb, Rng, Scanner, Simulation,
+    Solution,
+};
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+
+pub mod oracle;
+
+/// Row of pots, `true` when the pot contains a plant
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pots {
+    pub list: Vec<bool>,
+    /// index in `list` of the pot number 0
+    pub zero_pos: usize,
+}
+
+impl Pots {
+    /// Row starting from the pot number 0, panics if empty
+    pub fn new(pots: &[bool]) -> Pots {
+        if pots.is_empty() {
+            panic!("There must be at least one pot");
+        }
+        Pots {
+            list: Vec::from(pots),
+            zero_pos: 0,
+        }
+    }
+}
+
+// a pot, `#` with a plant or `.` without
+fn pot_tile(plant: bool) -> char {
+    if plant {
+        '#'
+    } else {
+        '.'
+    }
+}
+
+/// The pots from the number 0 to the end of the row printed like the
+/// initial state, `#..#.#`; the pots left of the number 0 are not printed
+impl Display for Pots {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let row: String = self.list[self.zero_pos..]
+            .iter()
+            .map(|&plant| pot_tile(plant))
+            .collect();
+        write!(f, "{}", row)
+    }
+}
+
+/// Ensure that there are at least 4 empty pots at the begin and at the end
+pub fn ensure_empty_pots(pots: Pots) -> Pots {
+    let at_least = 4;
+    let first_full = pots
+        .list
+        .iter()
+        .take(at_least)
+        .position(|p| *p)
+        .map(|p| at_least - p);
+    let last_full = pots
+        .list
+        .iter()
+        .rev()
+        .take(at_least)
+        .position(|p| *p)
+        .map(|p| (p as i32 - at_least as i32).unsigned_abs() as usize);
+
+    let to_add_at = |pos: Option<usize>| if let Some(p) = pos { 0..p } else { 0..0 }.map(|_| false);
+
+    let list = to_add_at(first_full)
+        .chain(pots.list)
+        .chain(to_add_at(last_full))
+        .collect::<Vec<_>>();
+
+    let zero_pos = pots.zero_pos + first_full.unwrap_or(0);
+
+    Pots { list, zero_pos }
+}
+
+/// A pot with the two pots on its left and on its right
+pub type Pattern = [bool; 5];
+/// Whether the pot in the middle of the pattern has a plant in the next
+/// generation
+pub type Patterns = HashMap<Pattern, bool>;
+
+/// A note printed like the puzzle input, `..#.. => #`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Note(pub Pattern, pub bool);
+
+impl Display for Note {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let pattern: String = self.0.iter().map(|&plant| pot_tile(plant)).collect();
+        write!(f, "{} => {}", pattern, pot_tile(self.1))
+    }
+}
+
+/// Parse a row of pots like `#..#.#`
+pub fn parse_pots(input: &str) -> Vec<bool> {
+    input.chars().map(|c| c == '#').collect()
+}
+
+// a pot, `#` with a plant or `.` without
+fn parse_pot(s: &mut Scanner) -> Result<bool, ParseError> {
+    s.char_if(|c| c == '#' || c == '.', "`#` or `.`")
+        .map(|c| c == '#')
+}
+
+/// Parse a note like `..#.. => #`
+pub fn parse_pattern(line: &str) -> Result<(Pattern, bool), ParseError> {
+    let mut s = Scanner::new(line);
+    let mut pattern = [false; 5];
+    for pot in pattern.iter_mut() {
+        *pot = parse_pot(&mut s)?;
+    }
+    s.literal(" => ")?;
+    let status = parse_pot(&mut s)?;
+    s.end()?;
+    Ok((pattern, status))
+}
+
+/// Parse one note per line, the lines come with their index in the input
+pub fn parse_patterns<'a>(
+    lines: impl Iterator<Item = (usize, &'a str)>,
+) -> Result<Patterns, ParseError> {
+    lines
+        .map(|(idx, line)| parse_pattern(line).map_err(|err| err.on_line(idx, line)))
+        .collect()
+}
+
+/// Next generation of the pots
+pub fn next_state(patterns: &Patterns, mut pots: Pots) -> Pots {
+    pots.list = (0..2)
+        .map(|_| false)
+        .chain(
+            pots.list
+                .windows(5)
+                .map(|pattern| *patterns.get(pattern).unwrap_or(&false)),
+        )
+        .collect::<Vec<_>>();
+    ensure_empty_pots(pots)
+}
+
+/// Sum of the numbers of the pots with a plant, every number shifted by
+/// `base`
+pub fn sum_pots_position(pots: &Pots, base: i64) -> i64 {
+    pots.list
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| **p)
+        .map(|(pos, _)| base + pos as i64 - pots.zero_pos as i64)
+        .sum()
+}
+
+/// The generations one after the other, starting with `pots`
+pub fn evolve(pots: Pots, patterns: &Patterns) -> impl Iterator<Item = Pots> + '_ {
+    std::iter::successors(Some(pots), move |pots| {
+        Some(next_state(patterns, pots.clone()))
+    })
+}
+
+/// Generation after `turns` generations
+pub fn play_game(pots: Pots, patterns: &Patterns, turns: usize) -> Pots {
+    evolve(pots, patterns).nth(turns).unwrap()
+}
+
+/// Parse the initial state and the notes
+pub fn parse_input(input: &str) -> Result<(Pots, Patterns), ParseError> {
+    let parse_state = |s: &mut Scanner| -> Result<Vec<bool>, ParseError> {
+        s.literal("initial state: ")?;
+        let mut pots = vec![parse_pot(s)?];
+        while !s.rest().trim_end().is_empty() {
+            pots.push(parse_pot(s)?);
+        }
+        Ok(pots)
+    };
+
+    let mut lines = input.lines().enumerate();
+    let (idx, line) = lines.next().ok_or_else(|| ParseError::new("empty input"))?;
+    let pots = parse_state(&mut Scanner::new(line)).map_err(|err| err.on_line(idx, line))?;
+
+    if let Some((idx, line)) = lines.next().filter(|(_, line)| !line.trim().is_empty()) {
+        let err = ParseError::at(0, line.len(), "expected an empty line");
+        return Err(err.on_line(idx, line));
+    }
+
+    let patterns = parse_patterns(lines)?;
+    Ok((ensure_empty_pots(Pots::new(&pots)), patterns))
+}
+
+/// Advance until the plants keep the same pattern in the next generation,
+/// or for `limit` generations at most, returning the generations played,
+/// how many pots the plants move to the right on every generation and the
+/// last generation. A row where all the plants died is settled, nothing
+/// moving any more
+pub fn find_convergence(mut pots: Pots, patterns: &Patterns, limit: usize) -> (usize, i64, Pots) {
+    let reduce = |list: &Vec<bool>| {
+        list.iter()
+            .skip_while(|v| !**v)
+            .cloned()
+            .collect::<Vec<bool>>()
+    };
+    // number of the first pot with a plant, the row grows on the left as
+    // the plants move there so its index in the list is not enough
+    let first_plant = |pots: &Pots| {
+        let index = pots.list.iter().position(|p| *p)?;
+        Some(index as i64 - pots.zero_pos as i64)
+    };
+    let mut turns: usize = 0;
+    let mut prev_list = reduce(&pots.list);
+    let mut prev_first_plant = match first_plant(&pots) {
+        Some(first_plant) => first_plant,
+        None => return (turns, 0, pots),
+    };
+
+    while turns < limit {
+        turns += 1;
+        pots = next_state(patterns, pots);
+        let first_plant = match first_plant(&pots) {
+            Some(first_plant) => first_plant,
+            None => return (turns, 0, pots),
+        };
+        if prev_list == reduce(&pots.list) {
+            return (turns, first_plant - prev_first_plant, pots);
+        }
+
+        prev_list = reduce(&pots.list);
+        prev_first_plant = first_plant;
+    }
+    (turns, 0, pots)
+}
+
+/// Whether [`find_convergence`] settles within `limit` generations, all
+/// the plants dying included
+pub fn settles(pots: &Pots, patterns: &Patterns, limit: usize) -> bool {
+    // the pots from the first plant, as compared by `find_convergence`
+    let from_first = |pots: &Pots| -> Option<Vec<bool>> {
+        let first = pots.list.iter().position(|p| *p)?;
+        Some(pots.list[first..].to_vec())
+    };
+    let mut prev = match from_first(pots) {
+        Some(prev) => prev,
+        None => return true,
+    };
+    for pots in evolve(pots.clone(), patterns).skip(1).take(limit) {
+        match from_first(&pots) {
+            Some(list) if list == prev => return true,
+            Some(list) => prev = list,
+            None => return true,
+        }
+    }
+    false
+}
+
+/// Random initial state of `count` pots and notes for all the patterns,
+/// with plants settling down within a couple of hundred generations
+pub fn generate_notes(rng: &mut Rng, count: usize) -> (Vec<bool>, Vec<(Pattern, bool)>) {
+    loop {
+        let pots: Vec<bool> = (0..count.max(1)).map(|_| rng.chance(0.5)).collect();
+        // no plant growing out of nowhere, they would fill the row, and
+        // few plants growing at all as crowded rows rarely settle
+        let mut notes: Vec<(Pattern, bool)> = (0..32)
+            .map(|n| {
+                let mut pattern = [false; 5];
+                for (i, pot) in pattern.iter_mut().enumerate() {
+                    *pot = n & (1 << (4 - i)) != 0;
+                }
+                (pattern, n != 0 && rng.chance(0.3))
+            })
+            .collect();
+        rng.shuffle(&mut notes);
+
+        let patterns: Patterns = notes.iter().cloned().collect();
+        if settles(&ensure_empty_pots(Pots::new(&pots)), &patterns, 200) {
+            return (pots, notes);
+        }
+    }
+}
+
+// the initial state followed by `turns` generations
+fn generations(pots: &Pots, patterns: &Patterns, turns: usize) -> Vec<Pots> {
+    evolve(pots.clone(), patterns).take(turns + 1).collect()
+}
+
+// a row for every generation with room for all of them, none for the rows
+// after the first `shown` ones
+fn plants_grid(generations: &[Pots], shown: usize) -> Grid<Option<bool>> {
+    // the first and the last pot numbers of every generation
+    let ends = generations.iter().enumerate().flat_map(|(y, pots)| {
+        let first = -(pots.zero_pos as i32);
+        let last = first + pots.list.len() as i32 - 1;
+        vec![Point::new(first, y as i32), Point::new(last, y as i32)]
+    });
+    let bounds = BoundingBox::from_points(ends).expect("There must be at least one pot");
+    Grid::from_fn(bounds, |p| {
+        let pots = &generations[p.y as usize];
+        let idx = p.x + pots.zero_pos as i32;
+        let plant = idx >= 0 && pots.list.get(idx as usize) == Some(&true);
+        Some(plant).filter(|_| (p.y as usize) < shown)
+    })
+}
+
+// green for a plant and brown for an empty pot, lighter for the pot number 0
+fn pot_colour(p: Point, plant: Option<bool>) -> Rgb {
+    match (plant, p.x) {
+        (None, _) => Rgb(20, 15, 10),
+        (Some(true), 0) => Rgb(120, 220, 120),
+        (Some(true), _) => Rgb(50, 160, 60),
+        (Some(false), 0) => Rgb(110, 85, 60),
+        (Some(false), _) => Rgb(60, 40, 30),
+    }
+}
+
+fn draw_generations(generations: &[Pots], shown: usize) -> Image {
+    let plants = plants_grid(generations, shown);
+    Image::from_grid(&plants, |p, &plant| pot_colour(p, plant)).scale(4)
+}
+
+/// Draw a row of pots for every generation from the initial state down to
+/// the generation `turns`, four pixels per pot: a plant is green and an
+/// empty pot brown, lighter for the pot number 0
+pub fn render_generations(pots: &Pots, patterns: &Patterns, turns: usize) -> Image {
+    let generations = generations(pots, patterns, turns);
+    draw_generations(&generations, generations.len())
+}
+
+/// The picture of [`render_generations`] growing one generation at a time
+pub struct GenerationsSimulation {
+    generations: Vec<Pots>,
+    shown: usize,
+}
+
+impl GenerationsSimulation {
+    pub fn new(pots: &Pots, patterns: &Patterns, turns: usize) -> GenerationsSimulation {
+        GenerationsSimulation {
+            generations: generations(pots, patterns, turns),
+            shown: 1,
+        }
+    }
+}
+
+impl Simulation for GenerationsSimulation {
+    fn step(&mut self) -> bool {
+        if self.shown == self.generations.len() {
+            return false;
+        }
+        self.shown += 1;
+        true
+    }
+
+    fn render(&self) -> Image {
+        draw_generations(&self.generations, self.shown)
+    }
+
+    fn text(&self) -> Grid<(char, Rgb)> {
+        let plants = plants_grid(&self.generations, self.shown);
+        plants.map(|p, &plant| {
+            let c = match plant {
+                Some(true) => '#',
+                Some(false) => '.',
+                None => ' ',
+            };
+            // the empty pots lighter than in the pictures to be seen on a
+            // dark terminal
+            let colour = match plant {
+                Some(false) => pot_colour(p, plant).mix(Rgb::WHITE, 0.3),
+                _ => pot_colour(p, plant),
+            };
+            (c, colour)
+        })
+    }
+}
+
+/// Both parts sum the numbers of the pots with a plant, part 1 after 20
+/// generations and part 2 after fifty billion unless told otherwise
+pub struct Day12 {
+    pub generations: usize,
+    /// Generations of part 2, too many to be simulated one by one
+    pub long_generations: usize,
+}
+
+impl Default for Day12 {
+    fn default() -> Day12 {
+        Day12 {
+            generations: 20,
+            long_generations: 50_000_000_000,
+        }
+    }
+}
+
+impl Solution for Day12 {
+    const DAY: u8 = 12;
+    const README: &'static str = include_str!("../README.md");
+
+    type Input = (Pots, Patterns);
+    type Part1 = i64;
+    type Part2 = i64;
+
+    const FLAGS: &'static [Flag] = &[
+        Flag {
+            name: "generations",
+            value: "N",
+            help: "generations of part 1 (20)",
+        },
+        Flag {
+            name: "long-generations",
+            value: "N",
+            help: "generations of part 2 (50000000000)",
+        },
+    ];
+
+    fn set_flag(&mut self, flag: &str, value: &str) -> Result<(), String> {
+        match flag {
+            "generations" => self.generations = flag_value(flag, value)?,
+            "long-generations" => self.long_generations = flag_value(flag, value)?,
+            _ => return Err(format!("Unknown flag --{}", flag)),
+        }
+        Ok(())
+    }
+
+    fn parse(&self, input: &str) -> Result<(Pots, Patterns), ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(&self, (pots, patterns): &(Pots, Patterns)) -> i64 {
+        let pots = play_game(pots.clone(), patterns, self.generations);
+        sum_pots_position(&pots, 0)
+    }
+
+    fn part2(&self, (pots, patterns): &(Pots, Patterns)) -> i64 {
+        // the plants settle down after the first generations, the pattern is
+        // then only moved along the row; fewer generations are all played
+        let (turns, shift, converged) =
+            find_convergence(pots.clone(), patterns, self.long_generations);
+        let remaining_turns = (self.long_generations - turns) as i64;
+        sum_pots_position(&converged, remaining_turns * shift)
+    }
+
+    fn render(&self, (pots, patterns): &(Pots, Patterns)) -> Option<Image> {
+        Some(render_generations(pots, patterns, self.generations))
+    }
+
+    fn simulate(&self, (pots, patterns): &(Pots, Patterns)) -> Option<Box<dyn Simulation>> {
+        let simulation = GenerationsSimulation::new(pots, patterns, self.generations);
+        Some(Box::new(simulation))
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        let (pots, notes) = generate_notes(rng, size);
+        let mut input = format!("initial state: {}\n\n", Pots::new(&pots));
+        for (pattern, plant) in notes {
+            input += &format!("{}\n", Note(pattern, plant));
+        }
+        input
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ensure_empty_pots, find_convergence, generate_notes, next_state, parse_input,
+        parse_pattern, parse_patterns, parse_pots, play_game, render_generations, settles,
+        sum_pots_position, Day12, GenerationsSimulation, Note, Patterns, Pots,
+    };
+    use common::{Point, Rgb, Rng, Simulation, Solution};
+
+    const T: bool = true;
+    const F: bool = false;
+
+    #[test]
+    fn test_ensure_empty_posts_one_true() {
+        let pots = Pots::new(&[T]);
+        let pots = ensure_empty_pots(pots);
+
+        assert_eq!(pots.list, vec![F, F, F, F, T, F, F, F, F]);
+        assert_eq!(pots.zero_pos, 4);
+
+        let pots = Pots::new(&[F, T]);
+        let pots = ensure_empty_pots(pots);
+
+        assert_eq!(pots.list, vec![F, F, F, F, T, F, F, F, F]);
+        assert_eq!(pots.zero_pos, 3);
+
+        let pots = Pots::new(&[F, F, T]);
+        let pots = ensure_empty_pots(pots);
+
+        assert_eq!(pots.list, vec![F, F, F, F, T, F, F, F, F]);
+        assert_eq!(pots.zero_pos, 2);
+
+        let pots = Pots::new(&[F, F, F, T]);
+        let pots = ensure_empty_pots(pots);
+
+        assert_eq!(pots.list, vec![F, F, F, F, T, F, F, F, F]);
+        assert_eq!(pots.zero_pos, 1);
+
+        let pots = Pots::new(&[F, F, F, F, T]);
+        let pots = ensure_empty_pots(pots);
+
+        assert_eq!(pots.list, vec![F, F, F, F, T, F, F, F, F]);
+        assert_eq!(pots.zero_pos, 0);
+
+        let pots = Pots::new(&[F, F, F, F, F, T]);
+        let pots = ensure_empty_pots(pots);
+
+        assert_eq!(pots.list, vec![F, F, F, F, F, T, F, F, F, F]);
+        assert_eq!(pots.zero_pos, 0);
+
+        let pots = Pots::new(&[T, F]);
+        let pots = ensure_empty_pots(pots);
+
+        assert_eq!(pots.list, vec![F, F, F, F, T, F, F, F, F]);
+        assert_eq!(pots.zero_pos, 4);
+
+        let pots = Pots::new(&[T, F, F]);
+        let pots = ensure_empty_pots(pots);
+
+        assert_eq!(pots.list, vec![F, F, F, F, T, F, F, F, F]);
+        assert_eq!(pots.zero_pos, 4);
+
+        let pots = Pots::new(&[T, F, F, F, F]);
+        let pots = ensure_empty_pots(pots);
+
+        assert_eq!(pots.list, vec![F, F, F, F, T, F, F, F, F]);
+        assert_eq!(pots.zero_pos, 4);
+    }
+
+    #[test]
+    fn test_parse_pattern() {
+        let line = "#.### => .";
+        assert_eq!(parse_pattern(line), Ok(([T, F, T, T, T], F)));
+
+        let line = "#.### => #";
+        assert_eq!(parse_pattern(line), Ok(([T, F, T, T, T], T)));
+
+        let line = "..#.. => #";
+        assert_eq!(parse_pattern(line), Ok(([F, F, T, F, F], T)));
+
+        let line = "..#. => #";
+        let err = parse_pattern(line).unwrap_err().on_line(0, line);
+        assert_eq!(
+            (err.column(), err.message()),
+            (Some(5), "expected `#` or `.`")
+        );
+    }
+
+    #[test]
+    fn test_parse_input_errors() {
+        let err = parse_input("initial state: #..#.x\n\n").unwrap_err();
+        assert_eq!((err.line(), err.column()), (Some(1), Some(21)));
+
+        let err = parse_input("initial state: #..#\n\n...## => #\n..#.. =>").unwrap_err();
+        assert_eq!((err.line(), err.column()), (Some(4), Some(9)));
+
+        assert!(parse_input("initial state: \n").is_err());
+        assert!(parse_input("initial state: #\n...## => #\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_patterns() {
+        let input = "#.### => .\n\
+                     #.#.# => #\n\
+                     ..#.. => #";
+        let patterns = parse_patterns(input.lines().enumerate()).unwrap();
+
+        assert!(patterns.contains_key(&[T, F, T, T, T]));
+        assert_eq!(*patterns.get(&[T, F, T, T, T]).unwrap(), F);
+
+        assert!(patterns.contains_key(&[T, F, T, F, T]));
+        assert_eq!(*patterns.get(&[T, F, T, F, T]).unwrap(), T);
+
+        assert!(patterns.contains_key(&[F, F, T, F, F]));
+        assert_eq!(*patterns.get(&[F, F, T, F, F]).unwrap(), T);
+    }
+
+    #[test]
+    fn test_next_state() {
+        let input = "...## => #\n\
+                     ..#.. => #\n\
+                     .#... => #\n\
+                     .#.#. => #\n\
+                     .#.## => #\n\
+                     .##.. => #\n\
+                     .#### => #\n\
+                     #.#.# => #\n\
+                     #.### => #\n\
+                     ##.#. => #\n\
+                     ##.## => #\n\
+                     ###.. => #\n\
+                     ###.# => #\n\
+                     ####. => #";
+        let patterns = parse_patterns(input.lines().enumerate()).unwrap();
+        let pots = parse_pots("#..#.#..##");
+        let pots = ensure_empty_pots(Pots::new(&pots));
+        let next = next_state(&patterns, pots);
+        assert_eq!(next.list, parse_pots("....#...#....#...."));
+        assert_eq!(next.zero_pos, 4);
+        let next = next_state(&patterns, next);
+        assert_eq!(next.list, parse_pots("....##..##...##...."));
+        assert_eq!(next.zero_pos, 4);
+        let next = next_state(&patterns, next);
+        assert_eq!(next.list, parse_pots("....#.#...#..#.#...."));
+        assert_eq!(next.zero_pos, 5);
+    }
+
+    #[test]
+    fn test_next_state_example() {
+        let input = "...## => #\n\
+                     ..#.. => #\n\
+                     .#... => #\n\
+                     .#.#. => #\n\
+                     .#.## => #\n\
+                     .##.. => #\n\
+                     .#### => #\n\
+                     #.#.# => #\n\
+                     #.### => #\n\
+                     ##.#. => #\n\
+                     ##.## => #\n\
+                     ###.. => #\n\
+                     ###.# => #\n\
+                     ####. => #";
+        let tests = vec![
+            ("....#...#....#.....#..#..#..#....", 4),
+            ("....##..##...##....#..#..#..##....", 4),
+            ("....#.#...#..#.#....#..#..#...#....", 5),
+            (".....#.#..#...#.#...#..#..##..##....", 5),
+            ("......#...##...#.#..#..#...#...#....", 5),
+            ("......##.#.#....#...#..##..##..##....", 5),
+            (".....#..###.#...##..#...#...#...#....", 5),
+            (".....#....##.#.#.#..##..##..##..##....", 5),
+            (".....##..#..#####....#...#...#...#....", 5),
+            ("....#.#..#...#.##....##..##..##..##....", 5),
+            (".....#...##...#.#...#.#...#...#...#....", 5),
+            (".....##.#.#....#.#...#.#..##..##..##....", 5),
+            ("....#..###.#....#.#...#....#...#...#....", 5),
+            ("....#....##.#....#.#..##...##..##..##....", 5),
+            ("....##..#..#.#....#....#..#.#...#...#....", 5),
+            ("....#.#..#...#.#...##...#...#.#..##..##....", 6),
+            (".....#...##...#.#.#.#...##...#....#...#....", 6),
+            (".....##.#.#....#####.#.#.#...##...##..##....", 6),
+            ("....#..###.#..#.#.#######.#.#.#..#.#...#....", 6),
+            ("....#....##....#####...#######....#.#..##....", 6),
+        ];
+
+        let patterns = parse_patterns(input.lines().enumerate()).unwrap();
+        let pots = parse_pots("#..#.#..##......###...###");
+        let pots = ensure_empty_pots(Pots::new(&pots));
+
+        tests.iter().fold(pots, |pots, test| {
+            let next = next_state(&patterns, pots);
+            assert_eq!(next.zero_pos, test.1);
+            assert_eq!(next.list, parse_pots(test.0));
+            next
+        });
+    }
+
+    #[test]
+    fn test_sum_pots_position() {
+        let pots = parse_pots("#.#.#");
+        let pots = ensure_empty_pots(Pots::new(&pots));
+        assert_eq!(sum_pots_position(&pots, 0), 6);
+        let pots = parse_pots(".#.#.#");
+        let pots = ensure_empty_pots(Pots::new(&pots));
+        assert_eq!(sum_pots_position(&pots, 0), 9);
+    }
+
+    #[test]
+    fn test_sum_pots_position_example() {
+        let input = "...## => #\n\
+                     ..#.. => #\n\
+                     .#... => #\n\
+                     .#.#. => #\n\
+                     .#.## => #\n\
+                     .##.. => #\n\
+                     .#### => #\n\
+                     #.#.# => #\n\
+                     #.### => #\n\
+                     ##.#. => #\n\
+                     ##.## => #\n\
+                     ###.. => #\n\
+                     ###.# => #\n\
+                     ####. => #";
+        let patterns = parse_patterns(input.lines().enumerate()).unwrap();
+        let pots = parse_pots("#..#.#..##......###...###");
+        let pots = ensure_empty_pots(Pots::new(&pots));
+
+        let pots = (0..20).fold(pots, |pots, _| next_state(&patterns, pots));
+
+        assert_eq!(sum_pots_position(&pots, 0), 325);
+    }
+
+    #[test]
+    fn test_play_game() {
+        let input = "initial state: #..#.#..##......###...###\n\
+                     \n\
+                     ...## => #\n\
+                     ..#.. => #\n\
+                     .#... => #\n\
+                     .#.#. => #\n\
+                     .#.## => #\n\
+                     .##.. => #\n\
+                     .#### => #\n\
+                     #.#.# => #\n\
+                     #.### => #\n\
+                     ##.#. => #\n\
+                     ##.## => #\n\
+                     ###.. => #\n\
+                     ###.# => #\n\
+                     ####. => #";
+
+        let test_pots = parse_pots("#..#.#..##......###...###");
+        let test_pots = ensure_empty_pots(Pots::new(&test_pots));
+
+        let pattern_input = "...## => #\n\
+                             ..#.. => #\n\
+                             .#... => #\n\
+                             .#.#. => #\n\
+                             .#.## => #\n\
+                             .##.. => #\n\
+                             .#### => #\n\
+                             #.#.# => #\n\
+                             #.### => #\n\
+                             ##.#. => #\n\
+                             ##.## => #\n\
+                             ###.. => #\n\
+                             ###.# => #\n\
+                             ####. => #";
+        let test_patterns = parse_patterns(pattern_input.lines().enumerate()).unwrap();
+
+        let (pots, patterns) = parse_input(input).unwrap();
+        assert_eq!(pots.list, test_pots.list);
+        assert_eq!(patterns, test_patterns);
+
+        let pots = play_game(pots, &patterns, 20);
+        assert_eq!(sum_pots_position(&pots, 0), 325);
+    }
+
+    #[test]
+    fn test_solution() {
+        let input = "initial state: #..#.#..##......###...###\n\
+                     \n\
+                     ...## => #\n\
+                     ..#.. => #\n\
+                     .#... => #\n\
+                     .#.#. => #\n\
+                     .#.## => #\n\
+                     .##.. => #\n\
+                     .#### => #\n\
+                     #.#.# => #\n\
+                     #.### => #\n\
+                     ##.#. => #\n\
+                     ##.## => #\n\
+                     ###.. => #\n\
+                     ###.# => #\n\
+                     ####. => #";
+        let day = Day12::default();
+        let pots = day.parse(input).unwrap();
+
+        assert_eq!(day.part1(&pots), 325);
+
+        // part 2 agrees with the simulation before and after the plants
+        // settle down
+        for &generations in &[20, 200] {
+            let day = Day12 {
+                generations,
+                long_generations: generations,
+            };
+            assert_eq!(day.part2(&pots), day.part1(&pots));
+        }
+
+        let mut day = Day12::default();
+        assert_eq!(
+            day.set_flag("long", "5"),
+            Err("Unknown flag --long".to_string())
+        );
+        assert_eq!(day.long_generations, 50_000_000_000);
+    }
+
+    #[test]
+    fn test_render_generations() {
+        let input = "initial state: #..#.#..##......###...###\n\
+                     \n\
+                     ...## => #\n\
+                     ..#.. => #\n\
+                     .#... => #\n\
+                     .#.#. => #\n\
+                     .#.## => #\n\
+                     .##.. => #\n\
+                     .#### => #\n\
+                     #.#.# => #\n\
+                     #.### => #\n\
+                     ##.#. => #\n\
+                     ##.## => #\n\
+                     ###.. => #\n\
+                     ###.# => #\n\
+                     ####. => #";
+        let (pots, patterns) = parse_input(input).unwrap();
+        let image = render_generations(&pots, &patterns, 20);
+        // a row of the image for the pots from -6 to 38
+        let row = |y: usize| -> String {
+            (0..image.width() / 4)
+                .map(|x| match image.get(x * 4, y * 4) {
+                    Some(Rgb(50, 160, 60)) | Some(Rgb(120, 220, 120)) => '#',
+                    _ => '.',
+                })
+                .collect()
+        };
+
+        assert_eq!((image.width(), image.height()), (45 * 4, 21 * 4));
+        assert_eq!(row(0), "......#..#.#..##......###...###..............");
+        assert_eq!(row(20), "....#....##....#####...#######....#.#..##....");
+        assert_eq!(image.get(6 * 4, 0), Some(Rgb(120, 220, 120)));
+    }
+
+    #[test]
+    fn test_simulation() {
+        let input = "initial state: #..#.#..##......###...###\n\
+                     \n\
+                     ...## => #\n\
+                     ..#.. => #\n\
+                     .#... => #";
+        let (pots, patterns) = parse_input(input).unwrap();
+        let mut simulation = GenerationsSimulation::new(&pots, &patterns, 3);
+        assert_eq!(simulation.render().get(0, 4), Some(Rgb(20, 15, 10)));
+        assert!((0..3).all(|_| simulation.step()));
+        assert!(!simulation.step());
+        assert_eq!(simulation.render(), render_generations(&pots, &patterns, 3));
+
+        let text = simulation.text();
+        let row = |y: i32| -> String {
+            let bounds = text.bounds();
+            (bounds.min.x..=bounds.max.x)
+                .map(|x| text[Point::new(x, y)].0)
+                .collect()
+        };
+        assert_eq!(row(0), ".....#..#.#..##......###...###....");
+        assert_eq!(text[Point::new(0, 0)].1, Rgb(120, 220, 120));
+    }
+
+    #[test]
+    fn test_generate() {
+        for seed in 0..5 {
+            let mut rng = Rng::new(seed);
+            let (pots, notes) = generate_notes(&mut rng, 100);
+            assert_eq!((pots.len(), notes.len()), (100, 32));
+            let pots = ensure_empty_pots(Pots::new(&pots));
+            let patterns = notes.into_iter().collect();
+            assert!(settles(&pots, &patterns, 200));
+            let (turns, _, _) = find_convergence(pots, &patterns, usize::MAX);
+            assert!(turns <= 200);
+
+            let day = Day12::default();
+            let input = day.parse(&day.generate(&mut rng, 10)).unwrap();
+            day.part1(&input);
+            day.part2(&input);
+        }
+
+        let (pots, patterns) = parse_input("initial state: #\n\n..#.. => .").unwrap();
+        assert!(settles(&pots, &patterns, 200));
+        let (pots, patterns) = parse_input("initial state: #\n\n..#.. => #").unwrap();
+        assert!(settles(&pots, &patterns, 200));
+    }
+
+    #[test]
+    fn test_plants_dying() {
+        let (pots, patterns) = parse_input("initial state: #.#\n\n..#.. => .").unwrap();
+        let (turns, shift, converged) = find_convergence(pots.clone(), &patterns, usize::MAX);
+        assert_eq!((turns, shift), (1, 0));
+        assert_eq!(sum_pots_position(&converged, 0), 0);
+
+        let mut day = Day12::default();
+        let input = (pots, patterns);
+        assert_eq!(day.part2(&input), 0);
+        day.long_generations = 0;
+        assert_eq!(day.part2(&input), 2);
+    }
+
+    #[test]
+    fn test_few_long_generations() {
+        let mut day = Day12::default();
+        let input = day.parse(&day.generate(&mut Rng::new(0), 20)).unwrap();
+        for generations in 0..30 {
+            day.long_generations = generations;
+            day.generations = generations;
+            assert_eq!(day.part2(&input), day.part1(&input), "{}", generations);
+        }
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let mut rng = Rng::new(0);
+        for _ in 0..200 {
+            let count = rng.range(1..=100) as usize;
+            let pots: Vec<bool> = (0..count).map(|_| rng.chance(0.5)).collect();
+            let pots = ensure_empty_pots(Pots::new(&pots));
+            let patterns: Patterns = (0..rng.range(0..=32))
+                .map(|_| {
+                    let mut pattern = [false; 5];
+                    pattern.iter_mut().for_each(|pot| *pot = rng.chance(0.5));
+                    (pattern, rng.chance(0.5))
+                })
+                .collect();
+
+            let mut input = format!("initial state: {}\n\n", pots);
+            for (&pattern, &plant) in &patterns {
+                input += &format!("{}\n", Note(pattern, plant));
+            }
+            assert_eq!(parse_input(&input), Ok((pots, patterns)));
+        }
+        let pots = ensure_empty_pots(Pots::new(&parse_pots("#..#.#")));
+        assert_eq!(pots.to_string(), "#..#.#....");
+        assert_eq!(
+            Note([false, false, true, false, true], true).to_string(),
+            "..#.# => #"
+        );
+    }
+}
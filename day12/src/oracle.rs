@@ -0,0 +1,133 @@
+//! Generations simulated one by one on the numbers of the pots with a
+//! plant, the oracle of the differential tests of the translation
+//! shortcut of [`find_convergence`](crate::find_convergence)
+
+use std::collections::BTreeSet;
+
+use crate::Patterns;
+
+/// Sum of the numbers of the pots with a plant after `generations`
+/// generations, the initial state starting from the pot number 0
+pub fn sum_after(pots: &[bool], patterns: &Patterns, generations: usize) -> i64 {
+    let mut plants: BTreeSet<i64> = (0..pots.len() as i64)
+        .filter(|&n| pots[n as usize])
+        .collect();
+    for _ in 0..generations {
+        let (first, last) = match (plants.iter().next(), plants.iter().next_back()) {
+            (Some(&first), Some(&last)) => (first, last),
+            _ => break,
+        };
+        plants = (first - 2..=last + 2)
+            .filter(|&n| {
+                let mut pattern = [false; 5];
+                for (i, pot) in pattern.iter_mut().enumerate() {
+                    *pot = plants.contains(&(n + i as i64 - 2));
+                }
+                patterns.get(&pattern) == Some(&true)
+            })
+            .collect();
+    }
+    plants.iter().sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::sum_after;
+    use crate::{parse_input, settles, Day12, Pots};
+    use common::{differential, shrink_vec, Rng, Solution};
+
+    // a puzzle input of a few pots, readable in the report of a mismatch
+    #[derive(Debug, Clone)]
+    struct Case {
+        pots: String,
+        notes: Vec<String>,
+        generations: usize,
+    }
+
+    impl Case {
+        fn input(&self) -> String {
+            format!("initial state: {}\n\n{}", self.pots, self.notes.join("\n"))
+        }
+
+        // the solver settles down on the input
+        fn is_valid(&self) -> bool {
+            let (pots, patterns) = parse_input(&self.input()).unwrap();
+            settles(&pots, &patterns, 200)
+        }
+    }
+
+    fn generate(rng: &mut Rng) -> Case {
+        let count = rng.range(1..=30) as usize;
+        let day = Day12::default();
+        let input = day.generate(rng, count);
+        let mut lines = input.lines();
+        let pots = lines.next().unwrap()["initial state: ".len()..].to_string();
+        Case {
+            pots,
+            notes: lines.skip(1).map(|note| note.to_string()).collect(),
+            generations: rng.range(0..=150) as usize,
+        }
+    }
+
+    // fewer pots, fewer notes growing plants or fewer generations
+    fn shrink(case: &Case) -> Vec<Case> {
+        let pots: Vec<char> = case.pots.chars().collect();
+        let fewer_pots = shrink_vec(&pots)
+            .into_iter()
+            .filter(|pots| !pots.is_empty())
+            .map(|pots| Case {
+                pots: pots.into_iter().collect(),
+                ..case.clone()
+            });
+        let fewer_notes = (0..case.notes.len())
+            .filter(|&n| case.notes[n].ends_with('#'))
+            .map(|n| {
+                let mut notes = case.notes.clone();
+                notes[n] = notes[n].replace("=> #", "=> .");
+                Case {
+                    notes,
+                    ..case.clone()
+                }
+            });
+        let fewer_generations =
+            IntoIterator::into_iter([case.generations / 2, case.generations.saturating_sub(1)])
+                .filter(|&generations| generations < case.generations)
+                .map(|generations| Case {
+                    generations,
+                    ..case.clone()
+                });
+        fewer_pots
+            .chain(fewer_notes)
+            .chain(fewer_generations)
+            .filter(Case::is_valid)
+            .collect()
+    }
+
+    #[test]
+    fn test_sum_after() {
+        let (pots, patterns) = parse_input(
+            "initial state: #..#.#..##......###...###\n\n\
+             ...## => #\n..#.. => #\n.#... => #\n.#.#. => #\n.#.## => #\n.##.. => #\n\
+             .#### => #\n#.#.# => #\n#.### => #\n##.#. => #\n##.## => #\n###.. => #\n\
+             ###.# => #\n####. => #",
+        )
+        .unwrap();
+        let Pots { list, zero_pos } = pots;
+        assert_eq!(sum_after(&list[zero_pos..], &patterns, 20), 325);
+
+        let oracle = |case: &Case| {
+            let (pots, patterns) = parse_input(&case.input()).unwrap();
+            sum_after(&pots.list[pots.zero_pos..], &patterns, case.generations)
+        };
+        let part2 = |case: &Case| {
+            let day = Day12 {
+                long_generations: case.generations,
+                ..Day12::default()
+            };
+            day.part2(&day.parse(&case.input()).unwrap())
+        };
+        if let Err(mismatch) = differential(100, generate, shrink, oracle, part2) {
+            panic!("{}", mismatch);
+        }
+    }
+}
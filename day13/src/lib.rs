@@ -0,0 +1,1166 @@
+//! Day 13: Mine Cart Madness
+
+use common::{
+    Answer, BoundingBox, Grid, Image, Json, ParseError, Point, Rgb, Rng, Simulation, Solution,
+};
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Display};
+
+/// Axe along which a cart moves
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub enum Axe {
+    X,
+    Y,
+}
+
+/// Direction Up means that the coordinate will increase
+/// while Down means that the coordinate will descrease
+/// `>` and `v` are Up
+/// `<` and `^` are Down
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub enum Direction {
+    Up,
+    Down,
+}
+
+/// Turn(Up) means that the direction is the same after the turn
+/// ```text
+/// >\ after the turn the direction is still Up
+///  v
+/// ```
+/// Turn(Down) means that the direction will flip
+/// ```text
+/// /< after the turn the direction change from Down to Up Up
+/// v
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub enum TrackPiece {
+    Straight(Axe),
+    Turn(Direction),
+    Intersection,
+}
+
+/// Pieces of track by column and row, empty tiles are `None`
+pub type Track = Grid<Option<TrackPiece>>;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// A cart on the track
+pub struct Cart {
+    pub coord: Point,
+    pub axe: Axe,
+    pub direction: Direction,
+    /// index in the left, straight, right cycle of the move at the next
+    /// intersection
+    pub next_intersection_move: usize,
+}
+
+enum IntersectionMove {
+    Straight,
+    TurnLeft,
+    TurnRight,
+}
+
+const INTERSECTION_MOVE_ORDER: [IntersectionMove; 3] = [
+    IntersectionMove::TurnLeft,
+    IntersectionMove::Straight,
+    IntersectionMove::TurnRight,
+];
+
+impl Cart {
+    /// Cart that will turn left at the first intersection
+    pub fn new(coord: Point, axe: Axe, direction: Direction) -> Cart {
+        Cart {
+            coord,
+            axe,
+            direction,
+            next_intersection_move: 0,
+        }
+    }
+
+    /// Coordinate of the tile in front of the cart
+    pub fn ahead(&self) -> Point {
+        let change = |n: i32| match self.direction {
+            Direction::Up => n + 1,
+            Direction::Down => n - 1,
+        };
+        let Point { x, y } = self.coord;
+        match self.axe {
+            Axe::X => Point::new(change(x), y),
+            Axe::Y => Point::new(x, change(y)),
+        }
+    }
+}
+
+/// Move the cart one tile, turning it on curves and intersections
+pub fn move_cart(track: &Track, cart: &mut Cart) {
+    let invert_axe = |axe: &Axe| match *axe {
+        Axe::X => Axe::Y,
+        Axe::Y => Axe::X,
+    };
+
+    cart.coord = cart.ahead();
+
+    let mut tile = track
+        .get(cart.coord)
+        .and_then(Option::as_ref)
+        .expect("Malformed track");
+    let intersection_tile: TrackPiece;
+
+    if let TrackPiece::Intersection = tile {
+        intersection_tile = match INTERSECTION_MOVE_ORDER[cart.next_intersection_move] {
+            // we dont care about the axe of the straight
+            IntersectionMove::Straight => TrackPiece::Straight(Axe::X),
+            IntersectionMove::TurnLeft => TrackPiece::Turn(match cart.axe {
+                Axe::X => Direction::Down,
+                Axe::Y => Direction::Up,
+            }),
+            IntersectionMove::TurnRight => TrackPiece::Turn(match cart.axe {
+                Axe::X => Direction::Up,
+                Axe::Y => Direction::Down,
+            }),
+        };
+        tile = &intersection_tile;
+
+        cart.next_intersection_move += 1;
+        cart.next_intersection_move %= 3;
+    };
+
+    let tile = tile;
+
+    if let TrackPiece::Turn(direction) = tile {
+        cart.axe = invert_axe(&cart.axe);
+
+        if let Direction::Down = direction {
+            cart.direction = match cart.direction {
+                Direction::Up => Direction::Down,
+                Direction::Down => Direction::Up,
+            }
+        }
+    }
+}
+
+impl TrackPiece {
+    /// Character of the piece on the map, `-`, `|`, `/`, `\\` or `+`
+    pub fn tile(&self) -> char {
+        match self {
+            TrackPiece::Straight(Axe::X) => '-',
+            TrackPiece::Straight(Axe::Y) => '|',
+            TrackPiece::Turn(Direction::Down) => '/',
+            TrackPiece::Turn(Direction::Up) => '\\',
+            TrackPiece::Intersection => '+',
+        }
+    }
+}
+
+impl Display for TrackPiece {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.tile())
+    }
+}
+
+impl Cart {
+    /// Character of the cart on the map, `>`, `<`, `v` or `^`
+    pub fn tile(&self) -> char {
+        match (&self.axe, &self.direction) {
+            (Axe::X, Direction::Up) => '>',
+            (Axe::X, Direction::Down) => '<',
+            (Axe::Y, Direction::Up) => 'v',
+            (Axe::Y, Direction::Down) => '^',
+        }
+    }
+}
+
+impl Display for Cart {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.tile())
+    }
+}
+
+/// The track with the carts on it printed like the puzzle input, from the
+/// column and row 0 and without trailing spaces; the carts must stand on
+/// straight pieces along their axe for the map to parse back the same
+#[derive(Debug, Clone, Copy)]
+pub struct Map<'a> {
+    pub track: &'a Track,
+    pub carts: &'a [Cart],
+}
+
+impl Display for Map<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let max = self.track.bounds().max;
+        let mut carts: HashMap<Point, &Cart> = HashMap::new();
+        for cart in self.carts {
+            carts.insert(cart.coord, cart);
+        }
+        for y in 0..=max.y {
+            let line: String = (0..=max.x)
+                .map(|x| {
+                    let coord = Point::new(x, y);
+                    match (carts.get(&coord), self.track.get(coord)) {
+                        (Some(cart), _) => cart.tile(),
+                        (None, Some(Some(piece))) => piece.tile(),
+                        (None, _) => ' ',
+                    }
+                })
+                .collect();
+            writeln!(f, "{}", line.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+/// Parse the map of the tracks with the carts on them
+pub fn parse_track(input: &str) -> Result<(Track, Vec<Cart>), ParseError> {
+    let mut pieces = Vec::new();
+    let mut carts = Vec::new();
+
+    for (y, line) in input.lines().enumerate() {
+        for (x, (offset, mut tile)) in line.char_indices().enumerate() {
+            let coord = Point::new(x as i32, y as i32);
+            match tile {
+                '>' => {
+                    carts.push(Cart::new(coord, Axe::X, Direction::Up));
+                    tile = '-';
+                }
+                '<' => {
+                    carts.push(Cart::new(coord, Axe::X, Direction::Down));
+                    tile = '-';
+                }
+                'v' => {
+                    carts.push(Cart::new(coord, Axe::Y, Direction::Up));
+                    tile = '|';
+                }
+                '^' => {
+                    carts.push(Cart::new(coord, Axe::Y, Direction::Down));
+                    tile = '|';
+                }
+                _ => (),
+            };
+
+            let piece = match tile {
+                ' ' => continue,
+                '-' => TrackPiece::Straight(Axe::X),
+                '|' => TrackPiece::Straight(Axe::Y),
+                '/' => TrackPiece::Turn(Direction::Down),
+                '\\' => TrackPiece::Turn(Direction::Up),
+                '+' => TrackPiece::Intersection,
+                _ => {
+                    let err = ParseError::at(offset, tile.len_utf8(), "unknown tile");
+                    return Err(err.on_line(y, line));
+                }
+            };
+            pieces.push((coord, piece));
+        }
+    }
+
+    let bounds = BoundingBox::from_points(pieces.iter().map(|(coord, _)| *coord))
+        .ok_or_else(|| ParseError::new("empty input"))?;
+    let mut track = Track::new(bounds, None);
+    for (coord, piece) in pieces {
+        track[coord] = Some(piece);
+    }
+    Ok((track, carts))
+}
+
+/// Parse the map and check that it has two carts or more, so that they can
+/// crash, and that none of them runs off the track whatever the crashes
+pub fn parse_map(input: &str) -> Result<(Track, Vec<Cart>), ParseError> {
+    let (track, carts) = parse_track(input)?;
+    if carts.len() < 2 {
+        return Err(ParseError::new("fewer than two carts on the track"));
+    }
+    for cart in &carts {
+        if let Some(coord) = find_derailment(&track, cart) {
+            let y = cart.coord.y as usize;
+            let line = input.lines().nth(y).unwrap_or_default();
+            let offset = line
+                .char_indices()
+                .nth(cart.coord.x as usize)
+                .map_or(0, |(offset, _)| offset);
+            let err = ParseError::at(offset, 1, format!("cart runs off the track at {}", coord));
+            return Err(err.on_line(y, line));
+        }
+    }
+    Ok((track, carts))
+}
+
+/// Where the cart, moving alone, leaves the track or enters a straight piece
+/// across it; `None` when it comes back to a position, direction and next
+/// intersection move it had before
+pub fn find_derailment(track: &Track, cart: &Cart) -> Option<Point> {
+    // a bit for every axe, direction and next intersection move by tile
+    let mut seen = track.map(|_, _| 0u16);
+    let mut cart = cart.clone();
+    loop {
+        let state = match (&cart.axe, &cart.direction) {
+            (Axe::X, Direction::Up) => 0,
+            (Axe::X, Direction::Down) => 1,
+            (Axe::Y, Direction::Up) => 2,
+            (Axe::Y, Direction::Down) => 3,
+        } * 3
+            + cart.next_intersection_move;
+        if seen[cart.coord] & 1 << state != 0 {
+            return None;
+        }
+        seen[cart.coord] |= 1 << state;
+
+        let ahead = cart.ahead();
+        match track.get(ahead).and_then(Option::as_ref) {
+            None => return Some(ahead),
+            Some(TrackPiece::Straight(axe)) if *axe != cart.axe => return Some(ahead),
+            Some(_) => move_cart(track, &mut cart),
+        }
+    }
+}
+
+/// Where a cart was at the end of a tick, ticks are counted from 1; printed
+/// as the coordinate alone
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Location {
+    pub coord: Point,
+    pub tick: usize,
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.coord)
+    }
+}
+
+impl Answer for Location {
+    fn fields(&self) -> Vec<(&'static str, Json)> {
+        vec![
+            ("x", self.coord.x.into()),
+            ("y", self.coord.y.into()),
+            ("tick", self.tick.into()),
+        ]
+    }
+}
+
+/// End of a simulation, the location sought or why there is none
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Outcome {
+    Found(Location),
+    /// The carts are back in the state of an earlier tick at the end of
+    /// `tick`, they go round forever
+    Loops {
+        tick: usize,
+    },
+    /// The last carts crashed into each other during `tick`
+    NoneLeft {
+        tick: usize,
+    },
+}
+
+impl Outcome {
+    pub fn location(&self) -> Option<Location> {
+        match self {
+            Outcome::Found(location) => Some(*location),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Outcome::Found(location) => write!(f, "{}", location),
+            Outcome::Loops { .. } => write!(f, "none, the carts go round forever"),
+            Outcome::NoneLeft { .. } => write!(f, "none, every cart crashed"),
+        }
+    }
+}
+
+impl Answer for Outcome {
+    fn fields(&self) -> Vec<(&'static str, Json)> {
+        match self {
+            Outcome::Found(location) => location.fields(),
+            Outcome::Loops { tick } | Outcome::NoneLeft { tick } => {
+                vec![("tick", (*tick).into())]
+            }
+        }
+    }
+}
+
+// whether the carts, in the order of `move_carts`, were seen in this state
+// before
+fn is_repeat(seen: &mut HashSet<Vec<Cart>>, carts: &mut [Cart]) -> bool {
+    carts.sort_unstable_by_key(|cart| cart.coord);
+    !seen.insert(carts.to_vec())
+}
+
+/// Location of the first crash
+pub fn simulate_till_crash(track: &Track, mut carts: Vec<Cart>) -> Outcome {
+    let mut seen = HashSet::new();
+    let mut tick = 0;
+    loop {
+        if is_repeat(&mut seen, &mut carts) {
+            return Outcome::Loops { tick };
+        }
+        tick += 1;
+        if let Some(&coord) = move_carts(track, &mut carts).first() {
+            return Outcome::Found(Location { coord, tick });
+        }
+    }
+}
+
+/// Remove the carts as soon as they crash and return the location of the
+/// last cart at the end of the tick in which it remains alone
+pub fn simulate_till_one_stand(track: &Track, mut carts: Vec<Cart>) -> Outcome {
+    let mut seen = HashSet::new();
+    let mut tick = 0;
+    loop {
+        match carts.len() {
+            0 => return Outcome::NoneLeft { tick },
+            1 => {
+                return Outcome::Found(Location {
+                    coord: carts[0].coord,
+                    tick,
+                })
+            }
+            _ => {}
+        }
+        if is_repeat(&mut seen, &mut carts) {
+            return Outcome::Loops { tick };
+        }
+        move_carts(track, &mut carts);
+        tick += 1;
+    }
+}
+
+/// Move every cart once, removing the carts that crash; returns where the
+/// crashes happened
+pub fn move_carts(track: &Track, carts: &mut Vec<Cart>) -> Vec<Point> {
+    // sorted first, the indices of the map are the ones of the loop below
+    carts.sort_unstable_by_key(|cart| cart.coord);
+    let mut coords_to_idx =
+        carts
+            .iter()
+            .enumerate()
+            .fold(HashMap::new(), |mut coords_to_idx, (idx, cart)| {
+                coords_to_idx.insert(cart.coord, idx);
+                coords_to_idx
+            });
+
+    let mut to_remove: Vec<usize> = Vec::with_capacity(carts.len());
+    let mut crashes = Vec::new();
+
+    for (idx, cart) in carts.iter_mut().enumerate() {
+        coords_to_idx.remove(&cart.coord);
+
+        move_cart(track, cart);
+        // detect crash
+        let crash_cart_idx = coords_to_idx.get(&cart.coord);
+        if let Some(&crash_idx) = crash_cart_idx {
+            to_remove.push(idx);
+            to_remove.push(crash_idx);
+            coords_to_idx.remove(&cart.coord);
+            crashes.push(cart.coord);
+        } else {
+            coords_to_idx.insert(cart.coord, idx);
+        }
+    }
+
+    to_remove.sort_unstable();
+    let mut offset = 0;
+    to_remove.iter().for_each(|&idx| {
+        carts.remove(idx - offset);
+        offset += 1;
+    });
+    crashes
+}
+
+// add the loop of a rectangle to the map, unless its corners land on the
+// track or its sides run along the track; returns the crossings made
+fn add_loop(map: &mut Grid<char>, min: Point, max: Point) -> Option<usize> {
+    let mut tiles = vec![
+        (min, '/'),
+        (Point::new(max.x, min.y), '\\'),
+        (Point::new(min.x, max.y), '\\'),
+        (max, '/'),
+    ];
+    for x in min.x + 1..max.x {
+        tiles.push((Point::new(x, min.y), '-'));
+        tiles.push((Point::new(x, max.y), '-'));
+    }
+    for y in min.y + 1..max.y {
+        tiles.push((Point::new(min.x, y), '|'));
+        tiles.push((Point::new(max.x, y), '|'));
+    }
+
+    let mut crossings = 0;
+    let mut next = map.clone();
+    for (p, tile) in tiles {
+        next[p] = match (map[p], tile) {
+            (' ', _) => tile,
+            ('|', '-') | ('-', '|') => {
+                crossings += 1;
+                '+'
+            }
+            _ => return None,
+        };
+    }
+    *map = next;
+    Some(crossings)
+}
+
+/// Random map of about `size` rows and columns: rectangular loops of track
+/// crossing each other and an odd number of carts, all but one crashing
+/// within a few thousand ticks
+pub fn generate_map(rng: &mut Rng, size: usize) -> String {
+    let side = size.clamp(8, 500) as i32;
+    loop {
+        let bounds = BoundingBox::with_size(Point::new(0, 0), side as usize, side as usize);
+        let mut map = Grid::new(bounds, ' ');
+        let mut loops = 0;
+        for _ in 0..side * 4 {
+            if loops == side / 8 + 2 {
+                break;
+            }
+            let min = Point::new(
+                rng.range(0..=side as i64 - 3) as i32,
+                rng.range(0..=side as i64 - 3) as i32,
+            );
+            let max = Point::new(
+                rng.range(min.x as i64 + 2..=(min.x + side / 2).min(side - 1) as i64) as i32,
+                rng.range(min.y as i64 + 2..=(min.y + side / 2).min(side - 1) as i64) as i32,
+            );
+            // the loops after the first one cross the track
+            let mut next = map.clone();
+            match add_loop(&mut next, min, max) {
+                Some(crossings) if loops == 0 || crossings > 0 => {
+                    map = next;
+                    loops += 1;
+                }
+                _ => (),
+            }
+        }
+        if loops < 2 {
+            continue;
+        }
+
+        let straights: Vec<Point> = map
+            .iter()
+            .filter(|&(_, &tile)| tile == '-' || tile == '|')
+            .map(|(p, _)| p)
+            .collect();
+        for _ in 0..10 {
+            let mut with_carts = map.clone();
+            let mut places = straights.clone();
+            rng.shuffle(&mut places);
+            let count = (loops as usize * 2 - 1).min(places.len() - (1 - places.len() % 2));
+            for &p in &places[..count] {
+                let carts = if with_carts[p] == '-' {
+                    ['<', '>']
+                } else {
+                    ['^', 'v']
+                };
+                with_carts[p] = *rng.pick(&carts);
+            }
+
+            let input: String = with_carts
+                .rows()
+                .map(|row| row.iter().collect::<String>().trim_end().to_string() + "\n")
+                .collect();
+            // the carts must end up alone, within the number of ticks of the
+            // puzzle inputs
+            let (track, mut carts) = parse_track(&input).expect("Generated track");
+            if (0..20_000).any(|_| {
+                move_carts(&track, &mut carts);
+                carts.len() == 1
+            }) {
+                return input;
+            }
+        }
+    }
+}
+
+const RAIL: Rgb = Rgb(170, 170, 170);
+const CART: Rgb = Rgb(220, 50, 50);
+const CRASH: Rgb = Rgb(250, 210, 40);
+
+/// Draw the track with a square of 3x3 pixels for every tile, scaled
+/// twice: the pieces of track are grey lines, the crashes yellow crosses
+/// and the carts red squares on top
+pub fn render_track(track: &Track, carts: &[Cart], crashes: &[Point]) -> Image {
+    let origin = track.bounds().min;
+    let mut image = Image::new(track.width() * 3, track.height() * 3, Rgb(20, 20, 30));
+    // the pixels of a tile in its square, from the top-left corner
+    let offsets = |piece: &TrackPiece| -> &'static [(usize, usize)] {
+        match piece {
+            TrackPiece::Straight(Axe::X) => &[(0, 1), (1, 1), (2, 1)],
+            TrackPiece::Straight(Axe::Y) => &[(1, 0), (1, 1), (1, 2)],
+            TrackPiece::Turn(Direction::Down) => &[(2, 0), (1, 1), (0, 2)],
+            TrackPiece::Turn(Direction::Up) => &[(0, 0), (1, 1), (2, 2)],
+            TrackPiece::Intersection => &[(1, 0), (0, 1), (1, 1), (2, 1), (1, 2)],
+        }
+    };
+    let corner = |coord: Point| {
+        let offset = coord - origin;
+        (offset.x as usize * 3, offset.y as usize * 3)
+    };
+
+    for (coord, piece) in track.iter() {
+        if let Some(piece) = piece {
+            let (x, y) = corner(coord);
+            for &(dx, dy) in offsets(piece) {
+                image.set(x + dx, y + dy, RAIL);
+            }
+        }
+    }
+    for &crash in crashes {
+        let (x, y) = corner(crash);
+        for &(dx, dy) in &[(0, 0), (2, 0), (1, 1), (0, 2), (2, 2)] {
+            image.set(x + dx, y + dy, CRASH);
+        }
+    }
+    for cart in carts {
+        let (x, y) = corner(cart.coord);
+        image.fill(x, y, 3, 3, CART);
+    }
+    image.scale(2)
+}
+
+/// The carts moving one tick at a time until at most one is left, the
+/// crashes stay on the track
+pub struct CartsSimulation {
+    track: Track,
+    carts: Vec<Cart>,
+    crashes: Vec<Point>,
+    /// crashes of the last tick
+    last_crashes: usize,
+}
+
+impl CartsSimulation {
+    pub fn new(track: Track, carts: Vec<Cart>) -> CartsSimulation {
+        CartsSimulation {
+            track,
+            carts,
+            crashes: Vec::new(),
+            last_crashes: 0,
+        }
+    }
+}
+
+impl Simulation for CartsSimulation {
+    fn step(&mut self) -> bool {
+        if self.carts.len() <= 1 {
+            return false;
+        }
+        let crashes = move_carts(&self.track, &mut self.carts);
+        self.last_crashes = crashes.len();
+        self.crashes.extend(crashes);
+        true
+    }
+
+    fn render(&self) -> Image {
+        render_track(&self.track, &self.carts, &self.crashes)
+    }
+
+    fn text(&self) -> Grid<(char, Rgb)> {
+        let mut text = self.track.map(|_, piece| match piece {
+            Some(piece) => (piece.tile(), RAIL),
+            None => (' ', RAIL),
+        });
+        for &crash in &self.crashes {
+            text[crash] = ('X', CRASH);
+        }
+        for cart in &self.carts {
+            text[cart.coord] = (cart.tile(), CART);
+        }
+        text
+    }
+
+    fn event(&self) -> Option<String> {
+        let crashes = &self.crashes[self.crashes.len() - self.last_crashes..];
+        if crashes.is_empty() {
+            return None;
+        }
+        let crashes: Vec<String> = crashes.iter().map(|crash| crash.to_string()).collect();
+        Some(format!("crash at {}", crashes.join(", ")))
+    }
+}
+
+/// Part 1 is the location of the first crash, part 2 the location of the
+/// last cart left
+#[derive(Default)]
+pub struct Day13;
+
+impl Solution for Day13 {
+    const DAY: u8 = 13;
+    const README: &'static str = include_str!("../README.md");
+
+    type Input = (Track, Vec<Cart>);
+    type Part1 = Outcome;
+    type Part2 = Outcome;
+
+    fn parse(&self, input: &str) -> Result<(Track, Vec<Cart>), ParseError> {
+        parse_map(input)
+    }
+
+    fn part1(&self, (track, carts): &(Track, Vec<Cart>)) -> Outcome {
+        simulate_till_crash(track, carts.clone())
+    }
+
+    fn part2(&self, (track, carts): &(Track, Vec<Cart>)) -> Outcome {
+        simulate_till_one_stand(track, carts.clone())
+    }
+
+    fn render(&self, (track, carts): &(Track, Vec<Cart>)) -> Option<Image> {
+        Some(render_track(track, carts, &[]))
+    }
+
+    fn simulate(&self, (track, carts): &(Track, Vec<Cart>)) -> Option<Box<dyn Simulation>> {
+        let simulation = CartsSimulation::new(track.clone(), carts.clone());
+        Some(Box::new(simulation))
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        generate_map(rng, size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        find_derailment, generate_map, move_cart, move_carts, parse_map, parse_track, render_track,
+        simulate_till_crash, simulate_till_one_stand, Axe, Cart, CartsSimulation, Day13, Direction,
+        Map, Outcome, Track, TrackPiece,
+    };
+    use common::{Point, Rgb, Rng, Simulation, Solution};
+
+    fn piece(track: &Track, x: i32, y: i32) -> Option<&TrackPiece> {
+        track.get(Point::new(x, y)).and_then(Option::as_ref)
+    }
+
+    #[test]
+    fn test_parse_track_pieces() {
+        let input = " - | \n / \\\n+";
+
+        let (track, _) = parse_track(input).unwrap();
+
+        assert_eq!(piece(&track, 1, 0), Some(&TrackPiece::Straight(Axe::X)));
+        assert_eq!(piece(&track, 3, 0), Some(&TrackPiece::Straight(Axe::Y)));
+        assert_eq!(
+            piece(&track, 1, 1),
+            Some(&TrackPiece::Turn(Direction::Down))
+        );
+        assert_eq!(piece(&track, 3, 1), Some(&TrackPiece::Turn(Direction::Up)));
+        assert_eq!(piece(&track, 0, 2), Some(&TrackPiece::Intersection));
+    }
+
+    #[test]
+    fn test_parse_track_cart() {
+        let input = "><v^";
+
+        let (track, carts) = parse_track(input).unwrap();
+
+        assert_eq!(piece(&track, 0, 0), Some(&TrackPiece::Straight(Axe::X)));
+        assert_eq!(piece(&track, 1, 0), Some(&TrackPiece::Straight(Axe::X)));
+        assert_eq!(piece(&track, 2, 0), Some(&TrackPiece::Straight(Axe::Y)));
+        assert_eq!(piece(&track, 3, 0), Some(&TrackPiece::Straight(Axe::Y)));
+
+        let cart = carts.first().expect("> cart");
+        assert_eq!(cart.coord, Point::new(0, 0));
+        assert_eq!(cart.axe, Axe::X);
+        assert_eq!(cart.direction, Direction::Up);
+
+        let cart = carts.get(1).expect("< cart");
+        assert_eq!(cart.coord, Point::new(1, 0));
+        assert_eq!(cart.axe, Axe::X);
+        assert_eq!(cart.direction, Direction::Down);
+
+        let cart = carts.get(2).expect("v cart");
+        assert_eq!(cart.coord, Point::new(2, 0));
+        assert_eq!(cart.axe, Axe::Y);
+        assert_eq!(cart.direction, Direction::Up);
+
+        let cart = carts.get(3).expect("v cart");
+        assert_eq!(cart.coord, Point::new(3, 0));
+        assert_eq!(cart.axe, Axe::Y);
+        assert_eq!(cart.direction, Direction::Down);
+    }
+
+    #[test]
+    fn test_parse_track_unknown_tile() {
+        let input = "/->-\\\n|   |\n\\-x-/";
+
+        let err = parse_track(input).unwrap_err();
+        assert_eq!((err.line(), err.column()), (Some(3), Some(3)));
+        assert_eq!(err.message(), "unknown tile");
+    }
+
+    #[test]
+    fn test_parse_map_carts() {
+        let err = parse_map("/--\\\n\\--/").unwrap_err();
+        assert_eq!(err.line(), None);
+        assert_eq!(err.message(), "fewer than two carts on the track");
+
+        let err = parse_map("/>-\\\n\\--/").unwrap_err();
+        assert_eq!(err.message(), "fewer than two carts on the track");
+
+        // parsed, even though no cart is left standing in part 2
+        let map = parse_map("/><\\\n\\--/").unwrap();
+        assert_eq!(Day13.part2(&map), Outcome::NoneLeft { tick: 1 });
+    }
+
+    #[test]
+    fn test_parse_map_derailment() {
+        let err = parse_map("->-<").unwrap_err();
+        assert_eq!((err.line(), err.column()), (Some(1), Some(2)));
+        assert_eq!(err.message(), "cart runs off the track at (4, 0)");
+
+        // across a straight piece of the other axe
+        let err = parse_map("/>|<\\\n|   |\n\\---/").unwrap_err();
+        assert_eq!((err.line(), err.column()), (Some(1), Some(2)));
+        assert_eq!(err.message(), "cart runs off the track at (2, 0)");
+
+        // the left turn at the intersection leads nowhere
+        let (track, carts) = parse_track("/->-+-<\\\n|   |  |\n\\---+--/").unwrap();
+        assert_eq!(find_derailment(&track, &carts[0]), Some(Point::new(4, -1)));
+
+        let (track, carts) = parse_track("/>-<\\\n|   |\n\\---/").unwrap();
+        assert_eq!(find_derailment(&track, &carts[0]), None);
+    }
+
+    #[test]
+    fn test_move_cart_straight() {
+        let input = ">-";
+        let (track, mut carts) = parse_track(input).unwrap();
+        let cart = &mut carts[0];
+        move_cart(&track, cart);
+        assert_eq!(cart.coord, Point::new(1, 0));
+        assert_eq!(cart.axe, Axe::X);
+        assert_eq!(cart.direction, Direction::Up);
+
+        let input = "-<";
+        let (track, mut carts) = parse_track(input).unwrap();
+        let cart = &mut carts[0];
+        move_cart(&track, cart);
+        assert_eq!(cart.coord, Point::new(0, 0));
+        assert_eq!(cart.axe, Axe::X);
+        assert_eq!(cart.direction, Direction::Down);
+
+        let input = "v\n|";
+        let (track, mut carts) = parse_track(input).unwrap();
+        let cart = &mut carts[0];
+        move_cart(&track, cart);
+        assert_eq!(cart.coord, Point::new(0, 1));
+        assert_eq!(cart.axe, Axe::Y);
+        assert_eq!(cart.direction, Direction::Up);
+
+        let input = "|\n^";
+        let (track, mut carts) = parse_track(input).unwrap();
+        let cart = &mut carts[0];
+        move_cart(&track, cart);
+        assert_eq!(cart.coord, Point::new(0, 0));
+        assert_eq!(cart.axe, Axe::Y);
+        assert_eq!(cart.direction, Direction::Down);
+    }
+
+    #[test]
+    fn test_move_cart_turn() {
+        let input = ">/";
+        let (track, mut carts) = parse_track(input).unwrap();
+        let cart = &mut carts[0];
+        move_cart(&track, cart);
+        assert_eq!(cart.coord, Point::new(1, 0));
+        assert_eq!(cart.axe, Axe::Y);
+        assert_eq!(cart.direction, Direction::Down);
+
+        let input = "/<";
+        let (track, mut carts) = parse_track(input).unwrap();
+        let cart = &mut carts[0];
+        move_cart(&track, cart);
+        assert_eq!(cart.coord, Point::new(0, 0));
+        assert_eq!(cart.axe, Axe::Y);
+        assert_eq!(cart.direction, Direction::Up);
+
+        let input = ">\\";
+        let (track, mut carts) = parse_track(input).unwrap();
+        let cart = &mut carts[0];
+        move_cart(&track, cart);
+        assert_eq!(cart.coord, Point::new(1, 0));
+        assert_eq!(cart.axe, Axe::Y);
+        assert_eq!(cart.direction, Direction::Up);
+
+        let input = "\\<";
+        let (track, mut carts) = parse_track(input).unwrap();
+        let cart = &mut carts[0];
+        move_cart(&track, cart);
+        assert_eq!(cart.coord, Point::new(0, 0));
+        assert_eq!(cart.axe, Axe::Y);
+        assert_eq!(cart.direction, Direction::Down);
+
+        let input = "v\n/";
+        let (track, mut carts) = parse_track(input).unwrap();
+        let cart = &mut carts[0];
+        move_cart(&track, cart);
+        assert_eq!(cart.coord, Point::new(0, 1));
+        assert_eq!(cart.axe, Axe::X);
+        assert_eq!(cart.direction, Direction::Down);
+
+        let input = "/\n^";
+        let (track, mut carts) = parse_track(input).unwrap();
+        let cart = &mut carts[0];
+        move_cart(&track, cart);
+        assert_eq!(cart.coord, Point::new(0, 0));
+        assert_eq!(cart.axe, Axe::X);
+        assert_eq!(cart.direction, Direction::Up);
+
+        let input = "v\n\\";
+        let (track, mut carts) = parse_track(input).unwrap();
+        let cart = &mut carts[0];
+        move_cart(&track, cart);
+        assert_eq!(cart.coord, Point::new(0, 1));
+        assert_eq!(cart.axe, Axe::X);
+        assert_eq!(cart.direction, Direction::Up);
+
+        let input = "\\\n^";
+        let (track, mut carts) = parse_track(input).unwrap();
+        let cart = &mut carts[0];
+        move_cart(&track, cart);
+        assert_eq!(cart.coord, Point::new(0, 0));
+        assert_eq!(cart.axe, Axe::X);
+        assert_eq!(cart.direction, Direction::Down);
+    }
+
+    #[test]
+    fn test_move_cart_intersection() {
+        let input = ">+";
+        let (track, mut carts) = parse_track(input).unwrap();
+        let cart = &mut carts[0];
+
+        move_cart(&track, cart);
+        assert_eq!(cart.coord, Point::new(1, 0));
+        assert_eq!(cart.axe, Axe::Y);
+        assert_eq!(cart.direction, Direction::Down);
+
+        let input = "+<";
+        let (track, mut carts) = parse_track(input).unwrap();
+        let cart = &mut carts[0];
+
+        move_cart(&track, cart);
+        assert_eq!(cart.coord, Point::new(0, 0));
+        assert_eq!(cart.axe, Axe::Y);
+        assert_eq!(cart.direction, Direction::Up);
+
+        let input = "v\n+";
+        let (track, mut carts) = parse_track(input).unwrap();
+        let cart = &mut carts[0];
+
+        move_cart(&track, cart);
+        assert_eq!(cart.coord, Point::new(0, 1));
+        assert_eq!(cart.axe, Axe::X);
+        assert_eq!(cart.direction, Direction::Up);
+
+        let input = "+\n^";
+        let (track, mut carts) = parse_track(input).unwrap();
+        let cart = &mut carts[0];
+
+        move_cart(&track, cart);
+        assert_eq!(cart.coord, Point::new(0, 0));
+        assert_eq!(cart.axe, Axe::X);
+        assert_eq!(cart.direction, Direction::Down);
+
+        let input = "v\n+++\n--+";
+        let (track, mut carts) = parse_track(input).unwrap();
+        let cart = &mut carts[0];
+
+        move_cart(&track, cart);
+        assert_eq!(cart.coord, Point::new(0, 1));
+        assert_eq!(cart.axe, Axe::X);
+        assert_eq!(cart.direction, Direction::Up);
+
+        move_cart(&track, cart);
+        assert_eq!(cart.coord, Point::new(1, 1));
+        assert_eq!(cart.axe, Axe::X);
+        assert_eq!(cart.direction, Direction::Up);
+
+        move_cart(&track, cart);
+        assert_eq!(cart.coord, Point::new(2, 1));
+        assert_eq!(cart.axe, Axe::Y);
+        assert_eq!(cart.direction, Direction::Up);
+
+        move_cart(&track, cart);
+        assert_eq!(cart.coord, Point::new(2, 2));
+        assert_eq!(cart.axe, Axe::X);
+        assert_eq!(cart.direction, Direction::Up);
+    }
+
+    #[test]
+    fn test_simulate_example_straight() {
+        let input = "->---<-";
+        let (track, carts) = parse_track(input).unwrap();
+        let crash = simulate_till_crash(&track, carts).location().unwrap();
+        assert_eq!((crash.coord, crash.tick), (Point::new(3, 0), 2));
+
+        let input = "|\nv\n|\n|\n|\n^\n|";
+        let (track, carts) = parse_track(input).unwrap();
+        let crash = simulate_till_crash(&track, carts).location().unwrap();
+        assert_eq!((crash.coord, crash.tick), (Point::new(0, 3), 2));
+    }
+
+    #[test]
+    fn test_simulate_example_complext() {
+        let input = "/->-\\        \n\
+                     |   |  /----\\\n\
+                     | /-+--+-\\  |\n\
+                     | | |  | v  |\n\
+                     \\-+-/  \\-+--/\n\
+                     \\------/   ";
+        let (track, carts) = parse_track(input).unwrap();
+        let crash = simulate_till_crash(&track, carts).location().unwrap();
+        assert_eq!((crash.coord, crash.tick), (Point::new(7, 3), 14));
+    }
+
+    #[test]
+    fn test_simulate_till_one_stand_example() {
+        let input = "/>-<\\\n|   |\n| /<+-\\\n| | | v\n\\>+</ |\n  |   ^\n  \\<->/";
+        let (track, carts) = parse_track(input).unwrap();
+        let last = simulate_till_one_stand(&track, carts).location().unwrap();
+        assert_eq!((last.coord, last.tick), (Point::new(6, 4), 3));
+    }
+
+    #[test]
+    fn test_simulate_without_end() {
+        // the two carts crash at once, none is left
+        let (track, carts) = parse_map("/><\\\n\\--/").unwrap();
+        let crash = simulate_till_crash(&track, carts.clone());
+        assert_eq!(
+            crash.location().map(|crash| crash.coord),
+            Some(Point::new(2, 0))
+        );
+        let last = simulate_till_one_stand(&track, carts);
+        assert_eq!(last, Outcome::NoneLeft { tick: 1 });
+        assert_eq!(last.to_string(), "none, every cart crashed");
+
+        // the carts follow each other round the loop
+        let (track, carts) = parse_map("/>->\\\n|   |\n\\---/").unwrap();
+        let crash = simulate_till_crash(&track, carts.clone());
+        assert_eq!(crash, Outcome::Loops { tick: 12 });
+        assert_eq!(crash.to_string(), "none, the carts go round forever");
+        assert_eq!(
+            simulate_till_one_stand(&track, carts),
+            Outcome::Loops { tick: 12 }
+        );
+    }
+
+    #[test]
+    fn test_solution() {
+        let input = "/>-<\\\n|   |\n| /<+-\\\n| | | v\n\\>+</ |\n  |   ^\n  \\<->/";
+        let track = Day13.parse(input).unwrap();
+
+        assert_eq!(Day13.part1(&track).to_string(), "(2, 0)");
+        assert_eq!(Day13.part2(&track).to_string(), "(6, 4)");
+    }
+
+    #[test]
+    fn test_render_track() {
+        let (track, carts) = parse_track("/>\\\n+-/").unwrap();
+        let image = render_track(&track, &carts, &[]);
+        assert_eq!((image.width(), image.height()), (18, 12));
+
+        let rail = Some(Rgb(170, 170, 170));
+        // the middle of the pieces, the cart and the empty corner of a turn
+        assert_eq!(image.get(2, 2), rail);
+        assert_eq!(image.get(14, 8), rail);
+        assert_eq!(image.get(2, 8), rail);
+        assert_eq!(image.get(6, 0), Some(Rgb(220, 50, 50)));
+        assert_eq!(image.get(0, 0), Some(Rgb(20, 20, 30)));
+        assert_eq!(image.get(4, 0), rail);
+    }
+
+    #[test]
+    fn test_simulation() {
+        let input = "/>-<\\\n|   |\n| /<+-\\\n| | | v\n\\>+</ |\n  |   ^\n  \\<->/";
+        let (track, carts) = parse_track(input).unwrap();
+
+        let mut first_tick = carts.clone();
+        let crashes = move_carts(&track, &mut first_tick);
+        assert_eq!(
+            crashes,
+            vec![Point::new(2, 0), Point::new(2, 4), Point::new(6, 4)]
+        );
+        assert_eq!(first_tick.len(), 3);
+
+        let mut simulation = CartsSimulation::new(track, carts);
+        assert!(simulation.step());
+        assert_eq!(
+            simulation.event(),
+            Some("crash at (2, 0), (2, 4), (6, 4)".to_string())
+        );
+        let text = simulation.text();
+        let first_row: String = text.rows().next().unwrap().iter().map(|c| c.0).collect();
+        assert_eq!(first_row, "/-X-\\  ");
+        assert!(simulation.step());
+        assert_eq!(simulation.event(), None);
+        assert!(simulation.step());
+        assert!(!simulation.step());
+
+        // the crash of the first tick at (2, 0) and the last cart at (6, 4), on
+        // another crash
+        let image = simulation.render();
+        assert_eq!(image.get(2 * 6, 0), Some(Rgb(250, 210, 40)));
+        assert_eq!(image.get(6 * 6, 4 * 6), Some(Rgb(220, 50, 50)));
+    }
+
+    #[test]
+    fn test_generate() {
+        for seed in 0..10 {
+            let mut rng = Rng::new(seed);
+            let map = generate_map(&mut rng, 40);
+            assert_eq!(map.lines().count(), 40);
+            let (track, carts) = Day13.parse(&map).unwrap();
+            assert!(carts.len() >= 3 && carts.len() % 2 == 1);
+            assert!(track.values().contains(&Some(TrackPiece::Intersection)));
+
+            let first = Day13.part1(&(track.clone(), carts.clone())).location();
+            let last = Day13.part2(&(track, carts)).location();
+            assert!(first.unwrap().tick <= last.unwrap().tick);
+        }
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let mut rng = Rng::new(0);
+        for _ in 0..500 {
+            // random tiles, the carts on straight pieces as on the maps
+            let (width, height) = (rng.range(1..=12), rng.range(1..=12));
+            let mut input = String::new();
+            for _ in 0..height {
+                for _ in 0..width {
+                    input
+                        .push(*rng.pick(&[' ', ' ', '-', '|', '/', '\\', '+', '>', '<', 'v', '^']));
+                }
+                input.push('\n');
+            }
+            let (track, carts) = match parse_track(&input) {
+                Ok(parsed) => parsed,
+                Err(_) => continue,
+            };
+            let map = Map {
+                track: &track,
+                carts: &carts,
+            };
+            assert_eq!(
+                parse_track(&map.to_string()),
+                Ok((track.clone(), carts.clone()))
+            );
+        }
+
+        let input =
+            "/->-\\\n|   |  /----\\\n| /-+--+-\\  |\n| | |  | v  |\n\\-+-/  \\-+--/\n  \\------/\n";
+        let (track, carts) = parse_track(input).unwrap();
+        let map = Map {
+            track: &track,
+            carts: &carts,
+        };
+        assert_eq!(map.to_string(), input);
+        assert_eq!(
+            Cart::new(Point::new(0, 0), Axe::Y, Direction::Down).to_string(),
+            "^"
+        );
+        assert_eq!(TrackPiece::Turn(Direction::Up).to_string(), "\\");
+    }
+}
@@ -0,0 +1,254 @@
+//! Day 14: Chocolate Charts
+
+use common::{single_line, ParseError, Rng, Scanner, Solution};
+use std::collections::LinkedList;
+
+/// Decimal digits of the number, most significant first
+pub fn get_digits(mut n: usize) -> Vec<u8> {
+    let mut digits = Vec::with_capacity(2);
+    let mut push = |n| {
+        digits.push((n % 10) as u8);
+    };
+
+    if n == 0 {
+        push(n);
+    }
+
+    while n != 0 {
+        push(n);
+        n /= 10;
+    }
+
+    digits.into_iter().rev().collect()
+}
+
+/// Scores of the recipes with the current recipes of the two elves
+pub struct Scoreboard {
+    scores: Vec<u8>,
+    a_idx: usize,
+    b_idx: usize,
+}
+
+/// Iterator over all the scores, creating new recipes when needed
+pub struct ScoreboardIter<'a> {
+    scoreboard: &'a mut Scoreboard,
+    iter_pos: usize,
+}
+
+impl Scoreboard {
+    /// Scoreboard with the first two recipes, 3 and 7
+    pub fn new() -> Scoreboard {
+        Scoreboard {
+            scores: vec![3, 7],
+            a_idx: 0,
+            b_idx: 1,
+        }
+    }
+
+    /// Number of recipes created so far
+    pub fn len(&self) -> usize {
+        self.scores.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scores.is_empty()
+    }
+
+    /// Iterate the scores from the first recipe, the scoreboard grows as
+    /// needed
+    pub fn iter<'a>(&'a mut self) -> ScoreboardIter<'a> {
+        ScoreboardIter {
+            scoreboard: self,
+            iter_pos: 0,
+        }
+    }
+}
+
+impl Default for Scoreboard {
+    fn default() -> Scoreboard {
+        Scoreboard::new()
+    }
+}
+
+impl<'a> Iterator for ScoreboardIter<'a> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        let a_idx = self.scoreboard.a_idx;
+        let b_idx = self.scoreboard.b_idx;
+
+        if self.iter_pos == self.scoreboard.len() {
+            let a_score = self.scoreboard.scores[a_idx];
+            let b_score = self.scoreboard.scores[b_idx];
+            let new_score = a_score + b_score;
+            let mut digits = get_digits(new_score as usize);
+            self.scoreboard.scores.append(&mut digits);
+
+            let len = self.scoreboard.len();
+            self.scoreboard.a_idx = (a_idx + a_score as usize + 1) % len;
+            self.scoreboard.b_idx = (b_idx + b_score as usize + 1) % len;
+        }
+
+        let digit = self.scoreboard.scores[self.iter_pos];
+        self.iter_pos += 1;
+
+        Some(digit)
+    }
+}
+
+/// Scores of the ten recipes after the first `limit` ones
+pub fn next_10_scores(limit: usize) -> Vec<u8> {
+    Scoreboard::new().iter().skip(limit).take(10).collect()
+}
+
+/// Number of recipes before the scores of the target appear
+pub fn find_in_scoreboard(target: &[u8]) -> usize {
+    let mut scoreboard = Scoreboard::new();
+    let mut window: LinkedList<u8> = scoreboard.iter().take(target.len()).collect();
+
+    let mut it = scoreboard.iter();
+    loop {
+        if window.iter().enumerate().all(|(idx, &v)| target[idx] == v) {
+            return scoreboard.len() - target.len();
+        }
+
+        // the iterator will always return a score
+        let digit = it.next().unwrap();
+        window.pop_front();
+        window.push_back(digit);
+    }
+}
+
+/// Part 1 is the scores of the ten recipes after the input, part 2 the
+/// number of recipes before the input digits appear; the digits are kept
+/// as written as leading zeros matter to part 2
+#[derive(Default)]
+pub struct Day14;
+
+impl Solution for Day14 {
+    const DAY: u8 = 14;
+    const README: &'static str = include_str!("../README.md");
+
+    type Input = (usize, Vec<u8>);
+    type Part1 = String;
+    type Part2 = usize;
+
+    fn parse(&self, input: &str) -> Result<(usize, Vec<u8>), ParseError> {
+        let line = single_line(input)?;
+        let parse = || -> Result<(usize, Vec<u8>), ParseError> {
+            let mut s = Scanner::new(line);
+            let digits = s.take_while(|c| c.is_ascii_digit());
+            let recipes = Scanner::new(digits).number()?;
+            s.end()?;
+            Ok((recipes, digits.bytes().map(|d| d - b'0').collect()))
+        };
+        parse().map_err(|err| err.on_line(0, line))
+    }
+
+    fn part1(&self, &(limit, _): &(usize, Vec<u8>)) -> String {
+        next_10_scores(limit)
+            .iter()
+            .map(|score| score.to_string())
+            .collect()
+    }
+
+    fn part2(&self, (_, digits): &(usize, Vec<u8>)) -> usize {
+        find_in_scoreboard(digits)
+    }
+
+    /// A number of 6 digits like the puzzle inputs, whatever the size
+    fn generate(&self, rng: &mut Rng, _size: usize) -> String {
+        format!("{}\n", rng.range(100_000..=999_999))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_in_scoreboard, get_digits, next_10_scores, Day14, Scoreboard};
+    use common::{Rng, Solution};
+
+    #[test]
+    fn test_get_digits() {
+        (0..10).for_each(|n| {
+            let digits = get_digits(n);
+            assert_eq!(digits.len(), 1);
+            assert_eq!(get_digits(n)[0], n as u8);
+        });
+
+        (1..10).for_each(|d| {
+            (0..10).for_each(|n| {
+                let digits = get_digits(d * 10 + n);
+                assert_eq!(digits.len(), 2);
+                assert_eq!(digits[0], d as u8);
+                assert_eq!(digits[1], n as u8);
+            });
+        });
+    }
+
+    #[test]
+    fn test_compute_scoreboard() {
+        let scoreboard = |n| Scoreboard::new().iter().take(n).collect::<Vec<_>>();
+
+        assert_eq!(scoreboard(0), vec![]);
+        assert_eq!(scoreboard(2), vec![3, 7]);
+        assert_eq!(scoreboard(5), vec![3, 7, 1, 0, 1]);
+        assert_eq!(scoreboard(6), vec![3, 7, 1, 0, 1, 0]);
+        assert_eq!(scoreboard(10), vec![3, 7, 1, 0, 1, 0, 1, 2, 4, 5]);
+        assert_eq!(
+            scoreboard(14),
+            vec![3, 7, 1, 0, 1, 0, 1, 2, 4, 5, 1, 5, 8, 9]
+        );
+        assert_eq!(
+            scoreboard(19),
+            vec![3, 7, 1, 0, 1, 0, 1, 2, 4, 5, 1, 5, 8, 9, 1, 6, 7, 7, 9]
+        );
+    }
+
+    #[test]
+    fn test_next_10_scores() {
+        let scoreboard = next_10_scores(9);
+        assert_eq!(scoreboard, vec![5, 1, 5, 8, 9, 1, 6, 7, 7, 9]);
+
+        let scoreboard = next_10_scores(5);
+        assert_eq!(scoreboard, vec![0, 1, 2, 4, 5, 1, 5, 8, 9, 1]);
+
+        let scoreboard = next_10_scores(18);
+        assert_eq!(scoreboard, vec![9, 2, 5, 1, 0, 7, 1, 0, 8, 5]);
+
+        let scoreboard = next_10_scores(2018);
+        assert_eq!(scoreboard, vec![5, 9, 4, 1, 4, 2, 9, 8, 8, 2]);
+    }
+
+    #[test]
+    fn test_find_in_scoreboard() {
+        assert_eq!(find_in_scoreboard(&[5, 1, 5, 8, 9]), 9);
+        assert_eq!(find_in_scoreboard(&[0, 1, 2, 4, 5]), 5);
+        assert_eq!(find_in_scoreboard(&[9, 2, 5, 1, 0]), 18);
+        assert_eq!(find_in_scoreboard(&[5, 9, 4, 1, 4]), 2018);
+    }
+
+    #[test]
+    fn test_solution() {
+        let limit = Day14.parse("2018\n").unwrap();
+
+        assert_eq!(Day14.part1(&limit), "5941429882");
+
+        let err = Day14.parse("-2018\n").unwrap_err();
+        assert_eq!(err.column(), Some(1));
+        assert!(Day14.parse("\n").is_err());
+
+        let digits = Day14.parse("01245\n").unwrap();
+        assert_eq!(Day14.part2(&digits), 5);
+    }
+
+    #[test]
+    fn test_generate() {
+        for seed in 0..10 {
+            let input = Day14.generate(&mut Rng::new(seed), 100);
+            let (recipes, digits) = Day14.parse(&input).unwrap();
+            assert_eq!(digits.len(), 6);
+            assert!(recipes >= 100_000);
+            assert_eq!(Day14.part1(&(recipes, digits)).len(), 10);
+        }
+    }
+}
@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// the parser must return an input or an error, whatever the bytes
+fuzz_target!(|input: &str| {
+    let _ = common::parse(&day05::Day05::default(), input);
+});